@@ -0,0 +1,220 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::FuncCall;
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{
+    closing_parenthesis, depth_at, location_to_offset, name_token_at, non_trivia_tokens,
+    parse_pg_query_stmt, split_list, tokens_range,
+};
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// The built-in aggregate functions of Postgres that are commonly used
+const AGGREGATES: &[&str] = &[
+    "array_agg",
+    "avg",
+    "bit_and",
+    "bit_or",
+    "bool_and",
+    "bool_or",
+    "count",
+    "every",
+    "json_agg",
+    "json_object_agg",
+    "jsonb_agg",
+    "jsonb_object_agg",
+    "max",
+    "min",
+    "mode",
+    "percentile_cont",
+    "percentile_disc",
+    "stddev",
+    "stddev_pop",
+    "stddev_samp",
+    "string_agg",
+    "sum",
+    "var_pop",
+    "var_samp",
+    "variance",
+    "xmlagg",
+];
+
+/// A call of an aggregate function, e.g. `count(distinct x) filter (where y)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AggregateCall {
+    /// The possibly qualified name of the function, e.g. `count`
+    pub name: String,
+    /// The range from the name to the closing parenthesis of the call, or of its `FILTER` or
+    /// `WITHIN GROUP` clause
+    pub range: TextRange,
+    /// The ranges of the arguments, which a `count(*)` does not have
+    pub arguments: Vec<TextRange>,
+    /// Whether the call aggregates all rows, i.e. `count(*)`
+    pub star: bool,
+    pub distinct: bool,
+    /// The ranges of the items of the `ORDER BY` within the call or its `WITHIN GROUP` clause
+    pub order_by: Vec<TextRange>,
+    /// The range of the condition of the `FILTER (WHERE ...)` clause
+    pub filter: Option<TextRange>,
+}
+
+/// Returns all calls of aggregate functions in `stmt`, ordered by their position
+///
+/// pg_query.rs does not know which functions are aggregates, so a call is an aggregate if it uses
+/// the syntax of one, e.g. `DISTINCT` or `FILTER`, or if it calls a built-in aggregate function.
+pub fn aggregates(stmt: &SyntaxNode) -> Vec<AggregateCall> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+    let tokens = non_trivia_tokens(stmt);
+
+    let mut calls = get_nodes(ast.to_ref())
+        .into_iter()
+        .filter_map(|(node, _)| match node {
+            NodeRef::FuncCall(call) if is_aggregate(call) => Some(call),
+            _ => None,
+        })
+        .filter_map(|call| aggregate_call(stmt, &tokens, call))
+        .collect::<Vec<_>>();
+    calls.sort_by_key(|c| c.range.start());
+    calls
+}
+
+fn is_aggregate(call: &FuncCall) -> bool {
+    call.agg_star
+        || call.agg_distinct
+        || call.agg_filter.is_some()
+        || !call.agg_order.is_empty()
+        || matches!(
+            call.funcname.last().and_then(|n| n.node.as_ref()),
+            Some(NodeEnum::String(name)) if AGGREGATES.contains(&name.str.as_str())
+        )
+}
+
+fn aggregate_call(
+    stmt: &SyntaxNode,
+    tokens: &[&SyntaxToken],
+    call: &FuncCall,
+) -> Option<AggregateCall> {
+    let start = location_to_offset(stmt, call.location)?;
+    let name = name_token_at(stmt, start)?;
+    let first = tokens
+        .iter()
+        .position(|t| t.text_range().start() == start)?;
+    let open = tokens.iter().position(|t| t == &name)? + 1;
+    let close = closing_parenthesis(tokens, open)?;
+    let mut end = close;
+
+    // the part within the parentheses, without a leading `DISTINCT`
+    let mut inner = &tokens[open + 1..close];
+    if inner.first().map(|t| t.kind()) == Some(SyntaxKind::Distinct) {
+        inner = &inner[1..];
+    }
+    let (arguments, mut order_by) = match inner
+        .iter()
+        .position(|t| t.kind() == SyntaxKind::Order)
+        .filter(|idx| depth_at(inner, *idx) == 0)
+    {
+        Some(idx) => (
+            &inner[..idx],
+            split_list(&inner[(idx + 2).min(inner.len())..]),
+        ),
+        None => (inner, Vec::new()),
+    };
+
+    // `WITHIN GROUP (ORDER BY ...)`
+    if call.agg_within_group && tokens.get(end + 1).map(|t| t.kind()) == Some(SyntaxKind::Within) {
+        let open = end + 3;
+        let close = closing_parenthesis(tokens, open)?;
+        order_by = split_list(&tokens[(open + 3).min(close)..close]);
+        end = close;
+    }
+
+    // `FILTER (WHERE ...)`
+    let mut filter = None;
+    if tokens.get(end + 1).map(|t| t.kind()) == Some(SyntaxKind::Filter) {
+        let open = end + 2;
+        let close = closing_parenthesis(tokens, open)?;
+        filter = tokens_range(&tokens[(open + 2).min(close)..close]);
+        end = close;
+    }
+
+    Some(AggregateCall {
+        name: call
+            .funcname
+            .iter()
+            .filter_map(|n| match n.node.as_ref() {
+                Some(NodeEnum::String(s)) => Some(s.str.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("."),
+        range: tokens[first].text_range().cover(tokens[end].text_range()),
+        arguments: if call.agg_star {
+            Vec::new()
+        } else {
+            split_list(arguments)
+        },
+        star: call.agg_star,
+        distinct: call.agg_distinct,
+        order_by,
+        filter,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn aggregates_of(input: &str) -> Vec<AggregateCall> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        aggregates(&parser.finish().cst)
+    }
+
+    #[test]
+    fn test_count_distinct() {
+        let input = "select count(distinct x), count(*), lower(y) from t;";
+
+        let calls = aggregates_of(input);
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].name, "count");
+        assert_eq!(&input[calls[0].range], "count(distinct x)");
+        assert!(calls[0].distinct);
+        assert_eq!(
+            calls[0]
+                .arguments
+                .iter()
+                .map(|r| &input[*r])
+                .collect::<Vec<_>>(),
+            vec!["x"]
+        );
+        assert!(calls[1].star);
+        assert!(calls[1].arguments.is_empty());
+    }
+
+    #[test]
+    fn test_ordered_aggregates() {
+        let input = "select array_agg(x order by y) filter (where z), string_agg(x, ',' order by x desc, f(a, b)) from t;";
+
+        let calls = aggregates_of(input);
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(
+            &input[calls[0].range],
+            "array_agg(x order by y) filter (where z)"
+        );
+        assert_eq!(calls[0].order_by.len(), 1);
+        assert_eq!(&input[calls[0].order_by[0]], "y");
+        assert_eq!(calls[0].filter.map(|r| &input[r]), Some("z"));
+
+        let text = |ranges: &[TextRange]| ranges.iter().map(|r| &input[*r]).collect::<Vec<_>>();
+        assert_eq!(text(&calls[1].arguments), vec!["x", "','"]);
+        assert_eq!(text(&calls[1].order_by), vec!["x desc", "f(a, b)"]);
+        assert_eq!(calls[1].filter, None);
+    }
+}
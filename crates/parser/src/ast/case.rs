@@ -0,0 +1,136 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::Node;
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{non_trivia_tokens, parse_pg_query_stmt, syntax_node_for};
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// A `CASE` expression
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CaseExpr {
+    /// The range from `CASE` to `END`
+    pub range: TextRange,
+    /// The range of the compared expression of a simple `CASE x WHEN ...`, or `None` for a
+    /// searched `CASE WHEN cond ...`
+    pub arg: Option<TextRange>,
+    pub arms: Vec<CaseArm>,
+    /// The range of the `ELSE` result
+    pub default: Option<TextRange>,
+}
+
+/// A `WHEN ... THEN ...` arm of a `CASE` expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CaseArm {
+    /// The range of the condition, or of the compared value of a simple `CASE`
+    pub condition: TextRange,
+    pub result: TextRange,
+}
+
+/// Returns all `CASE` expressions of `stmt`, including nested ones, ordered by their position
+pub fn case_expressions(stmt: &SyntaxNode) -> Vec<CaseExpr> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+
+    let mut cases = get_nodes(ast.to_ref())
+        .into_iter()
+        .filter_map(|(node, _)| match node {
+            NodeRef::CaseExpr(case) => Some((syntax_node_for(stmt, &node)?, case)),
+            _ => None,
+        })
+        .map(|(node, case)| CaseExpr {
+            range: node.text_range(),
+            arg: case.arg.as_deref().and_then(|n| expression_range(stmt, n)),
+            arms: case
+                .args
+                .iter()
+                .filter_map(|n| match n.node.as_ref() {
+                    Some(NodeEnum::CaseWhen(when)) => Some(CaseArm {
+                        condition: expression_range(stmt, when.expr.as_deref()?)?,
+                        result: expression_range(stmt, when.result.as_deref()?)?,
+                    }),
+                    _ => None,
+                })
+                .collect(),
+            default: case
+                .defresult
+                .as_deref()
+                .and_then(|n| expression_range(stmt, n)),
+        })
+        .collect::<Vec<_>>();
+    cases.sort_by_key(|c| c.range.start());
+    cases
+}
+
+/// Returns the range of the cst node of the expression `expr` within a `CASE`
+///
+/// The keyword that follows an expression is attached to its node, e.g. the `THEN` after a
+/// condition, so trailing keywords of the `CASE` are excluded.
+fn expression_range(stmt: &SyntaxNode, expr: &Node) -> Option<TextRange> {
+    let node = syntax_node_for(stmt, &expr.node.as_ref()?.to_ref())?;
+    let tokens = non_trivia_tokens(node);
+    let end = tokens.iter().rposition(|t| {
+        !matches!(
+            t.kind(),
+            SyntaxKind::When | SyntaxKind::Then | SyntaxKind::Else | SyntaxKind::EndP
+        )
+    })?;
+    Some(tokens.first()?.text_range().cover(tokens[end].text_range()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn cases(input: &str) -> Vec<CaseExpr> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        case_expressions(&parser.finish().cst)
+    }
+
+    #[test]
+    fn test_simple_case() {
+        let input = "select case kind when 'a' then 1 when 'b' then 2 else 0 end from t;";
+
+        let cases = cases(input);
+
+        assert_eq!(cases.len(), 1);
+        assert_eq!(
+            &input[cases[0].range],
+            "case kind when 'a' then 1 when 'b' then 2 else 0 end"
+        );
+        assert_eq!(cases[0].arg.map(|r| &input[r]), Some("kind"));
+        assert_eq!(
+            cases[0]
+                .arms
+                .iter()
+                .map(|a| (&input[a.condition], &input[a.result]))
+                .collect::<Vec<_>>(),
+            vec![("'a'", "1"), ("'b'", "2")]
+        );
+        assert_eq!(cases[0].default.map(|r| &input[r]), Some("0"));
+    }
+
+    #[test]
+    fn test_searched_case() {
+        let input = "select case when x > 1 then 'big' end from t;";
+
+        let cases = cases(input);
+
+        assert_eq!(cases.len(), 1);
+        assert_eq!(cases[0].arg, None);
+        assert_eq!(
+            cases[0]
+                .arms
+                .iter()
+                .map(|a| (&input[a.condition], &input[a.result]))
+                .collect::<Vec<_>>(),
+            vec![("x > 1", "'big'")]
+        );
+        assert_eq!(cases[0].default, None);
+    }
+}
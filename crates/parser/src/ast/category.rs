@@ -0,0 +1,246 @@
+use pg_query::protobuf::{AlterTableType, ExplainStmt};
+use pg_query::NodeEnum;
+
+use crate::ast::parse_pg_query_stmt;
+use crate::syntax_kind::{syntax_kind_name, SyntaxKind};
+use crate::syntax_node::SyntaxNode;
+
+/// The category of a statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatementCategory {
+    /// Statements that only read, e.g. `SELECT` and `COPY ... TO`
+    Query,
+    /// Statements that modify rows, e.g. `INSERT`, `TRUNCATE` and `COPY ... FROM`
+    Dml,
+    /// Statements that define or change objects, e.g. `CREATE TABLE` and `DROP INDEX`
+    Ddl,
+    /// Statements that manage roles and privileges, e.g. `GRANT` and `CREATE ROLE`
+    Dcl,
+    /// Transaction control, e.g. `BEGIN` and `COMMIT`
+    Transaction,
+    /// All other statements, e.g. `VACUUM` and `SET`
+    Utility,
+}
+
+impl StatementCategory {
+    /// A short name of the category for messages, e.g. `DDL`
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatementCategory::Query => "query",
+            StatementCategory::Dml => "DML",
+            StatementCategory::Ddl => "DDL",
+            StatementCategory::Dcl => "DCL",
+            StatementCategory::Transaction => "transaction control",
+            StatementCategory::Utility => "utility",
+        }
+    }
+}
+
+/// Returns the category of the statement node `stmt`
+///
+/// Returns `None` if the statement contains syntax errors. `SELECT ... INTO` creates a table and
+/// is DDL, and a select with a data-modifying common table expression, e.g.
+/// `WITH d AS (DELETE ... RETURNING *) SELECT ...`, is DML. `EXPLAIN ANALYZE` runs its statement,
+/// so it has the category of that statement.
+pub fn statement_category(stmt: &SyntaxNode) -> Option<StatementCategory> {
+    Some(category(&parse_pg_query_stmt(stmt)?))
+}
+
+fn category(ast: &NodeEnum) -> StatementCategory {
+    match ast {
+        NodeEnum::SelectStmt(select) if select.into_clause.is_some() => StatementCategory::Ddl,
+        NodeEnum::SelectStmt(select) => {
+            let modifies = select
+                .with_clause
+                .iter()
+                .flat_map(|w| &w.ctes)
+                .filter_map(|cte| match cte.node.as_ref()? {
+                    NodeEnum::CommonTableExpr(cte) => cte.ctequery.as_ref()?.node.as_ref(),
+                    _ => None,
+                })
+                .any(|query| category(query) == StatementCategory::Dml);
+            if modifies {
+                StatementCategory::Dml
+            } else {
+                StatementCategory::Query
+            }
+        }
+        NodeEnum::ExplainStmt(explain) if is_analyze(explain) => {
+            match explain.query.as_ref().and_then(|q| q.node.as_ref()) {
+                Some(query) => category(query),
+                None => StatementCategory::Utility,
+            }
+        }
+        NodeEnum::CopyStmt(copy) if !copy.is_from => StatementCategory::Query,
+        NodeEnum::InsertStmt(_)
+        | NodeEnum::UpdateStmt(_)
+        | NodeEnum::DeleteStmt(_)
+        | NodeEnum::TruncateStmt(_)
+        | NodeEnum::CopyStmt(_) => StatementCategory::Dml,
+        NodeEnum::GrantStmt(_)
+        | NodeEnum::GrantRoleStmt(_)
+        | NodeEnum::AlterDefaultPrivilegesStmt(_)
+        | NodeEnum::CreateRoleStmt(_)
+        | NodeEnum::AlterRoleStmt(_)
+        | NodeEnum::AlterRoleSetStmt(_)
+        | NodeEnum::DropRoleStmt(_) => StatementCategory::Dcl,
+        NodeEnum::TransactionStmt(_) => StatementCategory::Transaction,
+        NodeEnum::IndexStmt(_)
+        | NodeEnum::ViewStmt(_)
+        | NodeEnum::DefineStmt(_)
+        | NodeEnum::RuleStmt(_)
+        | NodeEnum::RenameStmt(_)
+        | NodeEnum::CommentStmt(_) => StatementCategory::Ddl,
+        ast => {
+            // the remaining ddl statements are named after their keyword, e.g. `CreateSeqStmt`
+            let name = syntax_kind_name(SyntaxKind::from_pg_query_node(&ast.to_ref()));
+            if ["Create", "Alter", "Drop"]
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+            {
+                StatementCategory::Ddl
+            } else {
+                StatementCategory::Utility
+            }
+        }
+    }
+}
+
+/// Whether `explain` runs its statement, i.e. is `EXPLAIN ANALYZE` or `EXPLAIN (ANALYZE)`
+fn is_analyze(explain: &ExplainStmt) -> bool {
+    explain.options.iter().any(|option| match option.node.as_ref() {
+        Some(NodeEnum::DefElem(option)) if option.defname == "analyze" => {
+            // `ANALYZE false` and `ANALYZE off` turn it off again
+            !matches!(
+                option.arg.as_ref().and_then(|a| a.node.as_ref()),
+                Some(NodeEnum::String(value)) if ["false", "off", "0"].contains(&value.str.to_lowercase().as_str())
+            )
+        }
+        _ => false,
+    })
+}
+
+/// Returns whether the DDL statement `stmt` succeeds with the same result when it runs again,
+/// e.g. `CREATE TABLE IF NOT EXISTS` and `DROP TABLE IF EXISTS`
+///
+/// `CREATE OR REPLACE` and statements that only set a property, e.g. `COMMENT ON` or
+/// `ALTER TABLE ... SET NOT NULL`, are idempotent as well. An `ALTER TABLE` is idempotent if all
+/// of its commands are. Returns `None` if the statement is not DDL or contains syntax errors.
+pub fn is_idempotent_ddl(stmt: &SyntaxNode) -> Option<bool> {
+    let ast = parse_pg_query_stmt(stmt)?;
+    if category(&ast) != StatementCategory::Ddl {
+        return None;
+    }
+    let idempotent = match &ast {
+        NodeEnum::CreateStmt(n) => n.if_not_exists,
+        NodeEnum::CreateTableAsStmt(n) => n.if_not_exists,
+        NodeEnum::IndexStmt(n) => n.if_not_exists,
+        NodeEnum::CreateSeqStmt(n) => n.if_not_exists,
+        NodeEnum::CreateSchemaStmt(n) => n.if_not_exists,
+        NodeEnum::CreateExtensionStmt(n) => n.if_not_exists,
+        NodeEnum::CreateStatsStmt(n) => n.if_not_exists,
+        NodeEnum::DefineStmt(n) => n.if_not_exists || n.replace,
+        NodeEnum::DropStmt(n) => n.missing_ok,
+        NodeEnum::ViewStmt(n) => n.replace,
+        NodeEnum::CreateFunctionStmt(n) => n.replace,
+        NodeEnum::RuleStmt(n) => n.replace,
+        NodeEnum::CommentStmt(_) => true,
+        NodeEnum::AlterTableStmt(n) => n.cmds.iter().all(|cmd| match cmd.node.as_ref() {
+            Some(NodeEnum::AlterTableCmd(cmd)) => match AlterTableType::from_i32(cmd.subtype) {
+                Some(
+                    AlterTableType::AtAddColumn
+                    | AlterTableType::AtDropColumn
+                    | AlterTableType::AtDropConstraint,
+                ) => cmd.missing_ok,
+                Some(
+                    AlterTableType::AtColumnDefault
+                    | AlterTableType::AtDropNotNull
+                    | AlterTableType::AtSetNotNull
+                    | AlterTableType::AtAlterColumnType
+                    | AlterTableType::AtSetOptions
+                    | AlterTableType::AtResetOptions,
+                ) => true,
+                _ => false,
+            },
+            _ => false,
+        }),
+        _ => false,
+    };
+    Some(idempotent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn category(input: &str) -> Option<StatementCategory> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        statement_category(&parsed.cst)
+    }
+
+    #[test]
+    fn test_statement_category() {
+        assert_eq!(category("select 1;"), Some(StatementCategory::Query));
+        assert_eq!(
+            category("select * into t2 from t;"),
+            Some(StatementCategory::Ddl)
+        );
+        assert_eq!(category("delete from t;"), Some(StatementCategory::Dml));
+        assert_eq!(
+            category("with d as (delete from t returning *) select * from d;"),
+            Some(StatementCategory::Dml)
+        );
+        assert_eq!(
+            category("with x as (select 1) select * from x;"),
+            Some(StatementCategory::Query)
+        );
+        assert_eq!(
+            category("explain analyze delete from t;"),
+            Some(StatementCategory::Dml)
+        );
+        assert_eq!(
+            category("explain (analyze off) delete from t;"),
+            Some(StatementCategory::Utility)
+        );
+        assert_eq!(
+            category("explain delete from t;"),
+            Some(StatementCategory::Utility)
+        );
+        assert_eq!(category("create sequence s;"), Some(StatementCategory::Ddl));
+        assert_eq!(category("drop table t;"), Some(StatementCategory::Ddl));
+        assert_eq!(
+            category("grant select on t to r;"),
+            Some(StatementCategory::Dcl)
+        );
+        assert_eq!(category("commit;"), Some(StatementCategory::Transaction));
+        assert_eq!(category("vacuum t;"), Some(StatementCategory::Utility));
+        assert_eq!(category("select from;"), None);
+    }
+
+    #[test]
+    fn test_is_idempotent_ddl() {
+        let idempotent = |input: &str| {
+            let mut parser = Parser::new();
+            parser.parse_statement(input, None);
+            is_idempotent_ddl(&parser.finish().cst)
+        };
+
+        assert_eq!(
+            idempotent("create table if not exists t (id int);"),
+            Some(true)
+        );
+        assert_eq!(idempotent("create table t (id int);"), Some(false));
+        assert_eq!(idempotent("drop index if exists i;"), Some(true));
+        assert_eq!(
+            idempotent(
+                "alter table t add column if not exists a int, alter column b set not null;"
+            ),
+            Some(true)
+        );
+        assert_eq!(idempotent("alter table t add column a int;"), Some(false));
+        assert_eq!(idempotent("insert into t values (1);"), None);
+    }
+}
@@ -0,0 +1,140 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::Node;
+use pg_query::NodeRef;
+
+use crate::ast::{parse_pg_query_stmt, syntax_node_for};
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// The clause a node is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClauseContext {
+    SelectList,
+    FromClause,
+    WhereClause,
+    GroupBy,
+    Having,
+    OrderBy,
+    /// The node is not within any of the clauses above
+    None,
+}
+
+/// Returns the innermost clause that contains `node`
+///
+/// The cst does not have nodes for clauses. Instead, the statement that contains `node` is parsed
+/// with pg_query.rs, and the range of every clause of its selects, updates and deletes is
+/// determined from the cst nodes of the clause items. If clauses are nested, e.g. in a subquery,
+/// the innermost clause wins.
+pub fn clause_context(node: &SyntaxNode) -> ClauseContext {
+    let stmt = match node.ancestors().find(|n| {
+        n.parent()
+            .is_none_or(|p| p.kind() == SyntaxKind::SourceFile)
+    }) {
+        Some(stmt) => stmt,
+        None => return ClauseContext::None,
+    };
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return ClauseContext::None,
+    };
+
+    let mut clauses = Vec::new();
+    for (ast_node, _) in get_nodes(ast.to_ref()) {
+        match ast_node {
+            NodeRef::SelectStmt(s) => {
+                clauses.push((ClauseContext::SelectList, s.target_list.iter().collect()));
+                clauses.push((ClauseContext::FromClause, s.from_clause.iter().collect()));
+                clauses.push((
+                    ClauseContext::WhereClause,
+                    s.where_clause.as_deref().into_iter().collect(),
+                ));
+                clauses.push((ClauseContext::GroupBy, s.group_clause.iter().collect()));
+                clauses.push((
+                    ClauseContext::Having,
+                    s.having_clause.as_deref().into_iter().collect(),
+                ));
+                clauses.push((ClauseContext::OrderBy, s.sort_clause.iter().collect()));
+            }
+            NodeRef::UpdateStmt(s) => {
+                clauses.push((ClauseContext::FromClause, s.from_clause.iter().collect()));
+                clauses.push((
+                    ClauseContext::WhereClause,
+                    s.where_clause.as_deref().into_iter().collect(),
+                ));
+            }
+            NodeRef::DeleteStmt(s) => {
+                clauses.push((
+                    ClauseContext::WhereClause,
+                    s.where_clause.as_deref().into_iter().collect(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    let range = node.text_range();
+    clauses
+        .into_iter()
+        .filter_map(|(context, items): (ClauseContext, Vec<&Node>)| {
+            let extent = items
+                .into_iter()
+                .filter_map(|item| extent(stmt, item))
+                .reduce(|a, b| a.cover(b))?;
+            Some((context, extent))
+        })
+        .filter(|(_, extent)| extent.contains_range(range))
+        .min_by_key(|(_, extent)| extent.len())
+        .map_or(ClauseContext::None, |(context, _)| context)
+}
+
+/// Returns the range covered by the cst nodes of the ast node `node` and its descendants
+///
+/// Nodes without a location, e.g. joins, are covered by their descendants.
+fn extent(stmt: &SyntaxNode, node: &Node) -> Option<TextRange> {
+    get_nodes(node.node.as_ref()?.to_ref())
+        .iter()
+        .filter_map(|(n, _)| syntax_node_for(stmt, n))
+        .map(|n| n.text_range())
+        .reduce(|a, b| a.cover(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    /// Returns the context of the column reference that starts with `column`
+    fn context_of(input: &str, column: &str) -> ClauseContext {
+        let result = parse(input);
+        let node = result
+            .root()
+            .descendants()
+            .filter(|n| n.kind() == SyntaxKind::ColumnRef)
+            .find(|n| {
+                n.descendants_with_tokens()
+                    .filter_map(|e| e.into_token())
+                    .next()
+                    .is_some_and(|t| t.resolved().text() == column)
+            })
+            .unwrap();
+        clause_context(node)
+    }
+
+    #[test]
+    fn test_clause_context() {
+        let input = "select 1;\nselect a from t where b = 1 order by c;";
+
+        assert_eq!(context_of(input, "a"), ClauseContext::SelectList);
+        assert_eq!(context_of(input, "b"), ClauseContext::WhereClause);
+        assert_eq!(context_of(input, "c"), ClauseContext::OrderBy);
+    }
+
+    #[test]
+    fn test_innermost_clause_wins() {
+        let input = "select a from t where b in (select c from u);";
+
+        assert_eq!(context_of(input, "b"), ClauseContext::WhereClause);
+        assert_eq!(context_of(input, "c"), ClauseContext::SelectList);
+    }
+}
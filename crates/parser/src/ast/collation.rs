@@ -0,0 +1,89 @@
+use cstree::text::TextRange;
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{location_to_offset, name_token_at, parse_pg_query_stmt};
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the collation names of all `COLLATE` clauses in `stmt`, along with the range from the
+/// `COLLATE` keyword to the end of the name
+///
+/// This covers collations of expressions, e.g. in `ORDER BY`, and of column definitions. The
+/// name is qualified with its schema if specified, e.g. `pg_catalog.C`.
+pub fn collations(stmt: &SyntaxNode) -> Vec<(String, TextRange)> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+
+    let mut collations = get_nodes(ast.to_ref())
+        .into_iter()
+        .filter_map(|(node, _)| match node {
+            NodeRef::CollateClause(c) => Some(c),
+            _ => None,
+        })
+        .filter_map(|c| {
+            let name = c
+                .collname
+                .iter()
+                .filter_map(|n| match n.node.as_ref() {
+                    Some(NodeEnum::String(s)) => Some(s.str.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join(".");
+
+            // the location is the start of the collated expression for expressions, and the
+            // `COLLATE` keyword for column definitions
+            let offset = location_to_offset(stmt, c.location)?;
+            let mut tokens = stmt
+                .descendants_with_tokens()
+                .filter_map(|e| e.into_token())
+                .filter(|t| !t.kind().is_trivia() && t.text_range().start() >= offset)
+                .skip_while(|t| t.kind() != SyntaxKind::Collate);
+            let keyword = tokens.next()?;
+            let name_token = name_token_at(stmt, tokens.next()?.text_range().start())?;
+            Some((name, keyword.text_range().cover(name_token.text_range())))
+        })
+        .collect::<Vec<_>>();
+    collations.sort_by_key(|(_, range)| range.start());
+    collations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn collation_texts(input: &str) -> Vec<(String, String)> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        collations(&parsed.cst)
+            .into_iter()
+            .map(|(name, range)| (name, input[range].to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_order_by_collation() {
+        assert_eq!(
+            collation_texts("select name from users order by name collate \"C\", email;"),
+            vec![("C".to_string(), "collate \"C\"".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_column_collation() {
+        assert_eq!(
+            collation_texts(
+                "create table users (name text not null collate pg_catalog.\"de_DE\", email text);"
+            ),
+            vec![(
+                "pg_catalog.de_DE".to_string(),
+                "collate pg_catalog.\"de_DE\"".to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::Node;
+use pg_query::NodeEnum;
+
+use crate::ast::qualified_name;
+use crate::ast::{closing_parenthesis, non_trivia_tokens, parse_pg_query_stmt, tokens_range};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Whether a `COPY` reads into a table or writes out of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CopyDirection {
+    To,
+    From,
+}
+
+/// The file or stream that a `COPY` reads from or writes to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CopyTarget {
+    /// A file on the server, e.g. `'/tmp/users.csv'`
+    File(String),
+    /// A shell command whose input or output is used, e.g. `PROGRAM 'gzip > users.gz'`
+    Program(String),
+    Stdin,
+    Stdout,
+}
+
+/// The table or query of a `COPY`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CopySource {
+    /// A table, qualified with its schema if specified
+    Table(String),
+    /// The text of a query, e.g. `select * from users`
+    Query(String),
+}
+
+/// A `COPY` statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyStmt {
+    pub direction: CopyDirection,
+    pub target: CopyTarget,
+    pub source: CopySource,
+    /// The columns of the table, which are empty if all columns are copied
+    pub columns: Vec<String>,
+    /// The value of the `FORMAT` option, e.g. `csv`
+    pub format: Option<String>,
+    /// The value of the `DELIMITER` option
+    pub delimiter: Option<String>,
+    /// Whether the `HEADER` option is set
+    pub header: bool,
+    /// The range of the inline data that follows a `COPY ... FROM STDIN` in a source file
+    pub data: Option<TextRange>,
+}
+
+/// Returns the details of the `COPY` statement `stmt`
+///
+/// Both the option list of `WITH (...)` and the older syntax without parentheses are supported,
+/// e.g. `WITH CSV HEADER`.
+pub fn copy_statement(stmt: &SyntaxNode) -> Option<CopyStmt> {
+    let copy = match parse_pg_query_stmt(stmt)? {
+        NodeEnum::CopyStmt(copy) => copy,
+        _ => return None,
+    };
+
+    let source = match (copy.relation.as_ref(), copy.query.as_deref()) {
+        (Some(relation), _) => CopySource::Table(qualified_name(relation)),
+        // the query is the parenthesized text after `COPY`, whose node has no location
+        (None, Some(_)) => {
+            let tokens = non_trivia_tokens(stmt);
+            let close = closing_parenthesis(&tokens, 1)?;
+            let range = tokens_range(&tokens[2..close])? - stmt.text_range().start();
+            CopySource::Query(stmt.resolved().text().to_string()[range].to_string())
+        }
+        (None, None) => return None,
+    };
+    let target = if copy.is_program {
+        CopyTarget::Program(copy.filename.clone())
+    } else if !copy.filename.is_empty() {
+        CopyTarget::File(copy.filename.clone())
+    } else if copy.is_from {
+        CopyTarget::Stdin
+    } else {
+        CopyTarget::Stdout
+    };
+
+    let mut format = None;
+    let mut delimiter = None;
+    let mut header = false;
+    for option in &copy.options {
+        let option = match option.node.as_ref() {
+            Some(NodeEnum::DefElem(option)) => option,
+            _ => continue,
+        };
+        let value = option.arg.as_deref().and_then(option_value);
+        match option.defname.as_str() {
+            "format" => format = value,
+            "delimiter" => delimiter = value,
+            "header" => {
+                header = value.is_none_or(|v| {
+                    matches!(v.to_lowercase().as_str(), "1" | "true" | "on" | "match")
+                })
+            }
+            _ => {}
+        }
+    }
+
+    Some(CopyStmt {
+        direction: if copy.is_from {
+            CopyDirection::From
+        } else {
+            CopyDirection::To
+        },
+        data: match target {
+            CopyTarget::Stdin => inline_data(stmt),
+            _ => None,
+        },
+        target,
+        source,
+        columns: copy
+            .attlist
+            .iter()
+            .filter_map(|n| match n.node.as_ref() {
+                Some(NodeEnum::String(s)) => Some(s.str.clone()),
+                _ => None,
+            })
+            .collect(),
+        format,
+        delimiter,
+        header,
+    })
+}
+
+/// Returns the text of the argument `arg` of an option, e.g. `csv` or `1`
+fn option_value(arg: &Node) -> Option<String> {
+    match arg.node.as_ref()? {
+        NodeEnum::String(s) => Some(s.str.clone()),
+        NodeEnum::Integer(i) => Some(i.ival.to_string()),
+        NodeEnum::Float(f) => Some(f.str.clone()),
+        _ => None,
+    }
+}
+
+/// Returns the range of the `CopyData` token that follows `stmt`, skipping trivia
+fn inline_data(stmt: &SyntaxNode) -> Option<TextRange> {
+    let mut sibling = stmt.next_sibling_or_token();
+    while let Some(element) = sibling {
+        match element.kind() {
+            SyntaxKind::CopyData => return Some(element.text_range()),
+            kind if kind.is_trivia() => sibling = element.next_sibling_or_token(),
+            _ => return None,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::statements;
+    use crate::parser::parse;
+
+    fn copy(input: &str) -> Vec<Option<CopyStmt>> {
+        statements(parse(input).root())
+            .iter()
+            .map(|stmt| copy_statement(stmt))
+            .collect()
+    }
+
+    #[test]
+    fn test_copy_to_stdout() {
+        assert_eq!(
+            copy("copy t to stdout with csv header;"),
+            vec![Some(CopyStmt {
+                direction: CopyDirection::To,
+                target: CopyTarget::Stdout,
+                source: CopySource::Table("t".to_string()),
+                columns: Vec::new(),
+                format: Some("csv".to_string()),
+                delimiter: None,
+                header: true,
+                data: None,
+            })]
+        );
+        let stmts = copy("copy (select id from users) to program 'gzip > users.gz' with (format csv, header false);");
+        let stmt = stmts[0].as_ref().unwrap();
+        assert_eq!(
+            stmt.source,
+            CopySource::Query("select id from users".to_string())
+        );
+        assert_eq!(
+            stmt.target,
+            CopyTarget::Program("gzip > users.gz".to_string())
+        );
+        assert!(!stmt.header);
+    }
+
+    #[test]
+    fn test_copy_from_file() {
+        assert_eq!(
+            copy("copy public.t (a, b) from '/path' delimiter ',';"),
+            vec![Some(CopyStmt {
+                direction: CopyDirection::From,
+                target: CopyTarget::File("/path".to_string()),
+                source: CopySource::Table("public.t".to_string()),
+                columns: vec!["a".to_string(), "b".to_string()],
+                format: None,
+                delimiter: Some(",".to_string()),
+                header: false,
+                data: None,
+            })]
+        );
+    }
+
+    #[test]
+    fn test_copy_from_stdin() {
+        let input = "copy t from stdin;\n1\tfoo\n2\tbar;\n\\.\nselect 1;";
+        let parsed = parse(input);
+        assert!(parsed.diagnostics().is_empty());
+        let stmts = statements(parsed.root());
+        assert_eq!(stmts.len(), 2);
+
+        let copy = copy_statement(stmts[0]).unwrap();
+        assert_eq!(copy.target, CopyTarget::Stdin);
+        assert_eq!(&input[copy.data.unwrap()], "1\tfoo\n2\tbar;\n\\.");
+        assert_eq!(stmts[1].kind(), SyntaxKind::SelectStmt);
+    }
+}
@@ -0,0 +1,485 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::{self, AlterTableType, ConstrType, Node, RangeVar};
+use pg_query::NodeEnum;
+
+use crate::ast::{generated_columns, parse_pg_query_stmt, token_at, type_names, GeneratedKind};
+use crate::syntax_node::SyntaxNode;
+
+/// The kind of a `TableConstraint`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConstraintKind {
+    PrimaryKey,
+    Unique,
+    Check,
+    ForeignKey,
+    NotNull,
+}
+
+/// A constraint of a `CREATE TABLE` statement, defined either on a column or on the table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableConstraint {
+    pub kind: ConstraintKind,
+    /// The name of the constraint, if it is named explicitly
+    pub name: Option<String>,
+    /// The constrained columns. Empty for table-level check constraints.
+    pub columns: Vec<String>,
+    /// The referenced table of a foreign key, qualified with its schema if specified
+    pub referenced_table: Option<String>,
+    /// The referenced columns of a foreign key. Empty if the primary key is referenced implicitly.
+    pub referenced_columns: Vec<String>,
+}
+
+/// A column definition of a `CREATE TABLE` statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnDefinition {
+    pub name: String,
+    /// The type as it is written, e.g. `varchar(255)` or `text[]`
+    pub type_name: String,
+    /// The number of array dimensions of the type, e.g. 2 for `int[][]`
+    pub array_dimensions: usize,
+    /// Whether the type is `smallserial`, `serial` or `bigserial`, which the server creates as an
+    /// integer column that is not null and defaults to the next value of a new sequence
+    pub serial: bool,
+    /// Whether the column accepts `NULL`, i.e. it is neither `NOT NULL`, a primary key, an
+    /// identity column nor a serial column
+    pub nullable: bool,
+    /// The text of the `DEFAULT` expression, which serial columns do not have in the statement
+    pub default: Option<String>,
+    /// The constraints of the column, in the order they are written
+    pub constraints: Vec<TableConstraint>,
+    /// The range of the name of the column
+    pub range: TextRange,
+}
+
+/// What happens to the referencing rows of a foreign key when the referenced row is deleted or
+/// updated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferentialAction {
+    /// The default, which fails at the end of the statement if referencing rows remain
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+}
+
+/// How a foreign key with multiple columns matches rows in which some columns are `NULL`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchType {
+    /// The default, which does not check a row if any column is `NULL`
+    Simple,
+    Full,
+    Partial,
+}
+
+/// The referential actions of a foreign key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FkAction {
+    /// The table that the foreign key is defined on, qualified with its schema if specified
+    pub table: String,
+    /// The foreign key constraint
+    pub constraint: TableConstraint,
+    pub on_delete: ReferentialAction,
+    pub on_update: ReferentialAction,
+    pub match_type: MatchType,
+}
+
+/// Returns the referential actions of all foreign keys that the `CREATE TABLE` or
+/// `ALTER TABLE ... ADD CONSTRAINT` statement `stmt` defines
+///
+/// Foreign keys without explicit actions have the defaults `NO ACTION` and `MATCH SIMPLE`.
+pub fn foreign_key_actions(stmt: &SyntaxNode) -> Vec<FkAction> {
+    let (relation, constraints) = match parse_pg_query_stmt(stmt) {
+        Some(NodeEnum::CreateStmt(create)) => {
+            let constraints = create
+                .table_elts
+                .iter()
+                .filter_map(|n| n.node.as_ref())
+                .flat_map(|elt| match elt {
+                    NodeEnum::ColumnDef(column) => column
+                        .constraints
+                        .iter()
+                        .filter_map(|n| match n.node.as_ref() {
+                            Some(NodeEnum::Constraint(c)) => {
+                                Some(((**c).clone(), Some(column.colname.clone())))
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                    NodeEnum::Constraint(c) => vec![((**c).clone(), None)],
+                    _ => Vec::new(),
+                })
+                .collect::<Vec<_>>();
+            (create.relation, constraints)
+        }
+        Some(NodeEnum::AlterTableStmt(alter)) => {
+            let constraints = alter
+                .cmds
+                .iter()
+                .filter_map(|n| match n.node.as_ref()? {
+                    NodeEnum::AlterTableCmd(cmd)
+                        if cmd.subtype == AlterTableType::AtAddConstraint as i32 =>
+                    {
+                        match cmd.def.as_ref()?.node.as_ref()? {
+                            NodeEnum::Constraint(c) => Some(((**c).clone(), None)),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            (alter.relation, constraints)
+        }
+        _ => return Vec::new(),
+    };
+    let table = match relation {
+        Some(relation) => qualified_name(&relation),
+        None => return Vec::new(),
+    };
+
+    constraints
+        .iter()
+        .filter(|(c, _)| c.contype == ConstrType::ConstrForeign as i32)
+        .filter_map(|(c, column)| {
+            Some(FkAction {
+                table: table.clone(),
+                constraint: table_constraint(c, column.as_deref())?,
+                on_delete: referential_action(&c.fk_del_action),
+                on_update: referential_action(&c.fk_upd_action),
+                match_type: match c.fk_matchtype.as_str() {
+                    "f" => MatchType::Full,
+                    "p" => MatchType::Partial,
+                    _ => MatchType::Simple,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Converts the action code of pg_query, e.g. `c` for `CASCADE`
+fn referential_action(action: &str) -> ReferentialAction {
+    match action {
+        "r" => ReferentialAction::Restrict,
+        "c" => ReferentialAction::Cascade,
+        "n" => ReferentialAction::SetNull,
+        "d" => ReferentialAction::SetDefault,
+        _ => ReferentialAction::NoAction,
+    }
+}
+
+/// Returns all primary key, unique, check, foreign key and not null constraints of the
+/// `CREATE TABLE` statement `stmt`
+///
+/// Column-level constraints come first, in the order of their columns, followed by the
+/// table-level constraints.
+pub fn table_constraints(stmt: &SyntaxNode) -> Vec<TableConstraint> {
+    let create = match parse_pg_query_stmt(stmt) {
+        Some(NodeEnum::CreateStmt(create)) => create,
+        _ => return Vec::new(),
+    };
+
+    let mut column_constraints = Vec::new();
+    let mut table_constraints = Vec::new();
+    for elt in create.table_elts.iter().filter_map(|n| n.node.as_ref()) {
+        match elt {
+            NodeEnum::ColumnDef(column) => column_constraints.extend(
+                column
+                    .constraints
+                    .iter()
+                    .filter_map(|n| match n.node.as_ref() {
+                        Some(NodeEnum::Constraint(c)) => Some(c),
+                        _ => None,
+                    })
+                    .filter_map(|c| table_constraint(c, Some(&column.colname))),
+            ),
+            NodeEnum::Constraint(c) => table_constraints.extend(table_constraint(c, None)),
+            _ => {}
+        }
+    }
+    column_constraints.extend(table_constraints);
+    column_constraints
+}
+
+/// Converts a pg_query `constraint`, which is defined on `column` if it is a column-level constraint
+fn table_constraint(
+    constraint: &protobuf::Constraint,
+    column: Option<&str>,
+) -> Option<TableConstraint> {
+    let kind = match ConstrType::from_i32(constraint.contype)? {
+        ConstrType::ConstrPrimary => ConstraintKind::PrimaryKey,
+        ConstrType::ConstrUnique => ConstraintKind::Unique,
+        ConstrType::ConstrCheck => ConstraintKind::Check,
+        ConstrType::ConstrForeign => ConstraintKind::ForeignKey,
+        ConstrType::ConstrNotnull => ConstraintKind::NotNull,
+        _ => return None,
+    };
+
+    let columns = match column {
+        Some(column) => vec![column.to_string()],
+        None if kind == ConstraintKind::ForeignKey => names(&constraint.fk_attrs),
+        None => names(&constraint.keys),
+    };
+
+    Some(TableConstraint {
+        kind,
+        name: Some(constraint.conname.clone()).filter(|n| !n.is_empty()),
+        columns,
+        referenced_table: constraint.pktable.as_ref().map(qualified_name),
+        referenced_columns: names(&constraint.pk_attrs),
+    })
+}
+
+/// Returns the column definitions of the `CREATE TABLE` statement `stmt`, in their order
+///
+/// Table-level constraints are not part of the columns, e.g. a `PRIMARY KEY (id)` after the
+/// columns, and do not change their nullability.
+pub fn column_definitions(stmt: &SyntaxNode) -> Vec<ColumnDefinition> {
+    let create = match parse_pg_query_stmt(stmt) {
+        Some(NodeEnum::CreateStmt(create)) => create,
+        _ => return Vec::new(),
+    };
+    let types = type_names(stmt);
+    let text = stmt.resolved().text().to_string();
+    let defaults = generated_columns(stmt)
+        .into_iter()
+        .filter(|c| c.kind == GeneratedKind::Default)
+        .collect::<Vec<_>>();
+
+    create
+        .table_elts
+        .iter()
+        .filter_map(|n| match n.node.as_ref()? {
+            NodeEnum::ColumnDef(column) => Some(column),
+            _ => None,
+        })
+        .filter_map(|column| {
+            let type_name = column.type_name.as_ref()?;
+            let type_range = types
+                .iter()
+                .find(|(t, _)| t.location == type_name.location)
+                .map(|(_, range)| *range)?;
+            let base_name = match type_name.names.last()?.node.as_ref()? {
+                NodeEnum::String(s) => s.str.as_str(),
+                _ => return None,
+            };
+            let serial = matches!(
+                base_name,
+                "smallserial" | "serial2" | "serial" | "serial4" | "bigserial" | "serial8"
+            );
+            let constraints = column
+                .constraints
+                .iter()
+                .filter_map(|n| match n.node.as_ref()? {
+                    NodeEnum::Constraint(c) => Some(c),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            let not_null = constraints.iter().any(|c| {
+                [
+                    ConstrType::ConstrNotnull,
+                    ConstrType::ConstrPrimary,
+                    ConstrType::ConstrIdentity,
+                ]
+                .iter()
+                .any(|kind| c.contype == *kind as i32)
+            });
+
+            Some(ColumnDefinition {
+                name: column.colname.clone(),
+                type_name: text[type_range - stmt.text_range().start()].to_string(),
+                array_dimensions: type_name.array_bounds.len(),
+                serial,
+                nullable: !not_null && !serial,
+                default: defaults
+                    .iter()
+                    .find(|c| c.column == column.colname)
+                    .map(|c| c.expression.clone()),
+                constraints: constraints
+                    .iter()
+                    .filter_map(|c| table_constraint(c, Some(&column.colname)))
+                    .collect(),
+                range: token_at(stmt, column.location)?.text_range(),
+            })
+        })
+        .collect()
+}
+
+/// Returns the name of `table`, qualified with its schema if the statement specifies one
+pub(crate) fn qualified_name(table: &RangeVar) -> String {
+    if table.schemaname.is_empty() {
+        table.relname.clone()
+    } else {
+        format!("{}.{}", table.schemaname, table.relname)
+    }
+}
+
+/// Returns the values of all `String` nodes of `nodes`, which is how pg_query lists column names
+fn names(nodes: &[Node]) -> Vec<String> {
+    nodes
+        .iter()
+        .filter_map(|n| match n.node.as_ref() {
+            Some(NodeEnum::String(s)) => Some(s.str.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn constraints(input: &str) -> Vec<TableConstraint> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        table_constraints(&parsed.cst)
+    }
+
+    #[test]
+    fn test_composite_primary_key_and_foreign_key() {
+        let constraints = constraints(
+            "create table order_items (
+                order_id int references orders,
+                item_no int not null,
+                product_id int,
+                primary key (order_id, item_no),
+                constraint fk_product foreign key (product_id) references shop.products (id)
+            );",
+        );
+
+        assert_eq!(
+            constraints,
+            vec![
+                TableConstraint {
+                    kind: ConstraintKind::ForeignKey,
+                    name: None,
+                    columns: vec!["order_id".to_string()],
+                    referenced_table: Some("orders".to_string()),
+                    referenced_columns: vec![],
+                },
+                TableConstraint {
+                    kind: ConstraintKind::NotNull,
+                    name: None,
+                    columns: vec!["item_no".to_string()],
+                    referenced_table: None,
+                    referenced_columns: vec![],
+                },
+                TableConstraint {
+                    kind: ConstraintKind::PrimaryKey,
+                    name: None,
+                    columns: vec!["order_id".to_string(), "item_no".to_string()],
+                    referenced_table: None,
+                    referenced_columns: vec![],
+                },
+                TableConstraint {
+                    kind: ConstraintKind::ForeignKey,
+                    name: Some("fk_product".to_string()),
+                    columns: vec!["product_id".to_string()],
+                    referenced_table: Some("shop.products".to_string()),
+                    referenced_columns: vec!["id".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_column_definitions() {
+        let input = "create table users (
+            id bigserial primary key,
+            email varchar(255) not null unique,
+            tags text[][] default '{}',
+            created_at timestamptz not null default now(),
+            bio text
+        );";
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let columns = column_definitions(&parser.finish().cst);
+
+        let summary = columns
+            .iter()
+            .map(|c| {
+                (
+                    c.name.as_str(),
+                    c.type_name.as_str(),
+                    c.array_dimensions,
+                    c.serial,
+                    c.nullable,
+                    c.default.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("id", "bigserial", 0, true, false, None),
+                ("email", "varchar(255)", 0, false, false, None),
+                ("tags", "text[][]", 2, false, true, Some("'{}'")),
+                ("created_at", "timestamptz", 0, false, false, Some("now()")),
+                ("bio", "text", 0, false, true, None),
+            ]
+        );
+        assert_eq!(
+            columns[1]
+                .constraints
+                .iter()
+                .map(|c| c.kind)
+                .collect::<Vec<_>>(),
+            vec![ConstraintKind::NotNull, ConstraintKind::Unique]
+        );
+        assert_eq!(&input[columns[4].range], "bio");
+    }
+
+    #[test]
+    fn test_check_and_unique() {
+        let kinds = constraints("create table t (id int unique, check (id > 0));")
+            .iter()
+            .map(|c| c.kind)
+            .collect::<Vec<_>>();
+
+        assert_eq!(kinds, vec![ConstraintKind::Unique, ConstraintKind::Check]);
+    }
+
+    #[test]
+    fn test_foreign_key_actions() {
+        let actions = |input: &str| {
+            let mut parser = Parser::new();
+            parser.parse_statement(input, None);
+            foreign_key_actions(&parser.finish().cst)
+                .into_iter()
+                .map(|a| {
+                    (
+                        a.table,
+                        a.constraint.columns,
+                        a.on_delete,
+                        a.on_update,
+                        a.match_type,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            actions(
+                "create table app.items (order_id int references orders on delete cascade, id int primary key);"
+            ),
+            vec![(
+                "app.items".to_string(),
+                vec!["order_id".to_string()],
+                ReferentialAction::Cascade,
+                ReferentialAction::NoAction,
+                MatchType::Simple
+            )]
+        );
+        assert_eq!(
+            actions("alter table items add constraint fk_product foreign key (product_id) references products (id) match full on update restrict;"),
+            vec![(
+                "items".to_string(),
+                vec!["product_id".to_string()],
+                ReferentialAction::NoAction,
+                ReferentialAction::Restrict,
+                MatchType::Full
+            )]
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use cstree::text::TextRange;
+use pg_query::NodeEnum;
+
+use crate::ast::{parse_pg_query_stmt, syntax_node_for};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the `ResTarget` nodes of the `RETURNING` clause of the `INSERT`, `UPDATE` or `DELETE`
+/// statement `stmt`
+///
+/// Returns `None` if `stmt` is not one of these statements, or has no `RETURNING` clause.
+/// `RETURNING *` is a single target.
+pub fn returning_clause(stmt: &SyntaxNode) -> Option<Vec<&SyntaxNode>> {
+    let returning_list = match parse_pg_query_stmt(stmt)? {
+        NodeEnum::InsertStmt(s) => s.returning_list,
+        NodeEnum::UpdateStmt(s) => s.returning_list,
+        NodeEnum::DeleteStmt(s) => s.returning_list,
+        _ => return None,
+    };
+    if returning_list.is_empty() {
+        return None;
+    }
+
+    Some(
+        returning_list
+            .iter()
+            .filter_map(|n| n.node.as_ref())
+            .filter_map(|n| syntax_node_for(stmt, &n.to_ref()))
+            .collect(),
+    )
+}
+
+/// Returns the value expressions of every row of the `INSERT ... VALUES` statement `stmt`
+///
+/// Returns `None` if `stmt` is not an `INSERT` statement, or inserts the result of a query.
+pub fn insert_values(stmt: &SyntaxNode) -> Option<Vec<Vec<&SyntaxNode>>> {
+    match parse_pg_query_stmt(stmt)? {
+        NodeEnum::InsertStmt(s) => rows(stmt, &s.select_stmt?.node?),
+        _ => None,
+    }
+}
+
+/// Returns the value expressions of every row of `stmt`, which is either a standalone `VALUES`
+/// statement or an `INSERT ... VALUES` statement
+pub fn values_rows(stmt: &SyntaxNode) -> Option<Vec<Vec<&SyntaxNode>>> {
+    match parse_pg_query_stmt(stmt)? {
+        NodeEnum::InsertStmt(s) => rows(stmt, &s.select_stmt?.node?),
+        select @ NodeEnum::SelectStmt(_) => rows(stmt, &select),
+        _ => None,
+    }
+}
+
+/// Returns the number of values and the range of the values of every row of `stmt`, which is
+/// either a standalone `VALUES` statement or an `INSERT ... VALUES` statement
+///
+/// Unlike `values_rows`, the values are counted in the ast, so a value that cannot be mapped onto
+/// the cst still counts. The range covers the values that can be mapped, if any.
+pub(crate) fn values_row_extents(stmt: &SyntaxNode) -> Option<Vec<(usize, Option<TextRange>)>> {
+    let values_lists = match parse_pg_query_stmt(stmt)? {
+        NodeEnum::InsertStmt(s) => match s.select_stmt?.node? {
+            NodeEnum::SelectStmt(s) => s.values_lists,
+            _ => return None,
+        },
+        NodeEnum::SelectStmt(s) => s.values_lists,
+        _ => return None,
+    };
+    if values_lists.is_empty() {
+        return None;
+    }
+
+    Some(
+        values_lists
+            .iter()
+            .filter_map(|n| match n.node.as_ref() {
+                Some(NodeEnum::List(row)) => Some((
+                    row.items.len(),
+                    row.items
+                        .iter()
+                        .filter_map(|n| n.node.as_ref())
+                        .filter_map(|n| syntax_node_for(stmt, &n.to_ref()))
+                        .map(|n| n.text_range())
+                        .reduce(|a, b| a.cover(b)),
+                )),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+/// Returns the rows of the values list `select` of the statement node `stmt`
+fn rows<'a>(stmt: &'a SyntaxNode, select: &NodeEnum) -> Option<Vec<Vec<&'a SyntaxNode>>> {
+    let values_lists = match select {
+        NodeEnum::SelectStmt(s) if !s.values_lists.is_empty() => &s.values_lists,
+        _ => return None,
+    };
+
+    Some(
+        values_lists
+            .iter()
+            .filter_map(|n| match n.node.as_ref() {
+                Some(NodeEnum::List(row)) => Some(
+                    row.items
+                        .iter()
+                        .filter_map(|n| n.node.as_ref())
+                        .filter_map(|n| syntax_node_for(stmt, &n.to_ref()))
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn returning(input: &str) -> Option<Vec<String>> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        returning_clause(&parsed.cst).map(|targets| {
+            targets
+                .iter()
+                .map(|n| n.resolved().text().to_string())
+                .collect()
+        })
+    }
+
+    fn values(
+        input: &str,
+        f: fn(&SyntaxNode) -> Option<Vec<Vec<&SyntaxNode>>>,
+    ) -> Option<Vec<Vec<String>>> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        f(&parsed.cst).map(|rows| {
+            rows.iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|n| n.resolved().text().to_string().trim().to_string())
+                        .collect()
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_returning_clause() {
+        assert_eq!(
+            returning("insert into users (name) values ('a') returning id, name;"),
+            Some(vec!["id".to_string(), "name".to_string()])
+        );
+        assert_eq!(
+            returning("update users set name = 'b' returning *;"),
+            Some(vec!["*".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_without_returning_clause() {
+        assert_eq!(returning("delete from users;"), None);
+        assert_eq!(returning("select id from users;"), None);
+    }
+
+    #[test]
+    fn test_insert_values() {
+        assert_eq!(
+            values(
+                "insert into users (id, name) values (1, 'a'), (default, lower($1));",
+                insert_values
+            ),
+            Some(vec![
+                vec!["1".to_string(), "'a'".to_string()],
+                vec!["default".to_string(), "lower($1)".to_string()],
+            ])
+        );
+        assert_eq!(
+            values("insert into users select * from old_users;", insert_values),
+            None
+        );
+        assert_eq!(values("values (1);", insert_values), None);
+    }
+
+    #[test]
+    fn test_values_rows() {
+        assert_eq!(
+            values("values (1, 2), (3, 4);", values_rows),
+            Some(vec![
+                vec!["1".to_string(), "2".to_string()],
+                vec!["3".to_string(), "4".to_string()],
+            ])
+        );
+        assert_eq!(
+            values("insert into t values (1);", values_rows),
+            Some(vec![vec!["1".to_string()]])
+        );
+        assert_eq!(values("select 1;", values_rows), None);
+    }
+}
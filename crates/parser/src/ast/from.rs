@@ -0,0 +1,312 @@
+use cstree::text::{TextRange, TextSize};
+use pg_query::protobuf::Node;
+use pg_query::NodeEnum;
+
+use crate::ast::{location_to_offset, token_before, SelectStmt};
+use crate::pg_query_utils::{get_nodes, get_position_for_pg_query_node};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// The kind of an item of a `FROM` clause
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FromItemKind {
+    Table,
+    /// A subquery, e.g. `(select ...) s`
+    Subquery,
+    /// A function call, e.g. `generate_series(1, 10) g`
+    Function,
+    /// A `VALUES` list, e.g. `(values (1), (2)) v (x)`
+    Values,
+    /// A join of other items, e.g. `a join b on ...`
+    Join,
+}
+
+/// An item of a `FROM` clause
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FromItem {
+    pub kind: FromItemKind,
+    /// The range of the item, including its alias
+    pub range: TextRange,
+    relations: Vec<FromItem>,
+}
+
+impl FromItem {
+    /// The items that participate in a join, with the items of nested joins flattened, ordered by
+    /// their position
+    ///
+    /// Returns an empty list for items that are not a join.
+    pub fn relations(&self) -> &[FromItem] {
+        &self.relations
+    }
+}
+
+/// Returns the items of the `FROM` clause of `select`
+pub fn from_items(select: &SelectStmt) -> Vec<FromItem> {
+    let stmt = select.syntax();
+    let from_clause = &select.ast().from_clause;
+    let start = match from_clause
+        .first()
+        .and_then(|item| start_offset(stmt, item))
+    {
+        Some(start) => start,
+        None => return Vec::new(),
+    };
+    let keyword = match token_before(stmt, SyntaxKind::From, start) {
+        Some(keyword) => keyword.text_range(),
+        None => return Vec::new(),
+    };
+    let tokens = stmt
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .filter(|t| !t.kind().is_trivia() && t.text_range().start() >= keyword.end())
+        .collect::<Vec<_>>();
+
+    from_clause
+        .iter()
+        .zip(split_items(&tokens))
+        .filter_map(|(item, tokens)| from_item(item.node.as_ref()?, tokens))
+        .collect()
+}
+
+/// Returns the position of the first located node of the `FROM` clause item `item`
+fn start_offset(stmt: &SyntaxNode, item: &Node) -> Option<TextSize> {
+    let position = get_nodes(item.node.as_ref()?.to_ref())
+        .iter()
+        .map(|(n, _)| get_position_for_pg_query_node(n))
+        .filter(|p| *p >= 0)
+        .min()?;
+    location_to_offset(stmt, position)
+}
+
+fn from_item(node: &NodeEnum, tokens: &[&SyntaxToken]) -> Option<FromItem> {
+    let range = tokens
+        .first()?
+        .text_range()
+        .cover(tokens.last()?.text_range());
+    let kind = match node {
+        NodeEnum::RangeVar(_) | NodeEnum::RangeTableSample(_) => FromItemKind::Table,
+        NodeEnum::RangeSubselect(s) => match s.subquery.as_ref().and_then(|n| n.node.as_ref()) {
+            Some(NodeEnum::SelectStmt(query)) if !query.values_lists.is_empty() => {
+                FromItemKind::Values
+            }
+            _ => FromItemKind::Subquery,
+        },
+        NodeEnum::RangeFunction(_) | NodeEnum::RangeTableFunc(_) => FromItemKind::Function,
+        NodeEnum::JoinExpr(_) => {
+            let mut leaves = Vec::new();
+            join_leaves(node, &mut leaves);
+            let relations = leaves
+                .into_iter()
+                .zip(split_join(tokens))
+                .filter_map(|(leaf, tokens)| from_item(leaf, tokens))
+                .collect();
+            return Some(FromItem {
+                kind: FromItemKind::Join,
+                range,
+                relations,
+            });
+        }
+        _ => return None,
+    };
+    Some(FromItem {
+        kind,
+        range,
+        relations: Vec::new(),
+    })
+}
+
+/// Collects the items that participate in the join `node`, in order
+fn join_leaves<'a>(node: &'a NodeEnum, leaves: &mut Vec<&'a NodeEnum>) {
+    match node {
+        NodeEnum::JoinExpr(join) => {
+            for arg in [&join.larg, &join.rarg].into_iter().flatten() {
+                if let Some(arg) = arg.node.as_ref() {
+                    join_leaves(arg, leaves);
+                }
+            }
+        }
+        _ => leaves.push(node),
+    }
+}
+
+/// Splits the tokens after the `FROM` keyword into the tokens of its items
+///
+/// The clause ends at the first keyword of the next clause, or a closing parenthesis that is not
+/// opened within the clause.
+fn split_items<'a, 't>(tokens: &'a [&'t SyntaxToken]) -> Vec<&'a [&'t SyntaxToken]> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut depth = 0;
+    for (idx, token) in tokens.iter().enumerate() {
+        match token.kind() {
+            SyntaxKind::Ascii40 => depth += 1,
+            SyntaxKind::Ascii41 if depth == 0 => {
+                items.push(&tokens[start..idx]);
+                return items;
+            }
+            SyntaxKind::Ascii41 => depth -= 1,
+            SyntaxKind::Ascii44 if depth == 0 => {
+                items.push(&tokens[start..idx]);
+                start = idx + 1;
+            }
+            kind if depth == 0 && ends_from_clause(kind) => {
+                items.push(&tokens[start..idx]);
+                return items;
+            }
+            _ => {}
+        }
+    }
+    items.push(&tokens[start..]);
+    items
+}
+
+fn ends_from_clause(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Where
+            | SyntaxKind::GroupP
+            | SyntaxKind::Having
+            | SyntaxKind::Window
+            | SyntaxKind::Order
+            | SyntaxKind::Limit
+            | SyntaxKind::Offset
+            | SyntaxKind::Fetch
+            | SyntaxKind::For
+            | SyntaxKind::Union
+            | SyntaxKind::Intersect
+            | SyntaxKind::Except
+            | SyntaxKind::Ascii59
+    )
+}
+
+fn is_join_keyword(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Join
+            | SyntaxKind::Cross
+            | SyntaxKind::Natural
+            | SyntaxKind::Left
+            | SyntaxKind::Right
+            | SyntaxKind::Full
+            | SyntaxKind::InnerP
+            | SyntaxKind::OuterP
+    )
+}
+
+/// Splits the tokens of a join into the tokens of the participating items, in order
+///
+/// The join keywords and the join conditions are dropped. A parenthesized nested join is split
+/// into its items as well.
+fn split_join<'a, 't>(tokens: &'a [&'t SyntaxToken]) -> Vec<&'a [&'t SyntaxToken]> {
+    let mut items = Vec::new();
+    let mut start = Some(0);
+    let mut depth = 0;
+    let push = |items: &mut Vec<_>, item: &'a [&'t SyntaxToken]| {
+        if item.is_empty() {
+            return;
+        }
+        // a parenthesized join, e.g. `(b join c)`
+        if is_parenthesized(item)
+            && !matches!(
+                item[1].kind(),
+                SyntaxKind::Select | SyntaxKind::With | SyntaxKind::Values | SyntaxKind::Table
+            )
+        {
+            items.extend(split_join(&item[1..item.len() - 1]));
+        } else {
+            items.push(item);
+        }
+    };
+    for (idx, token) in tokens.iter().enumerate() {
+        match token.kind() {
+            SyntaxKind::Ascii40 => depth += 1,
+            SyntaxKind::Ascii41 => depth -= 1,
+            kind if depth == 0 && is_join_keyword(kind) => {
+                if let Some(s) = start.take() {
+                    push(&mut items, &tokens[s..idx]);
+                }
+                start = Some(idx + 1);
+            }
+            // the join condition, which lasts until the next join keyword
+            SyntaxKind::On | SyntaxKind::Using if depth == 0 => {
+                if let Some(s) = start.take() {
+                    push(&mut items, &tokens[s..idx]);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        push(&mut items, &tokens[s..]);
+    }
+    items
+}
+
+/// Whether `tokens` are wrapped in a single pair of parentheses
+fn is_parenthesized(tokens: &[&SyntaxToken]) -> bool {
+    if tokens.len() < 2 || tokens[0].kind() != SyntaxKind::Ascii40 {
+        return false;
+    }
+    let mut depth = 0;
+    for (idx, token) in tokens.iter().enumerate() {
+        match token.kind() {
+            SyntaxKind::Ascii40 => depth += 1,
+            SyntaxKind::Ascii41 => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            return idx == tokens.len() - 1;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_from_items() {
+        let input = "select * from users u, (select 1 as a) s, generate_series(1, 3) as g (n), (values (1), (2)) v (x), orders o join (items i join x using (id)) on i.order_id = o.id where true;";
+
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        let select = SelectStmt::cast(&parsed.cst).unwrap();
+        let items = from_items(&select);
+
+        let texts = items
+            .iter()
+            .map(|item| (item.kind, &input[item.range]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            vec![
+                (FromItemKind::Table, "users u"),
+                (FromItemKind::Subquery, "(select 1 as a) s"),
+                (FromItemKind::Function, "generate_series(1, 3) as g (n)"),
+                (FromItemKind::Values, "(values (1), (2)) v (x)"),
+                (
+                    FromItemKind::Join,
+                    "orders o join (items i join x using (id)) on i.order_id = o.id"
+                ),
+            ]
+        );
+
+        let relations = items[4]
+            .relations()
+            .iter()
+            .map(|item| (item.kind, &input[item.range]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            relations,
+            vec![
+                (FromItemKind::Table, "orders o"),
+                (FromItemKind::Table, "items i"),
+                (FromItemKind::Table, "x"),
+            ]
+        );
+        assert!(items[0].relations().is_empty());
+    }
+}
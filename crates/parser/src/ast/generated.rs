@@ -0,0 +1,270 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::{AlterTableType, ColumnDef, ConstrType, SqlValueFunctionOp};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::qualified_name;
+use crate::ast::{
+    closing_parenthesis, depth_at, non_trivia_tokens, parse_pg_query_stmt, token_at, tokens_range,
+};
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// How the value of a `GeneratedColumn` is computed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GeneratedKind {
+    /// `DEFAULT expr`, which is computed once when a row is inserted without the column
+    Default,
+    /// `GENERATED ALWAYS AS (expr) STORED`, which is computed whenever the row changes
+    Generated,
+}
+
+/// A column whose value is computed from an expression
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GeneratedColumn {
+    /// The table of the column, qualified with its schema if specified
+    pub table: String,
+    pub column: String,
+    pub kind: GeneratedKind,
+    /// The text of the expression, without the parentheses of a generated column
+    pub expression: String,
+    /// The range of the expression
+    pub range: TextRange,
+    /// The lowercase names of the functions the expression calls, including functions without
+    /// parentheses such as `current_timestamp`
+    pub functions: Vec<String>,
+}
+
+/// Returns the columns with a `DEFAULT` or `GENERATED ... AS` expression that the `CREATE TABLE`
+/// or `ALTER TABLE ... ADD COLUMN` statement `stmt` defines
+pub fn generated_columns(stmt: &SyntaxNode) -> Vec<GeneratedColumn> {
+    let (relation, columns) = match parse_pg_query_stmt(stmt) {
+        Some(NodeEnum::CreateStmt(create)) => {
+            let columns = create
+                .table_elts
+                .iter()
+                .filter_map(|n| match n.node.as_ref()? {
+                    NodeEnum::ColumnDef(column) => Some(column.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            (create.relation, columns)
+        }
+        Some(NodeEnum::AlterTableStmt(alter)) => {
+            let columns = alter
+                .cmds
+                .iter()
+                .filter_map(|n| match n.node.as_ref()? {
+                    NodeEnum::AlterTableCmd(cmd)
+                        if cmd.subtype == AlterTableType::AtAddColumn as i32 =>
+                    {
+                        match cmd.def.as_ref()?.node.as_ref()? {
+                            NodeEnum::ColumnDef(column) => Some(column.clone()),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            (alter.relation, columns)
+        }
+        _ => return Vec::new(),
+    };
+    let table = match relation {
+        Some(relation) => qualified_name(&relation),
+        None => return Vec::new(),
+    };
+    let tokens = non_trivia_tokens(stmt);
+    let text = stmt.resolved().text().to_string();
+    let source = |range: TextRange| text[range - stmt.text_range().start()].to_string();
+
+    columns
+        .iter()
+        .flat_map(|column| {
+            let starts = column_constraint_starts(stmt, &tokens, column);
+            column
+                .constraints
+                .iter()
+                .filter_map(|n| match n.node.as_ref()? {
+                    NodeEnum::Constraint(c) => Some(c),
+                    _ => None,
+                })
+                .filter_map(|constraint| {
+                    let kind = match ConstrType::from_i32(constraint.contype)? {
+                        ConstrType::ConstrDefault => GeneratedKind::Default,
+                        ConstrType::ConstrGenerated => GeneratedKind::Generated,
+                        _ => return None,
+                    };
+                    let start = index_of(&tokens, token_at(stmt, constraint.location)?)?;
+                    // the expression ends where the next constraint or the column ends
+                    let end = *starts.iter().find(|idx| **idx > start)?;
+                    let expression = match kind {
+                        GeneratedKind::Default => {
+                            let keyword = start
+                                + tokens[start..end]
+                                    .iter()
+                                    .position(|t| t.kind() == SyntaxKind::Default)?;
+                            &tokens[keyword + 1..end]
+                        }
+                        GeneratedKind::Generated => {
+                            let open = start
+                                + tokens[start..end]
+                                    .iter()
+                                    .position(|t| t.kind() == SyntaxKind::Ascii40)?;
+                            &tokens[open + 1..closing_parenthesis(&tokens, open)?]
+                        }
+                    };
+                    let range = tokens_range(expression)?;
+                    let functions = constraint
+                        .raw_expr
+                        .as_deref()
+                        .and_then(|n| n.node.as_ref())
+                        .map(|expr| function_names(expr.to_ref()))
+                        .unwrap_or_default();
+                    Some(GeneratedColumn {
+                        table: table.clone(),
+                        column: column.colname.clone(),
+                        kind,
+                        expression: source(range),
+                        range,
+                        functions,
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Returns the indices of the first tokens of the constraints of `column`, followed by the index
+/// of the token after the column, in ascending order
+fn column_constraint_starts(
+    stmt: &SyntaxNode,
+    tokens: &[&SyntaxToken],
+    column: &ColumnDef,
+) -> Vec<usize> {
+    let name = match token_at(stmt, column.location).and_then(|t| index_of(tokens, t)) {
+        Some(name) => name,
+        None => return Vec::new(),
+    };
+    let depth = depth_at(tokens, name);
+    let end = (name..tokens.len())
+        .find(|idx| {
+            depth_at(tokens, *idx) == depth
+                && matches!(
+                    tokens[*idx].kind(),
+                    SyntaxKind::Ascii41 | SyntaxKind::Ascii44 | SyntaxKind::Ascii59
+                )
+        })
+        .unwrap_or(tokens.len());
+
+    let mut starts = column
+        .constraints
+        .iter()
+        .filter_map(|n| match n.node.as_ref()? {
+            NodeEnum::Constraint(c) => token_at(stmt, c.location),
+            _ => None,
+        })
+        .filter_map(|t| index_of(tokens, t))
+        .chain(std::iter::once(end))
+        .collect::<Vec<_>>();
+    starts.sort_unstable();
+    starts
+}
+
+fn index_of(tokens: &[&SyntaxToken], token: &SyntaxToken) -> Option<usize> {
+    tokens.iter().position(|t| *t == token)
+}
+
+/// Returns the lowercase names of all functions that `expr` calls, in the order of the ast
+fn function_names(expr: NodeRef) -> Vec<String> {
+    get_nodes(expr)
+        .into_iter()
+        .filter_map(|(node, _)| match node {
+            NodeRef::FuncCall(call) => match call.funcname.last()?.node.as_ref()? {
+                NodeEnum::String(s) => Some(s.str.to_lowercase()),
+                _ => None,
+            },
+            NodeRef::SqlvalueFunction(function) => {
+                let name = match SqlValueFunctionOp::from_i32(function.op)? {
+                    SqlValueFunctionOp::SvfopCurrentDate => "current_date",
+                    SqlValueFunctionOp::SvfopCurrentTime
+                    | SqlValueFunctionOp::SvfopCurrentTimeN => "current_time",
+                    SqlValueFunctionOp::SvfopCurrentTimestamp
+                    | SqlValueFunctionOp::SvfopCurrentTimestampN => "current_timestamp",
+                    SqlValueFunctionOp::SvfopLocaltime | SqlValueFunctionOp::SvfopLocaltimeN => {
+                        "localtime"
+                    }
+                    SqlValueFunctionOp::SvfopLocaltimestamp
+                    | SqlValueFunctionOp::SvfopLocaltimestampN => "localtimestamp",
+                    SqlValueFunctionOp::SvfopCurrentRole => "current_role",
+                    SqlValueFunctionOp::SvfopCurrentUser => "current_user",
+                    SqlValueFunctionOp::SvfopUser => "user",
+                    SqlValueFunctionOp::SvfopSessionUser => "session_user",
+                    SqlValueFunctionOp::SvfopCurrentCatalog => "current_catalog",
+                    SqlValueFunctionOp::SvfopCurrentSchema => "current_schema",
+                    SqlValueFunctionOp::SqlvalueFunctionOpUndefined => return None,
+                };
+                Some(name.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn columns(input: &str) -> Vec<GeneratedColumn> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        generated_columns(&parser.finish().cst)
+    }
+
+    #[test]
+    fn test_generated_column() {
+        let input = "create table app.items (price numeric, qty int not null, total numeric generated always as (price * (qty + 1)) stored, id int);";
+        let columns = columns(input);
+        assert_eq!(columns.len(), 1);
+        assert_eq!(columns[0].table, "app.items");
+        assert_eq!(columns[0].column, "total");
+        assert_eq!(columns[0].kind, GeneratedKind::Generated);
+        assert_eq!(columns[0].expression, "price * (qty + 1)");
+        assert_eq!(&input[columns[0].range], "price * (qty + 1)");
+        assert!(columns[0].functions.is_empty());
+    }
+
+    #[test]
+    fn test_default_with_function() {
+        let columns = columns(
+            "create table t (created_at timestamptz default now() not null, updated_at timestamptz default current_timestamp, token text constraint t_token default md5(random()::text));",
+        )
+        .into_iter()
+        .map(|c| (c.column, c.kind, c.expression, c.functions))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                (
+                    "created_at".to_string(),
+                    GeneratedKind::Default,
+                    "now()".to_string(),
+                    vec!["now".to_string()]
+                ),
+                (
+                    "updated_at".to_string(),
+                    GeneratedKind::Default,
+                    "current_timestamp".to_string(),
+                    vec!["current_timestamp".to_string()]
+                ),
+                (
+                    "token".to_string(),
+                    GeneratedKind::Default,
+                    "md5(random()::text)".to_string(),
+                    vec!["md5".to_string(), "random".to_string()]
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use pg_query::protobuf::Node;
+use pg_query::NodeEnum;
+
+use crate::ast::{parse_pg_query_stmt, qualified_name, syntax_node_for};
+use crate::syntax_node::SyntaxNode;
+
+/// An indexed column or expression of an `IndexStmt`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IndexElement {
+    Column(String),
+    /// The text of an expression, e.g. `lower(email)`
+    Expression(String),
+}
+
+/// A `CREATE INDEX` statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexStmt {
+    /// The name of the index, if it is named explicitly
+    pub name: Option<String>,
+    /// The indexed table, qualified with its schema if specified
+    pub table: String,
+    pub elements: Vec<IndexElement>,
+    /// The index method, e.g. `btree` or `gin`
+    pub method: String,
+    pub unique: bool,
+    pub concurrently: bool,
+    /// The text of the predicate of a partial index
+    pub predicate: Option<String>,
+}
+
+/// Returns the details of the `CREATE INDEX` statement `stmt`
+pub fn index_statement(stmt: &SyntaxNode) -> Option<IndexStmt> {
+    let index = match parse_pg_query_stmt(stmt)? {
+        NodeEnum::IndexStmt(index) => index,
+        _ => return None,
+    };
+    let relation = index.relation.as_ref()?;
+
+    Some(IndexStmt {
+        name: Some(index.idxname.clone()).filter(|n| !n.is_empty()),
+        table: qualified_name(relation),
+        elements: index
+            .index_params
+            .iter()
+            .filter_map(|n| match n.node.as_ref() {
+                Some(NodeEnum::IndexElem(elem)) => match elem.expr.as_ref() {
+                    Some(expr) => Some(IndexElement::Expression(expression_text(stmt, expr)?)),
+                    None => Some(IndexElement::Column(elem.name.clone())),
+                },
+                _ => None,
+            })
+            .collect(),
+        method: index.access_method.clone(),
+        unique: index.unique,
+        concurrently: index.concurrent,
+        predicate: index
+            .where_clause
+            .as_ref()
+            .and_then(|expr| expression_text(stmt, expr)),
+    })
+}
+
+/// Returns the text of the cst node of the expression `expr`
+fn expression_text(stmt: &SyntaxNode, expr: &Node) -> Option<String> {
+    let node = syntax_node_for(stmt, &expr.node.as_ref()?.to_ref())?;
+    Some(node.resolved().text().to_string().trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn index(input: &str) -> Option<IndexStmt> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        index_statement(&parsed.cst)
+    }
+
+    #[test]
+    fn test_btree_index() {
+        assert_eq!(
+            index("create index users_email_idx on public.users (email, created_at);"),
+            Some(IndexStmt {
+                name: Some("users_email_idx".to_string()),
+                table: "public.users".to_string(),
+                elements: vec![
+                    IndexElement::Column("email".to_string()),
+                    IndexElement::Column("created_at".to_string()),
+                ],
+                method: "btree".to_string(),
+                unique: false,
+                concurrently: false,
+                predicate: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_partial_unique_expression_index() {
+        assert_eq!(
+            index("create unique index concurrently on users using gin (lower(email)) where deleted_at is null;"),
+            Some(IndexStmt {
+                name: None,
+                table: "users".to_string(),
+                elements: vec![IndexElement::Expression("lower(email)".to_string())],
+                method: "gin".to_string(),
+                unique: true,
+                concurrently: true,
+                predicate: Some("deleted_at is null".to_string()),
+            })
+        );
+    }
+}
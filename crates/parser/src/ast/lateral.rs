@@ -0,0 +1,86 @@
+use cstree::text::TextRange;
+use pg_query::NodeRef;
+
+use crate::ast::{
+    closing_parenthesis, location_to_offset, non_trivia_tokens, parse_pg_query_stmt, token_before,
+};
+use crate::pg_query_utils::{get_nodes, get_position_for_pg_query_node};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the ranges of all `LATERAL` subqueries and function calls in the `FROM` clauses of
+/// `stmt`, ordered by their position
+///
+/// A range reaches from the `LATERAL` keyword to the closing parenthesis of the subquery or the
+/// call, without the alias.
+pub fn lateral_joins(stmt: &SyntaxNode) -> Vec<TextRange> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+    let tokens = non_trivia_tokens(stmt);
+
+    let mut ranges = get_nodes(ast.to_ref())
+        .into_iter()
+        .filter(|(node, _)| match node {
+            NodeRef::RangeSubselect(subselect) => subselect.lateral,
+            NodeRef::RangeFunction(function) => function.lateral,
+            _ => false,
+        })
+        .filter_map(|(node, _)| {
+            // neither a subquery nor a function call in `FROM` has a location, but the keyword
+            // precedes their contents
+            let position = get_nodes(node)
+                .iter()
+                .map(|(n, _)| get_position_for_pg_query_node(n))
+                .filter(|p| *p >= 0)
+                .min()?;
+            let keyword = token_before(
+                stmt,
+                SyntaxKind::LateralP,
+                location_to_offset(stmt, position)?,
+            )?;
+            let lateral = tokens.iter().position(|t| t == &keyword)?;
+            let open = lateral
+                + tokens[lateral..]
+                    .iter()
+                    .position(|t| t.kind() == SyntaxKind::Ascii40)?;
+            let close = closing_parenthesis(&tokens, open)?;
+            Some(keyword.text_range().cover(tokens[close].text_range()))
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|r| r.start());
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lateral(input: &str) -> Vec<&str> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        lateral_joins(&parser.finish().cst)
+            .into_iter()
+            .map(|r| &input[r])
+            .collect()
+    }
+
+    #[test]
+    fn test_lateral_subquery() {
+        assert_eq!(
+            lateral("select * from users u, lateral (select * from orders o where o.user_id = u.id limit 3) o;"),
+            vec!["lateral (select * from orders o where o.user_id = u.id limit 3)"]
+        );
+        assert!(lateral("select * from users u, (select 1) s;").is_empty());
+    }
+
+    #[test]
+    fn test_lateral_function() {
+        assert_eq!(
+            lateral("select * from ranges r cross join lateral generate_series(r.low, r.high) as g (n);"),
+            vec!["lateral generate_series(r.low, r.high)"]
+        );
+    }
+}
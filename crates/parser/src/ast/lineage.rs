@@ -0,0 +1,412 @@
+use pg_query::protobuf::{self, Node, SetOperation};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{parse_pg_query_stmt, qualified_name, view_statement};
+use crate::pg_query_utils::get_children;
+use crate::syntax_node::SyntaxNode;
+
+/// The source of an output column in a `LineageEdge`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LineageSource {
+    /// A column of a base table, qualified with its schema if specified. A `*` of a base table is
+    /// the column `*`, since the columns of base tables are unknown.
+    Column { table: String, column: String },
+    /// A synthetic source for values that do not derive from any column, e.g. literals and
+    /// function results without arguments, and for columns that cannot be resolved
+    Expression,
+}
+
+/// An edge from an output column of a query to one of its sources
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LineageEdge {
+    /// The name of the output column
+    pub column: String,
+    pub source: LineageSource,
+}
+
+/// The output columns of a query, each with its sources
+type Columns = Vec<(String, Vec<LineageSource>)>;
+
+/// A relation of a `FROM` clause, by the name it is referenced with
+enum Relation {
+    Table(String),
+    Derived(Columns),
+}
+
+/// Returns the sources of every output column of the select or `CREATE VIEW` statement `stmt`
+///
+/// Columns of CTEs and subqueries in the `FROM` clause are resolved to the base table columns
+/// they derive from, including recursive CTEs. Edges are ordered by the output columns.
+pub fn column_lineage(stmt: &SyntaxNode) -> Vec<LineageEdge> {
+    let (columns, names) = match view_statement(stmt) {
+        Some(view) => (query_columns(view.query.ast(), &[]), view.columns),
+        None => match parse_pg_query_stmt(stmt) {
+            Some(NodeEnum::SelectStmt(select)) => (query_columns(&select, &[]), Vec::new()),
+            _ => return Vec::new(),
+        },
+    };
+
+    rename(columns, &names)
+        .into_iter()
+        .flat_map(|(column, sources)| {
+            sources.into_iter().map(move |source| LineageEdge {
+                column: column.clone(),
+                source,
+            })
+        })
+        .collect()
+}
+
+/// Returns the output columns of `select`, which may use the CTEs `ctes` of enclosing queries
+fn query_columns(select: &protobuf::SelectStmt, ctes: &[(String, Columns)]) -> Columns {
+    let mut ctes = ctes.to_vec();
+    if let Some(with) = &select.with_clause {
+        for cte in with.ctes.iter().filter_map(|n| match n.node.as_ref() {
+            Some(NodeEnum::CommonTableExpr(cte)) => Some(cte),
+            _ => None,
+        }) {
+            let query = match cte.ctequery.as_ref().and_then(|n| n.node.as_ref()) {
+                Some(NodeEnum::SelectStmt(query)) => query,
+                _ => continue,
+            };
+            let columns = if with.recursive && is_set_operation(query) {
+                // the recursive term references the cte itself, which has the columns of the
+                // non-recursive term
+                let larg = query
+                    .larg
+                    .as_deref()
+                    .map(|l| query_columns(l, &ctes))
+                    .unwrap_or_default();
+                let mut recursive = ctes.clone();
+                recursive.push((
+                    cte.ctename.clone(),
+                    rename(larg.clone(), &names(&cte.aliascolnames)),
+                ));
+                let rarg = query
+                    .rarg
+                    .as_deref()
+                    .map(|r| query_columns(r, &recursive))
+                    .unwrap_or_default();
+                merge(larg, rarg)
+            } else {
+                query_columns(query, &ctes)
+            };
+            ctes.push((
+                cte.ctename.clone(),
+                rename(columns, &names(&cte.aliascolnames)),
+            ));
+        }
+    }
+
+    if is_set_operation(select) {
+        let larg = select.larg.as_deref().map(|l| query_columns(l, &ctes));
+        let rarg = select.rarg.as_deref().map(|r| query_columns(r, &ctes));
+        return merge(larg.unwrap_or_default(), rarg.unwrap_or_default());
+    }
+
+    if let Some(row) = select.values_lists.first() {
+        let len = match row.node.as_ref() {
+            Some(NodeEnum::List(row)) => row.items.len(),
+            _ => 0,
+        };
+        return (1..=len)
+            .map(|idx| (format!("column{}", idx), vec![LineageSource::Expression]))
+            .collect();
+    }
+
+    let mut relations = Vec::new();
+    for item in select.from_clause.iter() {
+        collect_relations(item, &ctes, &mut relations);
+    }
+
+    let mut columns = Vec::new();
+    for target in select
+        .target_list
+        .iter()
+        .filter_map(|n| match n.node.as_ref() {
+            Some(NodeEnum::ResTarget(t)) => Some(t),
+            _ => None,
+        })
+    {
+        let value = match target.val.as_deref() {
+            Some(value) => value,
+            None => continue,
+        };
+        if let Some(NodeEnum::ColumnRef(column)) = value.node.as_ref() {
+            if let Some(expanded) = expand_star(column, &relations) {
+                columns.extend(expanded);
+                continue;
+            }
+        }
+
+        let name = if target.name.is_empty() {
+            column_name(value)
+        } else {
+            target.name.clone()
+        };
+        let mut sources = Vec::new();
+        collect_sources(value, &relations, &mut sources);
+        if sources.is_empty() {
+            sources.push(LineageSource::Expression);
+        }
+        columns.push((name, sources));
+    }
+    columns
+}
+
+fn is_set_operation(select: &protobuf::SelectStmt) -> bool {
+    select.op != SetOperation::SetopNone as i32 && select.op != SetOperation::Undefined as i32
+}
+
+/// Collects the relations of the `FROM` clause item `node`
+fn collect_relations(
+    node: &Node,
+    ctes: &[(String, Columns)],
+    relations: &mut Vec<(String, Relation)>,
+) {
+    match node.node.as_ref() {
+        Some(NodeEnum::RangeVar(range_var)) => {
+            let name = match &range_var.alias {
+                Some(alias) => alias.aliasname.clone(),
+                None => range_var.relname.clone(),
+            };
+            let cte = ctes
+                .iter()
+                .rev()
+                .find(|(cte, _)| range_var.schemaname.is_empty() && *cte == range_var.relname);
+            let relation = match cte {
+                Some((_, columns)) => Relation::Derived(rename(
+                    columns.clone(),
+                    &alias_columns(range_var.alias.as_ref()),
+                )),
+                None => Relation::Table(qualified_name(range_var)),
+            };
+            relations.push((name, relation));
+        }
+        Some(NodeEnum::RangeSubselect(subselect)) => {
+            let columns = match subselect.subquery.as_ref().and_then(|n| n.node.as_ref()) {
+                Some(NodeEnum::SelectStmt(query)) => query_columns(query, ctes),
+                _ => Vec::new(),
+            };
+            let alias = subselect.alias.as_ref();
+            relations.push((
+                alias.map(|a| a.aliasname.clone()).unwrap_or_default(),
+                Relation::Derived(rename(columns, &alias_columns(alias))),
+            ));
+        }
+        Some(NodeEnum::JoinExpr(join)) => {
+            for arg in [&join.larg, &join.rarg].into_iter().flatten() {
+                collect_relations(arg, ctes, relations);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Expands `*` or `relation.*` into the columns of the relations, if `column` is a star
+fn expand_star(column: &protobuf::ColumnRef, relations: &[(String, Relation)]) -> Option<Columns> {
+    let (qualifier, last) = match column.fields.as_slice() {
+        [last] => (None, last),
+        [qualifier, last] => (Some(string(qualifier)?), last),
+        _ => return None,
+    };
+    if !matches!(last.node, Some(NodeEnum::AStar(_))) {
+        return None;
+    }
+
+    Some(
+        relations
+            .iter()
+            .filter(|(name, _)| qualifier.as_ref().is_none_or(|q| q == name))
+            .flat_map(|(_, relation)| match relation {
+                Relation::Table(table) => vec![(
+                    "*".to_string(),
+                    vec![LineageSource::Column {
+                        table: table.clone(),
+                        column: "*".to_string(),
+                    }],
+                )],
+                Relation::Derived(columns) => columns.clone(),
+            })
+            .collect(),
+    )
+}
+
+/// Collects the sources of all column references in the expression `node`
+///
+/// Subqueries within the expression are not resolved.
+fn collect_sources(
+    node: &Node,
+    relations: &[(String, Relation)],
+    sources: &mut Vec<LineageSource>,
+) {
+    fn collect(node: NodeRef, relations: &[(String, Relation)], sources: &mut Vec<LineageSource>) {
+        match node {
+            NodeRef::SelectStmt(_) => {}
+            NodeRef::ColumnRef(column) => {
+                for source in resolve(column, relations) {
+                    if !sources.contains(&source) {
+                        sources.push(source);
+                    }
+                }
+            }
+            _ => {
+                for child in get_children(node) {
+                    collect(child, relations, sources);
+                }
+            }
+        }
+    }
+
+    if let Some(node) = node.node.as_ref() {
+        collect(node.to_ref(), relations, sources);
+    }
+}
+
+/// Returns the sources of the column reference `column`
+///
+/// An unqualified column is looked up in the derived relations first. If it is not found there,
+/// it belongs to the only table of the `FROM` clause. Otherwise, it cannot be resolved.
+fn resolve(column: &protobuf::ColumnRef, relations: &[(String, Relation)]) -> Vec<LineageSource> {
+    let names = column.fields.iter().map(string).collect::<Option<Vec<_>>>();
+    let (qualifier, name) = match names.as_deref() {
+        Some([name]) => (None, name.clone()),
+        Some([.., qualifier, name]) => (Some(qualifier.clone()), name.clone()),
+        _ => return vec![LineageSource::Expression],
+    };
+
+    let candidates = relations
+        .iter()
+        .filter(|(relation, _)| qualifier.as_ref().is_none_or(|q| q == relation))
+        .collect::<Vec<_>>();
+    let derived = candidates.iter().find_map(|(_, relation)| match relation {
+        Relation::Derived(columns) => columns.iter().find(|(c, _)| *c == name),
+        Relation::Table(_) => None,
+    });
+    if let Some((_, sources)) = derived {
+        return sources.clone();
+    }
+
+    let tables = candidates
+        .iter()
+        .filter_map(|(_, relation)| match relation {
+            Relation::Table(table) => Some(table),
+            Relation::Derived(_) => None,
+        })
+        .collect::<Vec<_>>();
+    match tables.as_slice() {
+        [table] => vec![LineageSource::Column {
+            table: table.to_string(),
+            column: name,
+        }],
+        _ => vec![LineageSource::Expression],
+    }
+}
+
+/// Returns the name of an output column without an explicit name, the same way Postgres does
+fn column_name(node: &Node) -> String {
+    match node.node.as_ref() {
+        Some(NodeEnum::ColumnRef(column)) => column.fields.last().and_then(string),
+        Some(NodeEnum::FuncCall(call)) => call.funcname.last().and_then(string),
+        Some(NodeEnum::TypeCast(cast)) => cast.arg.as_deref().map(column_name),
+        Some(NodeEnum::CaseExpr(_)) => Some("case".to_string()),
+        _ => None,
+    }
+    .unwrap_or_else(|| "?column?".to_string())
+}
+
+/// Combines the columns of the two sides of a set operation, which are named after the left side
+fn merge(left: Columns, right: Columns) -> Columns {
+    let mut right = right.into_iter();
+    left.into_iter()
+        .map(|(name, mut sources)| {
+            for source in right.next().map(|(_, s)| s).unwrap_or_default() {
+                if !sources.contains(&source) {
+                    sources.push(source);
+                }
+            }
+            (name, sources)
+        })
+        .collect()
+}
+
+/// Renames the first columns of `columns` to `names`
+fn rename(columns: Columns, names: &[String]) -> Columns {
+    columns
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (name, sources))| (names.get(idx).cloned().unwrap_or(name), sources))
+        .collect()
+}
+
+fn alias_columns(alias: Option<&protobuf::Alias>) -> Vec<String> {
+    alias.map(|a| names(&a.colnames)).unwrap_or_default()
+}
+
+fn names(nodes: &[Node]) -> Vec<String> {
+    nodes.iter().filter_map(string).collect()
+}
+
+fn string(node: &Node) -> Option<String> {
+    match node.node.as_ref() {
+        Some(NodeEnum::String(s)) => Some(s.str.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lineage(input: &str) -> Vec<(String, Option<(String, String)>)> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        column_lineage(&parsed.cst)
+            .into_iter()
+            .map(|edge| {
+                let source = match edge.source {
+                    LineageSource::Column { table, column } => Some((table, column)),
+                    LineageSource::Expression => None,
+                };
+                (edge.column, source)
+            })
+            .collect()
+    }
+
+    fn column(table: &str, column: &str) -> Option<(String, String)> {
+        Some((table.to_string(), column.to_string()))
+    }
+
+    #[test]
+    fn test_cte_lineage() {
+        assert_eq!(
+            lineage(
+                "with active as (select id, lower(email) as email from public.users where active)
+select a.id as user_id, a.email, o.total, 1 as one
+from active a join orders o on o.user_id = a.id;"
+            ),
+            vec![
+                ("user_id".to_string(), column("public.users", "id")),
+                ("email".to_string(), column("public.users", "email")),
+                ("total".to_string(), column("orders", "total")),
+                ("one".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recursive_cte_and_view_lineage() {
+        assert_eq!(
+            lineage(
+                "create view tree (node) as with recursive t (n) as (
+    select id from nodes where parent_id is null
+    union all
+    select c.id from nodes c join t on c.parent_id = t.n
+) select n from t;"
+            ),
+            vec![("node".to_string(), column("nodes", "id"))]
+        );
+    }
+}
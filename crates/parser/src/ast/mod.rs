@@ -0,0 +1,232 @@
+//! Typed access to the details of statements.
+//!
+//! The cst only knows the kind and the range of every node. To access the details of a
+//! statement, e.g. whether a select is ordered, the text of the statement node is parsed with
+//! pg_query.rs again. The resulting ast nodes are mapped back onto the cst by their position,
+//! which is the same position that was used to place them while building the cst.
+
+mod aggregate;
+mod case;
+mod category;
+mod clause;
+mod collation;
+mod copy;
+mod create_table;
+mod dml;
+mod from;
+mod generated;
+mod index;
+mod lateral;
+mod lineage;
+pub(crate) mod name;
+mod prepared;
+mod select;
+mod set_operation;
+mod subquery;
+mod tablesample;
+mod target;
+mod transaction;
+mod types;
+mod view;
+mod window;
+
+use cstree::text::{TextRange, TextSize};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::pg_query_utils::get_position_for_pg_query_node;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+pub use aggregate::{aggregates, AggregateCall};
+pub use case::{case_expressions, CaseArm, CaseExpr};
+pub use category::{is_idempotent_ddl, statement_category, StatementCategory};
+pub use clause::{clause_context, ClauseContext};
+pub use collation::collations;
+pub use copy::{copy_statement, CopyDirection, CopySource, CopyStmt, CopyTarget};
+pub(crate) use create_table::qualified_name;
+pub use create_table::{
+    column_definitions, foreign_key_actions, table_constraints, ColumnDefinition, ConstraintKind,
+    FkAction, MatchType, ReferentialAction, TableConstraint,
+};
+pub(crate) use dml::values_row_extents;
+pub use dml::{insert_values, returning_clause, values_rows};
+pub use from::{from_items, FromItem, FromItemKind};
+pub use generated::{generated_columns, GeneratedColumn, GeneratedKind};
+pub use index::{index_statement, IndexElement, IndexStmt};
+pub use lateral::lateral_joins;
+pub use lineage::{column_lineage, LineageEdge, LineageSource};
+pub use prepared::{
+    deallocate_statement, execute_statement, prepare_statement, DeallocateStmt, ExecuteStmt,
+    PrepareStmt,
+};
+pub(crate) use select::selects_with_parent;
+pub use select::{
+    distinct, having, limit, limit_clause, order_by, DistinctClause, LimitClause, SelectStmt,
+};
+pub use set_operation::{all_set_operations, set_operations, SetOp, SetOpKind, SetOperand};
+pub(crate) use subquery::{nested_queries, query_range};
+pub use subquery::{subqueries, Subquery, SubqueryKind};
+pub use tablesample::{tablesample, TableSample};
+pub use target::{select_targets, SelectTarget};
+pub use transaction::{transaction_blocks, TransactionBlock};
+pub(crate) use types::type_names;
+pub use types::type_references;
+pub use view::{view_statement, ViewStmt};
+pub use window::{window_frames, FrameBound, FrameMode, WindowFrame};
+
+/// Parses the text of the statement node `stmt` with pg_query.rs and returns the ast node of the statement
+///
+/// Returns `None` if the statement contains syntax errors.
+pub(crate) fn parse_pg_query_stmt(stmt: &SyntaxNode) -> Option<NodeEnum> {
+    let text = stmt.try_resolved()?.text().to_string();
+    pg_query::parse(&text)
+        .ok()?
+        .protobuf
+        .stmts
+        .into_iter()
+        .next()?
+        .stmt?
+        .node
+}
+
+/// Converts a pg_query `location` within the statement node `stmt` into an offset in the source
+pub(crate) fn location_to_offset(stmt: &SyntaxNode, location: i32) -> Option<TextSize> {
+    let location = u32::try_from(location).ok()?;
+    Some(stmt.text_range().start() + TextSize::from(location))
+}
+
+/// Returns the cst node of an ast `node` of the statement node `stmt`
+///
+/// If multiple nodes of the same kind start at the same position, the outermost is returned.
+pub(crate) fn syntax_node_for<'a>(stmt: &'a SyntaxNode, node: &NodeRef) -> Option<&'a SyntaxNode> {
+    let offset = location_to_offset(stmt, get_position_for_pg_query_node(node))?;
+    let kind = SyntaxKind::from_pg_query_node(node);
+    stmt.descendants()
+        .find(|n| n.kind() == kind && n.text_range().start() == offset)
+}
+
+/// Returns the token of the statement node `stmt` that starts at the pg_query `location`
+pub(crate) fn token_at(stmt: &SyntaxNode, location: i32) -> Option<&SyntaxToken> {
+    let offset = location_to_offset(stmt, location)?;
+    stmt.descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .find(|t| t.text_range().start() == offset && !t.kind().is_trivia())
+}
+
+/// Returns the tokens of the node `node` without trivia, in the order of the source
+pub(crate) fn non_trivia_tokens(node: &SyntaxNode) -> Vec<&SyntaxToken> {
+    node.descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .filter(|t| !t.kind().is_trivia())
+        .collect()
+}
+
+/// Returns the range of the constant of the statement node `stmt` at the pg_query `location`
+///
+/// A negative number is positioned at its sign, so its range covers the sign and the number.
+pub(crate) fn constant_range(stmt: &SyntaxNode, location: i32) -> Option<TextRange> {
+    let offset = location_to_offset(stmt, location)?;
+    let mut tokens = non_trivia_tokens(stmt)
+        .into_iter()
+        .skip_while(|t| t.text_range().start() < offset);
+    let first = tokens.next()?;
+    if first.kind() != SyntaxKind::ArithmeticOp {
+        return Some(first.text_range());
+    }
+    Some(first.text_range().cover(tokens.next()?.text_range()))
+}
+
+/// Returns the last token of `kind` in `stmt` that starts before `offset`
+///
+/// Used to find the keywords of a clause, which are not part of the ast.
+pub(crate) fn token_before(
+    stmt: &SyntaxNode,
+    kind: SyntaxKind,
+    offset: TextSize,
+) -> Option<&SyntaxToken> {
+    stmt.descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .filter(|t| t.kind() == kind && t.text_range().start() < offset)
+        .last()
+}
+
+/// Returns the name token of the possibly qualified name that starts at `offset` in `stmt`
+///
+/// For `schema.table`, this is the token of `table`.
+pub(crate) fn name_token_at(stmt: &SyntaxNode, offset: TextSize) -> Option<&SyntaxToken> {
+    let tokens = stmt
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .filter(|t| !t.kind().is_trivia() && t.text_range().start() >= offset)
+        .collect::<Vec<_>>();
+    let mut idx = 0;
+    while tokens.get(idx + 1).map(|t| t.kind()) == Some(SyntaxKind::Ascii46)
+        && idx + 2 < tokens.len()
+    {
+        idx += 2;
+    }
+    tokens.get(idx).copied()
+}
+
+/// Returns the index of the parenthesis that closes the one at `open` in `tokens`
+pub(crate) fn closing_parenthesis(tokens: &[&SyntaxToken], open: usize) -> Option<usize> {
+    if tokens.get(open)?.kind() != SyntaxKind::Ascii40 {
+        return None;
+    }
+    let mut depth = 0;
+    for (idx, token) in tokens.iter().enumerate().skip(open) {
+        match token.kind() {
+            SyntaxKind::Ascii40 => depth += 1,
+            SyntaxKind::Ascii41 => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the depth of parentheses at the token `idx` of `tokens`
+pub(crate) fn depth_at(tokens: &[&SyntaxToken], idx: usize) -> i32 {
+    tokens[..idx].iter().fold(0, |depth, t| match t.kind() {
+        SyntaxKind::Ascii40 => depth + 1,
+        SyntaxKind::Ascii41 => depth - 1,
+        _ => depth,
+    })
+}
+
+/// Returns the ranges of the items of the comma separated list `tokens`
+pub(crate) fn split_list(tokens: &[&SyntaxToken]) -> Vec<TextRange> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    for idx in 0..=tokens.len() {
+        if idx == tokens.len()
+            || (tokens[idx].kind() == SyntaxKind::Ascii44 && depth_at(tokens, idx) == 0)
+        {
+            items.extend(tokens_range(&tokens[start..idx]));
+            start = idx + 1;
+        }
+    }
+    items
+}
+
+/// Returns the range from the first to the last token of `tokens`
+pub(crate) fn tokens_range(tokens: &[&SyntaxToken]) -> Option<TextRange> {
+    Some(
+        tokens
+            .first()?
+            .text_range()
+            .cover(tokens.last()?.text_range()),
+    )
+}
+
+/// Returns the statement nodes of `root`, which is either a source file or a single statement
+pub(crate) fn statements(root: &SyntaxNode) -> Vec<&SyntaxNode> {
+    match root.kind() {
+        SyntaxKind::SourceFile => root.children().collect(),
+        _ => vec![root],
+    }
+}
@@ -0,0 +1,52 @@
+//! Helpers for identifiers, which are either quoted (`"MyTable"`) or unquoted (`my_table`).
+
+/// Whether the identifier `text` is quoted
+pub(crate) fn is_quoted(text: &str) -> bool {
+    text.len() >= 2 && text.starts_with('"') && text.ends_with('"')
+}
+
+/// Returns the name of the identifier `text` as written, without the quotes of a quoted identifier
+pub(crate) fn unquote(text: &str) -> String {
+    if is_quoted(text) {
+        text[1..text.len() - 1].replace("\"\"", "\"")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Returns the name that the identifier `text` refers to, which is folded to lowercase if it is
+/// unquoted
+pub(crate) fn resolve(text: &str) -> String {
+    if is_quoted(text) {
+        unquote(text)
+    } else {
+        text.to_lowercase()
+    }
+}
+
+/// Whether `name` contains characters that are only valid within a quoted identifier
+pub(crate) fn needs_quoting(name: &str) -> bool {
+    match name.chars().next() {
+        None => true,
+        Some(c) if !(c.is_alphabetic() || c == '_') => true,
+        _ => name
+            .chars()
+            .any(|c| !(c.is_alphanumeric() || c == '_' || c == '$')),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(unquote("\"say \"\"hi\"\"\""), "say \"hi\"");
+        assert_eq!(resolve("\"MyTable\""), "MyTable");
+        assert_eq!(resolve("MyTable"), "mytable");
+
+        assert!(!needs_quoting("my_table$1"));
+        assert!(needs_quoting("my table"));
+        assert!(needs_quoting("1table"));
+    }
+}
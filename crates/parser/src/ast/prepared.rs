@@ -0,0 +1,163 @@
+use cstree::text::TextRange;
+use pg_query::NodeEnum;
+
+use crate::ast::type_references;
+use crate::ast::{
+    closing_parenthesis, depth_at, non_trivia_tokens, parse_pg_query_stmt, split_list, tokens_range,
+};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// A `PREPARE name (types) AS query` statement
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrepareStmt {
+    pub name: String,
+    pub name_range: TextRange,
+    /// The names of the types of the parameters, as returned by `type_references`, e.g.
+    /// `pg_catalog.int4`
+    pub parameter_types: Vec<String>,
+    /// The range of the prepared query
+    pub query: TextRange,
+}
+
+/// An `EXECUTE name (params)` statement
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExecuteStmt {
+    pub name: String,
+    pub name_range: TextRange,
+    /// The ranges of the values of the parameters
+    pub params: Vec<TextRange>,
+}
+
+/// A `DEALLOCATE name` or `DEALLOCATE ALL` statement
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DeallocateStmt {
+    /// The name of the prepared statement and its range, or `None` for `DEALLOCATE ALL`
+    pub name: Option<(String, TextRange)>,
+}
+
+/// Returns the details of the `PREPARE` statement `stmt`
+pub fn prepare_statement(stmt: &SyntaxNode) -> Option<PrepareStmt> {
+    let prepare = match parse_pg_query_stmt(stmt)? {
+        NodeEnum::PrepareStmt(prepare) => prepare,
+        _ => return None,
+    };
+    let tokens = tokens_of(stmt);
+    let as_keyword = (0..tokens.len())
+        .find(|idx| tokens[*idx].kind() == SyntaxKind::As && depth_at(&tokens, *idx) == 0)?;
+    Some(PrepareStmt {
+        name: prepare.name,
+        name_range: tokens.get(1)?.text_range(),
+        // the types of the parameters precede the types of the query
+        parameter_types: type_references(stmt)
+            .into_iter()
+            .take(prepare.argtypes.len())
+            .map(|(name, _)| name)
+            .collect(),
+        query: tokens_range(&tokens[as_keyword + 1..])?,
+    })
+}
+
+/// Returns the details of the `EXECUTE` statement `stmt`
+pub fn execute_statement(stmt: &SyntaxNode) -> Option<ExecuteStmt> {
+    let execute = match parse_pg_query_stmt(stmt)? {
+        NodeEnum::ExecuteStmt(execute) => execute,
+        _ => return None,
+    };
+    let tokens = tokens_of(stmt);
+    let params = match closing_parenthesis(&tokens, 2) {
+        Some(close) => split_list(&tokens[3..close]),
+        None => Vec::new(),
+    };
+    Some(ExecuteStmt {
+        name: execute.name,
+        name_range: tokens.get(1)?.text_range(),
+        params,
+    })
+}
+
+/// Returns the details of the `DEALLOCATE` statement `stmt`
+pub fn deallocate_statement(stmt: &SyntaxNode) -> Option<DeallocateStmt> {
+    let deallocate = match parse_pg_query_stmt(stmt)? {
+        NodeEnum::DeallocateStmt(deallocate) => deallocate,
+        _ => return None,
+    };
+    // `DEALLOCATE ALL` has no name
+    if deallocate.name.is_empty() {
+        return Some(DeallocateStmt { name: None });
+    }
+    let tokens = tokens_of(stmt);
+    let name = tokens[1..]
+        .iter()
+        .find(|t| t.kind() != SyntaxKind::Prepare)?;
+    Some(DeallocateStmt {
+        name: Some((deallocate.name, name.text_range())),
+    })
+}
+
+/// Returns the tokens of `stmt` without trivia and the terminating `;`
+fn tokens_of(stmt: &SyntaxNode) -> Vec<&SyntaxToken> {
+    let mut tokens = non_trivia_tokens(stmt);
+    if tokens.last().map(|t| t.kind()) == Some(SyntaxKind::Ascii59) {
+        tokens.pop();
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use cstree::syntax::ResolvedNode;
+
+    use super::*;
+    use crate::parser::Parser;
+
+    fn parse(input: &str) -> ResolvedNode<SyntaxKind> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        parser.finish().cst
+    }
+
+    #[test]
+    fn test_prepare_and_execute() {
+        let input =
+            "prepare find_user (int, text) as select * from users where id = $1 and name = $2;";
+        let prepare = prepare_statement(&parse(input)).unwrap();
+        assert_eq!(prepare.name, "find_user");
+        assert_eq!(&input[prepare.name_range], "find_user");
+        assert_eq!(
+            prepare.parameter_types,
+            vec!["pg_catalog.int4".to_string(), "text".to_string()]
+        );
+        assert_eq!(
+            &input[prepare.query],
+            "select * from users where id = $1 and name = $2"
+        );
+
+        let input = "execute find_user (1, lower('A'));";
+        let execute = execute_statement(&parse(input)).unwrap();
+        assert_eq!(execute.name, "find_user");
+        assert_eq!(
+            execute
+                .params
+                .iter()
+                .map(|r| &input[*r])
+                .collect::<Vec<_>>(),
+            vec!["1", "lower('A')"]
+        );
+    }
+
+    #[test]
+    fn test_deallocate() {
+        let input = "deallocate prepare find_user;";
+        let deallocate = deallocate_statement(&parse(input)).unwrap();
+        assert_eq!(
+            deallocate.name.map(|(name, range)| (name, &input[range])),
+            Some(("find_user".to_string(), "find_user"))
+        );
+
+        assert_eq!(
+            deallocate_statement(&parse("deallocate all;")),
+            Some(DeallocateStmt { name: None })
+        );
+    }
+}
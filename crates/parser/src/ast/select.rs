@@ -0,0 +1,390 @@
+use cstree::text::TextRange;
+use pg_query::{protobuf, NodeEnum, NodeRef};
+
+use crate::ast::{
+    depth_at, location_to_offset, non_trivia_tokens, parse_pg_query_stmt, syntax_node_for,
+    token_at, token_before,
+};
+use crate::pg_query_utils::{get_children, get_nodes, get_position_for_pg_query_node};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// A select within a statement
+///
+/// Wraps the cst node of the statement, together with the pg_query ast of the select. The select
+/// itself may be nested within the statement, e.g. as a subquery.
+#[derive(Debug, Clone)]
+pub struct SelectStmt {
+    syntax: SyntaxNode,
+    ast: protobuf::SelectStmt,
+}
+
+impl SelectStmt {
+    /// Casts the statement node `stmt` to a `SelectStmt`, if it is a select without syntax errors
+    pub fn cast(stmt: &SyntaxNode) -> Option<Self> {
+        match parse_pg_query_stmt(stmt)? {
+            NodeEnum::SelectStmt(ast) => Some(Self::new(stmt, *ast)),
+            _ => None,
+        }
+    }
+
+    /// Creates a `SelectStmt` for the select `ast`, which is part of the statement node `stmt`
+    pub fn new(stmt: &SyntaxNode, ast: protobuf::SelectStmt) -> Self {
+        Self {
+            syntax: stmt.clone(),
+            ast,
+        }
+    }
+
+    /// The cst node of the statement that contains the select
+    pub fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+
+    /// The pg_query ast of the select
+    pub fn ast(&self) -> &protobuf::SelectStmt {
+        &self.ast
+    }
+}
+
+/// Returns the `SortBy` nodes of the `ORDER BY` clause of `select`
+pub fn order_by(select: &SelectStmt) -> Vec<&SyntaxNode> {
+    select
+        .ast
+        .sort_clause
+        .iter()
+        .filter_map(|n| n.node.as_ref())
+        .filter_map(|n| syntax_node_for(&select.syntax, &n.to_ref()))
+        .collect()
+}
+
+/// The `DISTINCT` clause of a select
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DistinctClause<'a> {
+    /// `DISTINCT`, which removes all duplicate rows
+    Distinct,
+    /// `DISTINCT ON (...)` with the nodes of its expressions, which keeps the first row of every
+    /// set of rows that are equal on the expressions
+    DistinctOn(Vec<&'a SyntaxNode>),
+}
+
+/// Returns the `DISTINCT` clause of `select`, if any
+pub fn distinct(select: &SelectStmt) -> Option<DistinctClause<'_>> {
+    let clause = &select.ast.distinct_clause;
+    if clause.is_empty() {
+        return None;
+    }
+    // a plain `DISTINCT` is a list with a single empty node
+    if clause.iter().all(|n| n.node.is_none()) {
+        return Some(DistinctClause::Distinct);
+    }
+    Some(DistinctClause::DistinctOn(
+        clause
+            .iter()
+            .filter_map(|n| n.node.as_ref())
+            .filter_map(|n| syntax_node_for(&select.syntax, &n.to_ref()))
+            .collect(),
+    ))
+}
+
+/// Returns the node of the condition of the `HAVING` clause of `select`, if any
+pub fn having(select: &SelectStmt) -> Option<&SyntaxNode> {
+    let condition = select.ast.having_clause.as_ref()?.node.as_ref()?;
+    syntax_node_for(&select.syntax, &condition.to_ref())
+}
+
+/// Returns the range of the `LIMIT` or `FETCH` clause of `select`, from the keyword to the first
+/// token of the count
+///
+/// `LIMIT ALL` does not limit anything and is ignored. When the count of a `FETCH` is omitted, as
+/// in `FETCH FIRST ROW ONLY`, the range covers the keywords from `FETCH` to `ONLY` or `TIES`.
+pub fn limit(select: &SelectStmt) -> Option<TextRange> {
+    let count = select.ast.limit_count.as_ref()?.node.as_ref()?;
+    if let NodeEnum::AConst(c) = count {
+        if matches!(
+            c.val.as_ref().and_then(|v| v.node.as_ref()),
+            Some(NodeEnum::Null(_))
+        ) {
+            return None;
+        }
+    }
+
+    let position = get_position_for_pg_query_node(&count.to_ref());
+    if position < 0 {
+        // pg_query synthesizes the count of `FETCH FIRST ROW ONLY` without a location
+        return fetch_keywords(select);
+    }
+    let offset = location_to_offset(&select.syntax, position)?;
+    let count_range = select
+        .syntax
+        .token_at_offset(offset)
+        .right_biased()?
+        .text_range();
+    let keyword = [SyntaxKind::Limit, SyntaxKind::Fetch]
+        .into_iter()
+        .filter_map(|kind| token_before(&select.syntax, kind, offset))
+        .max_by_key(|t| t.text_range().start());
+    match keyword {
+        Some(keyword) => Some(keyword.text_range().cover(count_range)),
+        None => Some(count_range),
+    }
+}
+
+/// Returns the range from the `FETCH` keyword of `select` to the following `ONLY` or `TIES`
+///
+/// The keyword is the first `FETCH` after the last positioned node of the select that is at the
+/// parenthesis depth of the select, so that a `FETCH` of a subquery is skipped.
+fn fetch_keywords(select: &SelectStmt) -> Option<TextRange> {
+    let positions = get_nodes(NodeRef::SelectStmt(&select.ast))
+        .iter()
+        .map(|(n, _)| get_position_for_pg_query_node(n))
+        .filter(|p| *p >= 0)
+        .collect::<Vec<_>>();
+    let first = location_to_offset(&select.syntax, *positions.iter().min()?)?;
+    let last = location_to_offset(&select.syntax, *positions.iter().max()?)?;
+    let tokens = non_trivia_tokens(&select.syntax);
+    let depth = depth_at(
+        &tokens,
+        tokens
+            .iter()
+            .position(|t| t.text_range().start() >= first)?,
+    );
+    let fetch = (0..tokens.len()).find(|&idx| {
+        tokens[idx].kind() == SyntaxKind::Fetch
+            && tokens[idx].text_range().start() > last
+            && depth_at(&tokens, idx) == depth
+    })?;
+    let end = tokens[fetch..]
+        .iter()
+        .find(|t| matches!(t.kind(), SyntaxKind::Only | SyntaxKind::Ties))?;
+    Some(tokens[fetch].text_range().cover(end.text_range()))
+}
+
+/// The `LIMIT` and `OFFSET` clauses of a select, which are the same for the standard syntax
+/// `OFFSET n ROWS FETCH FIRST m ROWS ONLY`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LimitClause {
+    /// The text of the maximum number of rows, or `None` if the rows are not limited, e.g. by
+    /// `LIMIT ALL`
+    ///
+    /// Integers are normalized, so `FETCH FIRST ROW ONLY` has the count `1`.
+    pub count: Option<String>,
+    /// The text of the number of skipped rows
+    pub offset: Option<String>,
+    /// Whether rows that are equal to the last row in the order are returned as well, i.e.
+    /// `FETCH FIRST ... WITH TIES`
+    pub with_ties: bool,
+}
+
+/// Returns the `LIMIT`, `FETCH` and `OFFSET` clauses of `select`, or `None` if it has none of them
+pub fn limit_clause(select: &SelectStmt) -> Option<LimitClause> {
+    let count = select
+        .ast
+        .limit_count
+        .as_deref()
+        .and_then(|n| n.node.as_ref())
+        .filter(|n| {
+            !matches!(n, NodeEnum::AConst(c)
+                if matches!(c.val.as_ref().and_then(|v| v.node.as_ref()), Some(NodeEnum::Null(_))))
+        });
+    let offset = select
+        .ast
+        .limit_offset
+        .as_deref()
+        .and_then(|n| n.node.as_ref());
+    if count.is_none() && offset.is_none() {
+        return None;
+    }
+    Some(LimitClause {
+        count: count.and_then(|n| expression_text(select, n)),
+        offset: offset.and_then(|n| expression_text(select, n)),
+        with_ties: select.ast.limit_option == protobuf::LimitOption::WithTies as i32,
+    })
+}
+
+/// Returns the text of the expression `node` of `select`, or the value of an integer constant
+fn expression_text(select: &SelectStmt, node: &NodeEnum) -> Option<String> {
+    if let NodeEnum::AConst(c) = node {
+        if let Some(NodeEnum::Integer(i)) = c.val.as_ref().and_then(|v| v.node.as_ref()) {
+            return Some(i.ival.to_string());
+        }
+    }
+    let positions = get_nodes(node.to_ref())
+        .iter()
+        .map(|(n, _)| get_position_for_pg_query_node(n))
+        .filter(|p| *p >= 0)
+        .collect::<Vec<_>>();
+    let first = token_at(&select.syntax, *positions.iter().min()?)?;
+    let last = token_at(&select.syntax, *positions.iter().max()?)?;
+    let tokens = non_trivia_tokens(&select.syntax);
+    let start = tokens.iter().position(|t| *t == first)?;
+    let mut end = tokens.iter().position(|t| *t == last)?;
+    // the last node of a function call or a subquery is followed by closing parentheses
+    while depth_at(&tokens, end + 1) > depth_at(&tokens, start) {
+        end += 1;
+        if end >= tokens.len() {
+            return None;
+        }
+    }
+    let range = tokens[start].text_range().cover(tokens[end].text_range())
+        - select.syntax.text_range().start();
+    Some(select.syntax.resolved().text().to_string()[range].to_string())
+}
+
+/// Returns all selects of the statement node `stmt`, including nested ones, together with the
+/// select they are directly nested in
+pub(crate) fn selects_with_parent(stmt: &SyntaxNode) -> Vec<(SelectStmt, Option<SelectStmt>)> {
+    fn collect(
+        stmt: &SyntaxNode,
+        node: NodeRef,
+        parent: Option<&SelectStmt>,
+        selects: &mut Vec<(SelectStmt, Option<SelectStmt>)>,
+    ) {
+        let select = match node {
+            NodeRef::SelectStmt(s) => {
+                let select = SelectStmt::new(stmt, s.clone());
+                selects.push((select.clone(), parent.cloned()));
+                Some(select)
+            }
+            _ => None,
+        };
+        let parent = select.as_ref().or(parent);
+        for child in get_children(node) {
+            collect(stmt, child, parent, selects);
+        }
+    }
+
+    let mut selects = Vec::new();
+    if let Some(ast) = parse_pg_query_stmt(stmt) {
+        collect(stmt, ast.to_ref(), None, &mut selects);
+    }
+    selects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_order_by_and_limit() {
+        let input = "select * from t order by id, name desc limit 10;";
+
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+
+        let select = SelectStmt::cast(&parsed.cst).unwrap();
+
+        let sort_items = order_by(&select)
+            .iter()
+            .map(|n| n.resolved().text().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(sort_items, vec!["id", "name desc"]);
+
+        let range = limit(&select).unwrap();
+        assert_eq!(&input[range], "limit 10");
+        assert!(having(&select).is_none());
+    }
+
+    #[test]
+    fn test_limit_clause() {
+        let clause = |input: &str| {
+            let mut parser = Parser::new();
+            parser.parse_statement(input, None);
+            let parsed = parser.finish();
+            assert!(parsed.errors.is_empty());
+            let select = SelectStmt::cast(&parsed.cst).unwrap();
+            (
+                limit_clause(&select),
+                limit(&select).map(|r| input[r].to_string()),
+            )
+        };
+
+        let expected = LimitClause {
+            count: Some("10".to_string()),
+            offset: Some("5".to_string()),
+            with_ties: false,
+        };
+        assert_eq!(
+            clause("select * from t limit 10 offset 5;"),
+            (Some(expected.clone()), Some("limit 10".to_string()))
+        );
+        assert_eq!(
+            clause("select * from t offset 5 rows fetch first 10 rows only;"),
+            (Some(expected), Some("fetch first 10".to_string()))
+        );
+        assert_eq!(
+            clause("select * from t order by a fetch next ($1 + 1) rows with ties;").0,
+            Some(LimitClause {
+                count: Some("$1 + 1".to_string()),
+                offset: None,
+                with_ties: true,
+            })
+        );
+        assert_eq!(
+            clause("select * from t limit all offset lower(x);").0,
+            Some(LimitClause {
+                count: None,
+                offset: Some("lower(x)".to_string()),
+                with_ties: false,
+            })
+        );
+        assert_eq!(
+            clause("select * from t fetch first row only;"),
+            (
+                Some(LimitClause {
+                    count: Some("1".to_string()),
+                    offset: None,
+                    with_ties: false,
+                }),
+                Some("fetch first row only".to_string())
+            )
+        );
+        assert_eq!(clause("select * from t;").0, None);
+    }
+
+    #[test]
+    fn test_having() {
+        let mut parser = Parser::new();
+        parser.parse_statement(
+            "select kind from t group by kind having count(*) > 1;",
+            None,
+        );
+        let parsed = parser.finish();
+
+        let select = SelectStmt::cast(&parsed.cst).unwrap();
+
+        assert_eq!(
+            having(&select).unwrap().resolved().text().to_string(),
+            "count(*) > 1"
+        );
+    }
+
+    #[test]
+    fn test_distinct() {
+        let distinct_texts = |input: &str| {
+            let mut parser = Parser::new();
+            parser.parse_statement(input, None);
+            let parsed = parser.finish();
+            let select = SelectStmt::cast(&parsed.cst).unwrap();
+            distinct(&select).map(|d| match d {
+                DistinctClause::Distinct => Vec::new(),
+                DistinctClause::DistinctOn(exprs) => exprs
+                    .iter()
+                    .map(|n| n.resolved().text().to_string())
+                    .collect(),
+            })
+        };
+
+        assert_eq!(
+            distinct_texts("select distinct a from t;"),
+            Some(Vec::new())
+        );
+        assert_eq!(
+            distinct_texts("select distinct on (a) b from t;"),
+            Some(vec!["a".to_string()])
+        );
+        assert_eq!(distinct_texts("select a from t;"), None);
+    }
+}
@@ -0,0 +1,343 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::{self, SetOperation};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{
+    closing_parenthesis, depth_at, location_to_offset, non_trivia_tokens, parse_pg_query_stmt,
+    query_range, tokens_range, SelectStmt,
+};
+use crate::pg_query_utils::{get_nodes, get_position_for_pg_query_node};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// The operator of a `SetOp`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SetOpKind {
+    Union,
+    Intersect,
+    Except,
+}
+
+/// A `UNION`, `INTERSECT` or `EXCEPT` of two queries
+#[derive(Debug, Clone)]
+pub struct SetOp {
+    pub kind: SetOpKind,
+    /// Whether duplicates are kept, i.e. `ALL`
+    pub all: bool,
+    pub left: Box<SetOperand>,
+    pub right: Box<SetOperand>,
+    /// The range of the operator, e.g. `UNION ALL`
+    pub operator: TextRange,
+    /// The range of both operands and the operator, without the `ORDER BY` and `LIMIT` clauses
+    /// that apply to the result
+    pub range: TextRange,
+}
+
+/// An operand of a `SetOp`
+#[derive(Debug, Clone)]
+pub enum SetOperand {
+    /// A select, with its range without enclosing parentheses
+    Select(Box<SelectStmt>, TextRange),
+    /// A nested set operation, e.g. the `UNION` of `(a UNION b) EXCEPT c`
+    SetOp(SetOp),
+}
+
+impl SetOperand {
+    /// The range of the operand, without enclosing parentheses
+    pub fn range(&self) -> TextRange {
+        match self {
+            SetOperand::Select(_, range) => *range,
+            SetOperand::SetOp(op) => op.range,
+        }
+    }
+}
+
+/// Returns the set operation of the select `stmt`, if it is a set operation
+///
+/// Nested set operations are operands of the returned one, following the precedence of the
+/// operators, e.g. `a UNION b INTERSECT c` is the `UNION` of `a` and `b INTERSECT c`.
+pub fn set_operations(stmt: &SyntaxNode) -> Option<SetOp> {
+    let select = match parse_pg_query_stmt(stmt)? {
+        NodeEnum::SelectStmt(select) => select,
+        _ => return None,
+    };
+    let tokens = non_trivia_tokens(stmt);
+    query_set_operation(stmt, &tokens, &select)
+}
+
+/// Returns the set operations of all queries of the statement node `stmt`, ordered by the
+/// position of their operator
+///
+/// Unlike `set_operations`, these include the set operations of subqueries, common table
+/// expressions and the query of an `INSERT`, e.g. the `UNION` of
+/// `select * from (select 1 union select 2) x`.
+pub fn all_set_operations(stmt: &SyntaxNode) -> Vec<SetOp> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+    let tokens = non_trivia_tokens(stmt);
+
+    let mut ops = Vec::new();
+    match &ast {
+        NodeEnum::SelectStmt(select) => ops.extend(query_set_operation(stmt, &tokens, select)),
+        NodeEnum::InsertStmt(insert) => {
+            if let Some(NodeEnum::SelectStmt(select)) =
+                insert.select_stmt.as_ref().and_then(|n| n.node.as_ref())
+            {
+                ops.extend(
+                    insert_query(&tokens)
+                        .and_then(|query| query_set_operation(stmt, query, select)),
+                );
+            }
+        }
+        _ => {}
+    }
+    for (node, _) in get_nodes(ast.to_ref()) {
+        let query = match node {
+            NodeRef::SubLink(s) => s.subselect.as_deref(),
+            NodeRef::RangeSubselect(s) => s.subquery.as_deref(),
+            NodeRef::CommonTableExpr(c) => c.ctequery.as_deref(),
+            _ => None,
+        };
+        let select = match query.and_then(|q| q.node.as_ref()) {
+            Some(NodeEnum::SelectStmt(select)) if is_set_operation(select) => select,
+            _ => continue,
+        };
+        // the tokens within the parentheses of the query
+        let range = query.and_then(|q| query_range(stmt, &tokens, q));
+        let bounds = range.and_then(|range| {
+            let start = tokens
+                .iter()
+                .position(|t| t.text_range().start() == range.start())?;
+            let end = tokens
+                .iter()
+                .position(|t| t.text_range().end() == range.end())?;
+            Some((start + 1, end))
+        });
+        if let Some((start, end)) = bounds.filter(|(start, end)| start < end) {
+            ops.extend(query_set_operation(stmt, &tokens[start..end], select));
+        }
+    }
+    ops.sort_by_key(|op| op.operator.start());
+    ops
+}
+
+/// Returns the tokens of the query of an `INSERT` statement, whose non-trivia tokens are `tokens`
+///
+/// The query starts at its first keyword or parenthesis after the target table and its columns,
+/// and ends before `ON CONFLICT` or `RETURNING` clauses.
+fn insert_query<'a, 'b>(tokens: &'b [&'a SyntaxToken]) -> Option<&'b [&'a SyntaxToken]> {
+    let is_query_start = |idx: usize| match tokens[idx].kind() {
+        SyntaxKind::Select | SyntaxKind::Values | SyntaxKind::Table | SyntaxKind::With => true,
+        SyntaxKind::Ascii40 => matches!(
+            tokens.get(idx + 1).map(|t| t.kind()),
+            Some(SyntaxKind::Select | SyntaxKind::With | SyntaxKind::Ascii40)
+        ),
+        _ => false,
+    };
+    let into = tokens.iter().position(|t| t.kind() == SyntaxKind::Into)?;
+    let start =
+        (into..tokens.len()).find(|idx| depth_at(tokens, *idx) == 0 && is_query_start(*idx))?;
+    let end = (start..tokens.len())
+        .find(|idx| {
+            depth_at(tokens, *idx) == 0
+                && matches!(
+                    tokens[*idx].kind(),
+                    SyntaxKind::On | SyntaxKind::Returning | SyntaxKind::Ascii59
+                )
+        })
+        .unwrap_or(tokens.len());
+    Some(&tokens[start..end])
+}
+
+/// Returns the set operation of `select`, a query that spans `tokens` without enclosing
+/// parentheses
+fn query_set_operation(
+    stmt: &SyntaxNode,
+    tokens: &[&SyntaxToken],
+    select: &protobuf::SelectStmt,
+) -> Option<SetOp> {
+    if !is_set_operation(select) {
+        return None;
+    }
+
+    // the `WITH` clause ends at the closing parenthesis before the first operand
+    let start = match select.with_clause {
+        Some(_) => (1..tokens.len()).find(|idx| {
+            depth_at(tokens, *idx) == 0
+                && tokens[idx - 1].kind() == SyntaxKind::Ascii41
+                && matches!(
+                    tokens[*idx].kind(),
+                    SyntaxKind::Select
+                        | SyntaxKind::Values
+                        | SyntaxKind::Table
+                        | SyntaxKind::Ascii40
+                )
+        })?,
+        None => 0,
+    };
+    // the clauses that apply to the result, which operands only have within parentheses
+    let end = (start..tokens.len())
+        .find(|idx| {
+            depth_at(tokens, *idx) == 0
+                && matches!(
+                    tokens[*idx].kind(),
+                    SyntaxKind::Order
+                        | SyntaxKind::Limit
+                        | SyntaxKind::Offset
+                        | SyntaxKind::Fetch
+                        | SyntaxKind::Ascii59
+                )
+        })
+        .unwrap_or(tokens.len());
+
+    match operand(stmt, tokens, select, start, end)? {
+        SetOperand::SetOp(op) => Some(op),
+        SetOperand::Select(..) => None,
+    }
+}
+
+/// Returns the operand for `select`, which spans `tokens[start..end]`
+///
+/// The operator of a set operation is the last one before its right operand.
+fn operand(
+    stmt: &SyntaxNode,
+    tokens: &[&SyntaxToken],
+    select: &protobuf::SelectStmt,
+    mut start: usize,
+    mut end: usize,
+) -> Option<SetOperand> {
+    while end - start >= 2 && closing_parenthesis(tokens, start) == Some(end - 1) {
+        start += 1;
+        end -= 1;
+    }
+    let range = tokens_range(&tokens[start..end])?;
+    if !is_set_operation(select) {
+        return Some(SetOperand::Select(
+            Box::new(SelectStmt::new(stmt, select.clone())),
+            range,
+        ));
+    }
+
+    let (left, right) = (select.larg.as_deref()?, select.rarg.as_deref()?);
+    let right_start = get_nodes(NodeRef::SelectStmt(right))
+        .iter()
+        .map(|(n, _)| get_position_for_pg_query_node(n))
+        .filter(|p| *p >= 0)
+        .min()
+        .and_then(|p| location_to_offset(stmt, p))?;
+    let operator = (start..end)
+        .take_while(|idx| tokens[*idx].text_range().start() < right_start)
+        .filter(|idx| {
+            matches!(
+                tokens[*idx].kind(),
+                SyntaxKind::Union | SyntaxKind::Intersect | SyntaxKind::Except
+            )
+        })
+        .last()?;
+    let operator_end = match tokens.get(operator + 1).map(|t| t.kind()) {
+        Some(SyntaxKind::All | SyntaxKind::Distinct) => operator + 2,
+        _ => operator + 1,
+    };
+
+    Some(SetOperand::SetOp(SetOp {
+        kind: match SetOperation::from_i32(select.op)? {
+            SetOperation::SetopUnion => SetOpKind::Union,
+            SetOperation::SetopIntersect => SetOpKind::Intersect,
+            SetOperation::SetopExcept => SetOpKind::Except,
+            _ => return None,
+        },
+        all: select.all,
+        left: Box::new(operand(stmt, tokens, left, start, operator)?),
+        right: Box::new(operand(stmt, tokens, right, operator_end, end)?),
+        operator: tokens_range(&tokens[operator..operator_end])?,
+        range,
+    }))
+}
+
+fn is_set_operation(select: &protobuf::SelectStmt) -> bool {
+    select.op != SetOperation::SetopNone as i32 && select.op != SetOperation::Undefined as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    /// Renders `operand` with its operators and the texts of its selects
+    fn render(input: &str, operand: &SetOperand) -> String {
+        match operand {
+            SetOperand::Select(_, range) => input[*range].to_string(),
+            SetOperand::SetOp(op) => format!(
+                "[{} <{}> {}]",
+                render(input, &op.left),
+                &input[op.operator],
+                render(input, &op.right)
+            ),
+        }
+    }
+
+    fn set_op(input: &str) -> Option<String> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let op = set_operations(&parser.finish().cst)?;
+        Some(render(input, &SetOperand::SetOp(op)))
+    }
+
+    #[test]
+    fn test_union() {
+        let input = "select a from t union select b from u;";
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let op = set_operations(&parser.finish().cst).unwrap();
+        assert_eq!(op.kind, SetOpKind::Union);
+        assert!(!op.all);
+        assert_eq!(&input[op.range], "select a from t union select b from u");
+        assert!(matches!(
+            op.left.as_ref(),
+            SetOperand::Select(select, _) if select.ast().from_clause.len() == 1
+        ));
+        assert_eq!(&input[op.right.range()], "select b from u");
+
+        assert_eq!(set_op("select a from t;"), None);
+    }
+
+    #[test]
+    fn test_nested_set_operations() {
+        assert_eq!(
+            set_op(
+                "(select a from t union all select b from u) except select c from v order by 1;"
+            )
+            .as_deref(),
+            Some("[[select a from t <union all> select b from u] <except> select c from v]")
+        );
+        assert_eq!(
+            set_op(
+                "with x as (select 1) select 1 union all select 2 intersect (select 3 limit 1);"
+            )
+            .as_deref(),
+            Some("[select 1 <union all> [select 2 <intersect> select 3 limit 1]]")
+        );
+    }
+
+    #[test]
+    fn test_all_set_operations() {
+        let input = "with x as (select 1 union select 2) insert into t (a) select a from (select 3 intersect select 4) s where a in (select 5 except select 6) union all select 7 returning a;";
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let ops = all_set_operations(&parser.finish().cst)
+            .iter()
+            .map(|op| render(input, &SetOperand::SetOp(op.clone())))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ops,
+            vec![
+                "[select 1 <union> select 2]",
+                "[select 3 <intersect> select 4]",
+                "[select 5 <except> select 6]",
+                "[select a from (select 3 intersect select 4) s where a in (select 5 except select 6) <union all> select 7]",
+            ]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use cstree::text::{TextRange, TextSize};
+use pg_query::protobuf::SubLinkType;
+use pg_query::{protobuf, NodeRef};
+
+use crate::ast::{location_to_offset, non_trivia_tokens, parse_pg_query_stmt};
+use crate::pg_query_utils::{get_nodes, get_position_for_pg_query_node};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// The syntactic role of a subquery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubqueryKind {
+    /// A subquery that is used as a single value, e.g. `(select max(id) from t)`
+    Scalar,
+    /// A subquery that is compared to a row, e.g. `(a, b) = (select ...)`
+    Row,
+    /// `expr IN (select ...)`
+    In,
+    /// `expr op ANY (select ...)`
+    Any,
+    /// `expr op ALL (select ...)`
+    All,
+    /// `EXISTS (select ...)`
+    Exists,
+    /// `ARRAY(select ...)`
+    Array,
+    /// A derived table in `FROM`, e.g. `from (select ...) s`
+    Derived,
+}
+
+/// A subquery within a statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Subquery {
+    pub kind: SubqueryKind,
+    /// The range of the subquery, including its parentheses
+    pub range: TextRange,
+}
+
+/// Returns all subqueries of the statement node `stmt`, including nested ones, ordered by their
+/// position
+pub fn subqueries(stmt: &SyntaxNode) -> Vec<Subquery> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+    let tokens = non_trivia_tokens(stmt);
+
+    let mut subqueries = get_nodes(ast.to_ref())
+        .into_iter()
+        .filter_map(|(node, _)| {
+            let (kind, subquery) = match node {
+                NodeRef::SubLink(s) => (sub_link_kind(s)?, s.subselect.as_deref()?),
+                NodeRef::RangeSubselect(s) => (SubqueryKind::Derived, s.subquery.as_deref()?),
+                _ => return None,
+            };
+            let range = query_range(stmt, &tokens, subquery)?;
+            Some(Subquery { kind, range })
+        })
+        .collect::<Vec<_>>();
+    subqueries.sort_by_key(|s| s.range.start());
+    subqueries
+}
+
+/// Returns the ranges of all queries that are nested in the statement node `stmt`, ordered by
+/// their position
+///
+/// Unlike `subqueries`, these include the queries of common table expressions. The range of a
+/// query includes its parentheses, so a query is nested in all queries whose range contains it.
+pub(crate) fn nested_queries(stmt: &SyntaxNode) -> Vec<TextRange> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+    let tokens = non_trivia_tokens(stmt);
+
+    let mut ranges = get_nodes(ast.to_ref())
+        .into_iter()
+        .filter_map(|(node, _)| {
+            let query = match node {
+                NodeRef::SubLink(s) => s.subselect.as_deref()?,
+                NodeRef::RangeSubselect(s) => s.subquery.as_deref()?,
+                NodeRef::CommonTableExpr(c) => c.ctequery.as_deref()?,
+                _ => return None,
+            };
+            query_range(stmt, &tokens, query)
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|r| r.start());
+    ranges.dedup();
+    ranges
+}
+
+/// Returns the range of the parenthesized `query` of the statement node `stmt`, whose non-trivia
+/// tokens are `tokens`
+pub(crate) fn query_range(
+    stmt: &SyntaxNode,
+    tokens: &[&SyntaxToken],
+    query: &protobuf::Node,
+) -> Option<TextRange> {
+    let position = get_nodes(query.node.as_ref()?.to_ref())
+        .iter()
+        .map(|(n, _)| get_position_for_pg_query_node(n))
+        .filter(|p| *p >= 0)
+        .min()?;
+    parenthesized_range(tokens, location_to_offset(stmt, position)?)
+}
+
+fn sub_link_kind(sub_link: &protobuf::SubLink) -> Option<SubqueryKind> {
+    match SubLinkType::from_i32(sub_link.sub_link_type)? {
+        SubLinkType::ExprSublink => Some(SubqueryKind::Scalar),
+        SubLinkType::RowcompareSublink | SubLinkType::MultiexprSublink => Some(SubqueryKind::Row),
+        // `IN` is `= ANY` without an explicit operator
+        SubLinkType::AnySublink if sub_link.oper_name.is_empty() => Some(SubqueryKind::In),
+        SubLinkType::AnySublink => Some(SubqueryKind::Any),
+        SubLinkType::AllSublink => Some(SubqueryKind::All),
+        SubLinkType::ExistsSublink => Some(SubqueryKind::Exists),
+        SubLinkType::ArraySublink => Some(SubqueryKind::Array),
+        SubLinkType::CteSublink | SubLinkType::Undefined => None,
+    }
+}
+
+/// Returns the range from the parenthesis that opens the query containing `offset` to the one
+/// that closes it
+///
+/// The opening parenthesis is the last one before `offset` that is followed by the start of a
+/// query, since the first node of the query may itself be parenthesized.
+fn parenthesized_range(tokens: &[&SyntaxToken], offset: TextSize) -> Option<TextRange> {
+    let open = tokens
+        .iter()
+        .enumerate()
+        .take_while(|(_, t)| t.text_range().start() < offset)
+        .filter(|(idx, t)| {
+            t.kind() == SyntaxKind::Ascii40
+                && tokens.get(idx + 1).is_some_and(|next| {
+                    matches!(
+                        next.kind(),
+                        SyntaxKind::Select
+                            | SyntaxKind::With
+                            | SyntaxKind::Values
+                            | SyntaxKind::Table
+                    )
+                })
+        })
+        .map(|(idx, _)| idx)
+        .last()?;
+
+    let mut depth = 0;
+    let close = tokens[open..].iter().find(|t| {
+        match t.kind() {
+            SyntaxKind::Ascii40 => depth += 1,
+            SyntaxKind::Ascii41 => depth -= 1,
+            _ => {}
+        }
+        depth == 0
+    })?;
+    Some(tokens[open].text_range().cover(close.text_range()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn subquery_texts(input: &str) -> Vec<(SubqueryKind, &str)> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        subqueries(&parsed.cst)
+            .into_iter()
+            .map(|s| (s.kind, &input[s.range]))
+            .collect()
+    }
+
+    #[test]
+    fn test_scalar_subquery() {
+        assert_eq!(
+            subquery_texts("select id, (select max(created_at) from orders o where o.user_id = u.id) from users u;"),
+            vec![(
+                SubqueryKind::Scalar,
+                "(select max(created_at) from orders o where o.user_id = u.id)"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_exists_in_and_derived_subqueries() {
+        assert_eq!(
+            subquery_texts(
+                "select * from (select * from users) u where exists (select 1 from orders o where o.user_id = u.id) and id in (select user_id from admins);"
+            ),
+            vec![
+                (SubqueryKind::Derived, "(select * from users)"),
+                (
+                    SubqueryKind::Exists,
+                    "(select 1 from orders o where o.user_id = u.id)"
+                ),
+                (SubqueryKind::In, "(select user_id from admins)"),
+            ]
+        );
+    }
+}
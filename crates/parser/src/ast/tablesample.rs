@@ -0,0 +1,122 @@
+use cstree::text::TextRange;
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{
+    closing_parenthesis, location_to_offset, name_token_at, non_trivia_tokens, parse_pg_query_stmt,
+    qualified_name, token_before, tokens_range,
+};
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// A `TABLESAMPLE` clause of a table in `FROM`, e.g. `TABLESAMPLE BERNOULLI (10) REPEATABLE (42)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TableSample {
+    /// The possibly qualified name of the sampled table
+    pub table: String,
+    /// The lowercase name of the sampling method, e.g. `bernoulli` or `system`
+    pub method: String,
+    /// The text of the argument of the method, which is the percentage of the built-in methods
+    pub percentage: String,
+    /// The text of the seed of `REPEATABLE`
+    ///
+    /// Without a seed, every execution samples different rows.
+    pub repeatable: Option<String>,
+    /// The range from the `TABLESAMPLE` keyword to the end of the clause
+    pub range: TextRange,
+}
+
+/// Returns all `TABLESAMPLE` clauses of `stmt`, ordered by their position
+pub fn tablesample(stmt: &SyntaxNode) -> Vec<TableSample> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+    let text = stmt.resolved().text().to_string();
+    let source = |range: TextRange| text[range - stmt.text_range().start()].to_string();
+    let tokens = non_trivia_tokens(stmt);
+
+    let mut samples = get_nodes(ast.to_ref())
+        .into_iter()
+        .filter_map(|(node, _)| {
+            let sample = match node {
+                NodeRef::RangeTableSample(sample) => sample,
+                _ => return None,
+            };
+            let table = match sample.relation.as_deref()?.node.as_ref()? {
+                NodeEnum::RangeVar(table) => qualified_name(table),
+                _ => return None,
+            };
+            let method = match sample.method.last()?.node.as_ref()? {
+                NodeEnum::String(s) => s.str.clone(),
+                _ => return None,
+            };
+
+            // the location of the clause is the name of the method
+            let offset = location_to_offset(stmt, sample.location)?;
+            let keyword = token_before(stmt, SyntaxKind::Tablesample, offset)?;
+            let name = name_token_at(stmt, offset)?;
+            let open = tokens.iter().position(|t| *t == name)? + 1;
+            let close = closing_parenthesis(&tokens, open)?;
+            let percentage = source(tokens_range(&tokens[open + 1..close])?);
+
+            let mut end = close;
+            let mut repeatable = None;
+            if tokens.get(close + 1).map(|t| t.kind()) == Some(SyntaxKind::Repeatable) {
+                let close = closing_parenthesis(&tokens, close + 2)?;
+                repeatable = Some(source(tokens_range(&tokens[end + 3..close])?));
+                end = close;
+            }
+
+            Some(TableSample {
+                table,
+                method,
+                percentage,
+                repeatable,
+                range: keyword.text_range().cover(tokens[end].text_range()),
+            })
+        })
+        .collect::<Vec<_>>();
+    samples.sort_by_key(|s| s.range.start());
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn samples(input: &str) -> Vec<TableSample> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        tablesample(&parser.finish().cst)
+    }
+
+    #[test]
+    fn test_tablesample() {
+        let input = "select * from public.events tablesample bernoulli (10) where id > 1;";
+        let samples = samples(input);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].table, "public.events");
+        assert_eq!(samples[0].method, "bernoulli");
+        assert_eq!(samples[0].percentage, "10");
+        assert_eq!(samples[0].repeatable, None);
+        assert_eq!(&input[samples[0].range], "tablesample bernoulli (10)");
+    }
+
+    #[test]
+    fn test_tablesample_repeatable() {
+        let input =
+            "select * from a, b as x tablesample system (2.5 * 2) repeatable (42) where a.id = x.id;";
+        let samples = samples(input);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].table, "b");
+        assert_eq!(samples[0].method, "system");
+        assert_eq!(samples[0].percentage, "2.5 * 2");
+        assert_eq!(samples[0].repeatable.as_deref(), Some("42"));
+        assert_eq!(
+            &input[samples[0].range],
+            "tablesample system (2.5 * 2) repeatable (42)"
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::{self, AExprKind, MinMaxOp, SubLinkType};
+use pg_query::NodeEnum;
+
+use crate::ast::{location_to_offset, non_trivia_tokens, tokens_range, SelectStmt};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxToken;
+
+/// An output column of a select, e.g. `count(*) as n`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectTarget {
+    /// The ast node of the expression
+    pub expr: protobuf::Node,
+    /// The name of the output column, given with `AS` or inferred like Postgres does, e.g.
+    /// `count` for `count(*)`, or `None` for `*` and `t.*`, which expand to the columns of tables
+    pub alias: Option<String>,
+    /// Whether the alias is given with `AS`, or with a name that directly follows the expression
+    pub explicit_alias: bool,
+    /// The range of the target, including its alias
+    pub range: TextRange,
+}
+
+/// Returns the targets of the select list of `select`, in their order
+///
+/// Expressions that Postgres cannot name are named `?column?`, like Postgres does.
+pub fn select_targets(select: &SelectStmt) -> Vec<SelectTarget> {
+    let stmt = select.syntax();
+    let tokens = non_trivia_tokens(stmt);
+
+    select
+        .ast()
+        .target_list
+        .iter()
+        .filter_map(|n| match n.node.as_ref()? {
+            NodeEnum::ResTarget(target) => Some(target),
+            _ => None,
+        })
+        .filter_map(|target| {
+            let expr = target.val.as_deref()?;
+            let start = location_to_offset(stmt, target.location)?;
+            let first = tokens
+                .iter()
+                .position(|t| t.text_range().start() == start)?;
+            let len = target_len(&tokens[first..]);
+            let (alias, explicit_alias) = if target.name.is_empty() {
+                (column_name(expr.node.as_ref()?), false)
+            } else {
+                (Some(target.name.clone()), true)
+            };
+            Some(SelectTarget {
+                expr: expr.clone(),
+                alias,
+                explicit_alias,
+                range: tokens_range(&tokens[first..first + len])?,
+            })
+        })
+        .collect()
+}
+
+/// Returns the number of tokens of the target at the start of `tokens`, which ends before a comma
+/// or the keyword of the next clause
+fn target_len(tokens: &[&SyntaxToken]) -> usize {
+    let mut depth = 0;
+    for (idx, token) in tokens.iter().enumerate() {
+        match token.kind() {
+            SyntaxKind::Ascii40 => depth += 1,
+            SyntaxKind::Ascii41 if depth == 0 => return idx,
+            SyntaxKind::Ascii41 => depth -= 1,
+            SyntaxKind::Ascii44
+            | SyntaxKind::Ascii59
+            | SyntaxKind::From
+            | SyntaxKind::Into
+            | SyntaxKind::Where
+            | SyntaxKind::GroupP
+            | SyntaxKind::Having
+            | SyntaxKind::Window
+            | SyntaxKind::Order
+            | SyntaxKind::Limit
+            | SyntaxKind::Offset
+            | SyntaxKind::Fetch
+            | SyntaxKind::For
+            | SyntaxKind::Union
+            | SyntaxKind::Intersect
+            | SyntaxKind::Except
+                if depth == 0 =>
+            {
+                return idx
+            }
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// Returns the name that Postgres gives the output column of the expression `expr`, or `None` for
+/// a `*`
+fn column_name(expr: &NodeEnum) -> Option<String> {
+    let name = match expr {
+        NodeEnum::ColumnRef(column) => match column.fields.last()?.node.as_ref()? {
+            NodeEnum::String(s) => s.str.clone(),
+            NodeEnum::AStar(_) => return None,
+            _ => "?column?".to_string(),
+        },
+        NodeEnum::FuncCall(call) => match call.funcname.last().and_then(|n| n.node.as_ref()) {
+            Some(NodeEnum::String(s)) => s.str.clone(),
+            _ => "?column?".to_string(),
+        },
+        NodeEnum::TypeCast(cast) => {
+            // the name of the expression, or else the name of the type
+            let arg = cast
+                .arg
+                .as_deref()
+                .and_then(|n| n.node.as_ref())
+                .and_then(column_name)
+                .filter(|name| name != "?column?");
+            match arg {
+                Some(name) => name,
+                None => match cast
+                    .type_name
+                    .as_ref()
+                    .and_then(|t| t.names.last())
+                    .and_then(|n| n.node.as_ref())
+                {
+                    Some(NodeEnum::String(s)) => s.str.clone(),
+                    _ => "?column?".to_string(),
+                },
+            }
+        }
+        NodeEnum::CaseExpr(_) => "case".to_string(),
+        NodeEnum::CoalesceExpr(_) => "coalesce".to_string(),
+        NodeEnum::MinMaxExpr(expr) if expr.op == MinMaxOp::IsGreatest as i32 => {
+            "greatest".to_string()
+        }
+        NodeEnum::MinMaxExpr(_) => "least".to_string(),
+        NodeEnum::AExpr(expr) if expr.kind == AExprKind::AexprNullif as i32 => "nullif".to_string(),
+        NodeEnum::RowExpr(_) => "row".to_string(),
+        NodeEnum::AArrayExpr(_) => "array".to_string(),
+        NodeEnum::SubLink(sub_link) => match SubLinkType::from_i32(sub_link.sub_link_type) {
+            Some(SubLinkType::ExistsSublink) => "exists".to_string(),
+            Some(SubLinkType::ArraySublink) => "array".to_string(),
+            // the name of the only column of the subquery
+            Some(SubLinkType::ExprSublink) => {
+                match sub_link.subselect.as_deref().and_then(|n| n.node.as_ref()) {
+                    Some(NodeEnum::SelectStmt(select)) => match select.target_list.as_slice() {
+                        [protobuf::Node {
+                            node: Some(NodeEnum::ResTarget(target)),
+                        }] if !target.name.is_empty() => target.name.clone(),
+                        [protobuf::Node {
+                            node: Some(NodeEnum::ResTarget(target)),
+                        }] => target
+                            .val
+                            .as_deref()
+                            .and_then(|n| n.node.as_ref())
+                            .and_then(column_name)
+                            .unwrap_or_else(|| "?column?".to_string()),
+                        _ => "?column?".to_string(),
+                    },
+                    _ => "?column?".to_string(),
+                }
+            }
+            _ => "?column?".to_string(),
+        },
+        _ => "?column?".to_string(),
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn targets(input: &str) -> Vec<(Option<String>, bool, &str)> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        let select = SelectStmt::cast(&parsed.cst).unwrap();
+        select_targets(&select)
+            .into_iter()
+            .map(|t| (t.alias, t.explicit_alias, &input[t.range]))
+            .collect()
+    }
+
+    #[test]
+    fn test_select_targets() {
+        assert_eq!(
+            targets("select a, b as bee, count(*) as n from t;"),
+            vec![
+                (Some("a".to_string()), false, "a"),
+                (Some("bee".to_string()), true, "b as bee"),
+                (Some("n".to_string()), true, "count(*) as n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inferred_aliases() {
+        assert_eq!(
+            targets("select *, t.*, t.id, lower(name), 1 + 1, '1'::int4, case when x then 1 end, (select max(id) from u) from t"),
+            vec![
+                (None, false, "*"),
+                (None, false, "t.*"),
+                (Some("id".to_string()), false, "t.id"),
+                (Some("lower".to_string()), false, "lower(name)"),
+                (Some("?column?".to_string()), false, "1 + 1"),
+                (Some("int4".to_string()), false, "'1'::int4"),
+                (Some("case".to_string()), false, "case when x then 1 end"),
+                (Some("max".to_string()), false, "(select max(id) from u)"),
+            ]
+        );
+    }
+}
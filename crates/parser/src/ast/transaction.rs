@@ -0,0 +1,123 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::TransactionStmtKind;
+use pg_query::NodeEnum;
+
+use crate::ast::{parse_pg_query_stmt, statements};
+use crate::syntax_node::SyntaxNode;
+
+/// The statements from a `BEGIN` or `START TRANSACTION` up to the `COMMIT` or `ROLLBACK` that ends it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TransactionBlock {
+    /// The range from the start of the opening statement to the end of the closing statement, or
+    /// to the end of the last statement if the block is not closed
+    pub range: TextRange,
+    /// Whether the block is ended by a `COMMIT`, `ROLLBACK` or `PREPARE TRANSACTION`
+    pub closed: bool,
+    /// The names of all savepoints defined within the block
+    pub savepoints: Vec<String>,
+}
+
+impl TransactionBlock {
+    /// Whether `range` is within the block, excluding the statements that open and close it
+    ///
+    /// The last statement of a block that is not closed is within the block.
+    pub fn contains(&self, range: TextRange) -> bool {
+        self.range.start() < range.start()
+            && (range.end() < self.range.end() || !self.closed && range.end() <= self.range.end())
+    }
+}
+
+/// Returns all transaction blocks of `root`
+///
+/// Savepoints and rollbacks to savepoints do not end a block, and neither does a `BEGIN` within
+/// a block, which Postgres ignores with a warning.
+pub fn transaction_blocks(root: &SyntaxNode) -> Vec<TransactionBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<TransactionBlock> = None;
+    for stmt in statements(root) {
+        let range = stmt.text_range();
+        if let Some(block) = current.as_mut() {
+            block.range = block.range.cover(range);
+        }
+
+        let transaction = match parse_pg_query_stmt(stmt) {
+            Some(NodeEnum::TransactionStmt(t)) => t,
+            _ => continue,
+        };
+        match TransactionStmtKind::from_i32(transaction.kind) {
+            Some(TransactionStmtKind::TransStmtBegin | TransactionStmtKind::TransStmtStart)
+                if current.is_none() =>
+            {
+                current = Some(TransactionBlock {
+                    range,
+                    closed: false,
+                    savepoints: Vec::new(),
+                });
+            }
+            Some(TransactionStmtKind::TransStmtSavepoint) => {
+                if let Some(block) = current.as_mut() {
+                    block.savepoints.push(transaction.savepoint_name.clone());
+                }
+            }
+            Some(
+                TransactionStmtKind::TransStmtCommit
+                | TransactionStmtKind::TransStmtRollback
+                | TransactionStmtKind::TransStmtPrepare,
+            ) => {
+                if let Some(mut block) = current.take() {
+                    block.closed = true;
+                    blocks.push(block);
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks.extend(current);
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_closed_block() {
+        let input = "create table t (id int);
+begin;
+insert into t values (1);
+savepoint s1;
+insert into t values (2);
+rollback to savepoint s1;
+commit;
+select 1;";
+
+        let blocks = transaction_blocks(parse(input).root());
+
+        assert_eq!(blocks.len(), 1);
+        assert!(blocks[0].closed);
+        assert_eq!(blocks[0].savepoints, vec!["s1"]);
+        assert!(input[blocks[0].range].starts_with("begin;"));
+        assert!(input[blocks[0].range].ends_with("rollback to savepoint s1;\ncommit;"));
+    }
+
+    #[test]
+    fn test_unclosed_block() {
+        let input = "begin;\ncommit;\nstart transaction;\ninsert into t values (3);";
+
+        let blocks = transaction_blocks(parse(input).root());
+
+        assert_eq!(blocks.len(), 2);
+        assert!(!blocks[1].closed);
+        assert_eq!(
+            &input[blocks[1].range],
+            "start transaction;\ninsert into t values (3);"
+        );
+
+        let insert = TextRange::at((input.find("insert").unwrap() as u32).into(), 25.into());
+        assert_eq!(&input[insert], "insert into t values (3);");
+        assert!(blocks[1].contains(insert));
+        assert!(!blocks[1].contains(blocks[1].range));
+        assert!(!blocks[0].contains(blocks[0].range));
+    }
+}
@@ -0,0 +1,99 @@
+use cstree::text::TextRange;
+use pg_query::{protobuf, NodeEnum, NodeRef};
+
+use crate::ast::{location_to_offset, parse_pg_query_stmt, syntax_node_for};
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the names of all types that are referenced in `stmt`, along with their ranges
+///
+/// This covers casts, column definitions, e.g. of `CREATE TABLE` or `CREATE TYPE ... AS`, and
+/// all other places that take a type. The names are the ones pg_query.rs resolves the type to,
+/// qualified with their schema if specified, e.g. `int` is `pg_catalog.int4` and `varchar(255)`
+/// is `pg_catalog.varchar`. Array types end with a `[]` for every dimension.
+pub fn type_references(stmt: &SyntaxNode) -> Vec<(String, TextRange)> {
+    type_names(stmt)
+        .into_iter()
+        .map(|(type_name, range)| {
+            let mut name = type_name
+                .names
+                .iter()
+                .filter_map(|n| match n.node.as_ref() {
+                    Some(NodeEnum::String(s)) => Some(s.str.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join(".");
+            name.push_str(&"[]".repeat(type_name.array_bounds.len()));
+            (name, range)
+        })
+        .collect()
+}
+
+/// Returns the ast nodes of all types that are referenced in `stmt`, along with their ranges,
+/// ordered by their position
+pub(crate) fn type_names(stmt: &SyntaxNode) -> Vec<(protobuf::TypeName, TextRange)> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+
+    let mut type_names = get_nodes(ast.to_ref())
+        .into_iter()
+        .filter_map(|(node, _)| match node {
+            NodeRef::TypeName(type_name) => {
+                let range = match syntax_node_for(stmt, &node) {
+                    Some(n) => n.text_range(),
+                    None => {
+                        let offset = location_to_offset(stmt, type_name.location)?;
+                        stmt.token_at_offset(offset).right_biased()?.text_range()
+                    }
+                };
+                Some((type_name.clone(), range))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    type_names.sort_by_key(|(_, range)| range.start());
+    type_names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn types(input: &str) -> Vec<(String, &str)> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        type_references(&parsed.cst)
+            .into_iter()
+            .map(|(name, range)| (name, &input[range]))
+            .collect()
+    }
+
+    #[test]
+    fn test_create_table_types() {
+        assert_eq!(
+            types("create table t (id int, name varchar(255), tags text[], created_at pg_catalog.timestamptz);"),
+            vec![
+                ("pg_catalog.int4".to_string(), "int"),
+                ("pg_catalog.varchar".to_string(), "varchar(255)"),
+                ("text[]".to_string(), "text[]"),
+                ("pg_catalog.timestamptz".to_string(), "pg_catalog.timestamptz"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cast_types() {
+        assert_eq!(
+            types("select id::bigint, cast(name as text) from t;"),
+            vec![
+                ("pg_catalog.int8".to_string(), "bigint"),
+                ("text".to_string(), "text"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::{Node, ObjectType};
+use pg_query::NodeEnum;
+
+use crate::ast::{
+    depth_at, location_to_offset, non_trivia_tokens, parse_pg_query_stmt, qualified_name,
+    tokens_range, SelectStmt,
+};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// A `CREATE VIEW` or `CREATE MATERIALIZED VIEW` statement
+#[derive(Debug, Clone)]
+pub struct ViewStmt {
+    /// The name of the view, qualified with its schema if specified
+    pub name: String,
+    pub materialized: bool,
+    /// Whether the view is created with `OR REPLACE`, which materialized views do not support
+    pub or_replace: bool,
+    /// The explicit column names of the view, e.g. `a` and `b` of `create view v (a, b) as ...`
+    pub columns: Vec<String>,
+    /// The query that defines the view, which wraps the statement node like every nested select
+    pub query: SelectStmt,
+    /// The range of the query, from its first token after `AS` to its last one before a
+    /// `WITH [NO] DATA` or `WITH CHECK OPTION` clause
+    pub query_range: TextRange,
+}
+
+/// Returns the details of the `CREATE VIEW` or `CREATE MATERIALIZED VIEW` statement `stmt`
+pub fn view_statement(stmt: &SyntaxNode) -> Option<ViewStmt> {
+    match parse_pg_query_stmt(stmt)? {
+        NodeEnum::ViewStmt(view) => Some(ViewStmt {
+            name: qualified_name(view.view.as_ref()?),
+            materialized: false,
+            or_replace: view.replace,
+            columns: names(&view.aliases),
+            query_range: query_range(stmt, view.view.as_ref()?.location)?,
+            query: select(stmt, view.query?.node?)?,
+        }),
+        NodeEnum::CreateTableAsStmt(create)
+            if create.relkind == ObjectType::ObjectMatview as i32 =>
+        {
+            let into = create.into?;
+            Some(ViewStmt {
+                name: qualified_name(into.rel.as_ref()?),
+                materialized: true,
+                or_replace: false,
+                columns: names(&into.col_names),
+                query_range: query_range(stmt, into.rel.as_ref()?.location)?,
+                query: select(stmt, create.query?.node?)?,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Returns the range of the query of the view statement `stmt`, whose view name is at `location`
+fn query_range(stmt: &SyntaxNode, location: i32) -> Option<TextRange> {
+    let name = location_to_offset(stmt, location)?;
+    let tokens = non_trivia_tokens(stmt);
+    let start = (0..tokens.len()).find(|idx| {
+        tokens[*idx].text_range().start() > name
+            && tokens[*idx].kind() == SyntaxKind::As
+            && depth_at(&tokens, *idx) == 0
+    })? + 1;
+    // a `WITH` at the start is the one of the query itself
+    let end = (start + 1..tokens.len())
+        .find(|idx| {
+            matches!(tokens[*idx].kind(), SyntaxKind::With | SyntaxKind::Ascii59)
+                && depth_at(&tokens, *idx) == 0
+        })
+        .unwrap_or(tokens.len());
+    tokens_range(&tokens[start..end])
+}
+
+fn names(nodes: &[Node]) -> Vec<String> {
+    nodes
+        .iter()
+        .filter_map(|n| match n.node.as_ref() {
+            Some(NodeEnum::String(s)) => Some(s.str.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn select(stmt: &SyntaxNode, query: NodeEnum) -> Option<SelectStmt> {
+    match query {
+        NodeEnum::SelectStmt(select) => Some(SelectStmt::new(stmt, *select)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn view(input: &str) -> Option<ViewStmt> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        view_statement(&parsed.cst)
+    }
+
+    #[test]
+    fn test_view() {
+        let input = "create or replace view public.active_users (id, name) as select id, name from users where active;";
+        let stmt = view(input).unwrap();
+
+        assert_eq!(stmt.name, "public.active_users");
+        assert!(!stmt.materialized);
+        assert!(stmt.or_replace);
+        assert_eq!(stmt.columns, vec!["id", "name"]);
+        assert_eq!(stmt.query.ast().target_list.len(), 2);
+        assert_eq!(
+            &input[stmt.query_range],
+            "select id, name from users where active"
+        );
+    }
+
+    #[test]
+    fn test_materialized_view() {
+        let input = "create materialized view user_counts as with x as (select 1) select count(*) from users with no data;";
+        let stmt = view(input).unwrap();
+
+        assert_eq!(stmt.name, "user_counts");
+        assert!(stmt.materialized);
+        assert!(!stmt.or_replace);
+        assert!(stmt.columns.is_empty());
+        assert_eq!(stmt.query.ast().from_clause.len(), 1);
+        assert_eq!(
+            &input[stmt.query_range],
+            "with x as (select 1) select count(*) from users"
+        );
+        assert!(view("create table t as select 1;").is_none());
+    }
+}
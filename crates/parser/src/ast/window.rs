@@ -0,0 +1,208 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::{Node, WindowDef};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{closing_parenthesis, depth_at, non_trivia_tokens, parse_pg_query_stmt, token_at};
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+// the bits of `frame_options` of a `WindowDef`, see `parsenodes.h`
+const FRAMEOPTION_NONDEFAULT: i32 = 0x00001;
+const FRAMEOPTION_ROWS: i32 = 0x00004;
+const FRAMEOPTION_GROUPS: i32 = 0x00008;
+const FRAMEOPTION_START_UNBOUNDED_PRECEDING: i32 = 0x00020;
+const FRAMEOPTION_END_UNBOUNDED_PRECEDING: i32 = 0x00040;
+const FRAMEOPTION_START_UNBOUNDED_FOLLOWING: i32 = 0x00080;
+const FRAMEOPTION_END_UNBOUNDED_FOLLOWING: i32 = 0x00100;
+const FRAMEOPTION_START_OFFSET_PRECEDING: i32 = 0x00800;
+const FRAMEOPTION_END_OFFSET_PRECEDING: i32 = 0x01000;
+const FRAMEOPTION_START_OFFSET_FOLLOWING: i32 = 0x02000;
+const FRAMEOPTION_END_OFFSET_FOLLOWING: i32 = 0x04000;
+
+/// The unit of a frame of a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameMode {
+    Range,
+    Rows,
+    Groups,
+}
+
+/// The start or the end of a frame of a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    /// `<offset> PRECEDING`
+    Preceding,
+    CurrentRow,
+    /// `<offset> FOLLOWING`
+    Following,
+    UnboundedFollowing,
+}
+
+/// The frame of a window specification, e.g. `ROWS BETWEEN 1 PRECEDING AND CURRENT ROW`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WindowFrame {
+    pub mode: FrameMode,
+    pub start: FrameBound,
+    pub end: FrameBound,
+    /// Whether the frame is written out, instead of the default
+    /// `RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW`
+    pub explicit: bool,
+    /// The range of the frame clause, or the range of the parenthesized window specification if
+    /// the frame is implicit
+    pub range: TextRange,
+}
+
+/// Returns the frames of all window specifications in `stmt`, ordered by their position
+///
+/// These are the specifications of the `OVER` clauses of window function calls and of the
+/// `WINDOW` clauses. An `OVER` clause that only refers to a named window, e.g. `OVER w`, has no
+/// frame of its own and is skipped.
+pub fn window_frames(stmt: &SyntaxNode) -> Vec<WindowFrame> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+    let tokens = non_trivia_tokens(stmt);
+
+    let mut frames = get_nodes(ast.to_ref())
+        .into_iter()
+        .flat_map(|(node, _)| match node {
+            NodeRef::FuncCall(call) => call
+                .over
+                .as_deref()
+                .filter(|w| w.name.is_empty())
+                .into_iter()
+                .collect(),
+            NodeRef::SelectStmt(select) => {
+                select.window_clause.iter().filter_map(window_def).collect()
+            }
+            _ => Vec::new(),
+        })
+        .filter_map(|window| {
+            // the location of a window specification is its opening parenthesis
+            let open_token = token_at(stmt, window.location)?;
+            let open = tokens.iter().position(|t| *t == open_token)?;
+            let close = closing_parenthesis(&tokens, open)?;
+            let explicit = window.frame_options & FRAMEOPTION_NONDEFAULT != 0;
+            let range = if explicit {
+                let depth = depth_at(&tokens, open + 1);
+                let start = (open + 1..close).find(|idx| {
+                    depth_at(&tokens, *idx) == depth
+                        && matches!(
+                            tokens[*idx].kind(),
+                            SyntaxKind::Range | SyntaxKind::Rows | SyntaxKind::Groups
+                        )
+                })?;
+                tokens[start]
+                    .text_range()
+                    .cover(tokens[close - 1].text_range())
+            } else {
+                tokens[open].text_range().cover(tokens[close].text_range())
+            };
+            Some(frame(window.frame_options, explicit, range))
+        })
+        .collect::<Vec<_>>();
+    frames.sort_by_key(|f| f.range.start());
+    frames
+}
+
+fn window_def(node: &Node) -> Option<&WindowDef> {
+    match node.node.as_ref()? {
+        NodeEnum::WindowDef(window) => Some(window),
+        _ => None,
+    }
+}
+
+fn frame(options: i32, explicit: bool, range: TextRange) -> WindowFrame {
+    let mode = if options & FRAMEOPTION_ROWS != 0 {
+        FrameMode::Rows
+    } else if options & FRAMEOPTION_GROUPS != 0 {
+        FrameMode::Groups
+    } else {
+        FrameMode::Range
+    };
+    let start = if options & FRAMEOPTION_START_UNBOUNDED_PRECEDING != 0 {
+        FrameBound::UnboundedPreceding
+    } else if options & FRAMEOPTION_START_OFFSET_PRECEDING != 0 {
+        FrameBound::Preceding
+    } else if options & FRAMEOPTION_START_OFFSET_FOLLOWING != 0 {
+        FrameBound::Following
+    } else if options & FRAMEOPTION_START_UNBOUNDED_FOLLOWING != 0 {
+        FrameBound::UnboundedFollowing
+    } else {
+        FrameBound::CurrentRow
+    };
+    // a frame without `BETWEEN` ends at the current row
+    let end = if options & FRAMEOPTION_END_UNBOUNDED_PRECEDING != 0 {
+        FrameBound::UnboundedPreceding
+    } else if options & FRAMEOPTION_END_OFFSET_PRECEDING != 0 {
+        FrameBound::Preceding
+    } else if options & FRAMEOPTION_END_OFFSET_FOLLOWING != 0 {
+        FrameBound::Following
+    } else if options & FRAMEOPTION_END_UNBOUNDED_FOLLOWING != 0 {
+        FrameBound::UnboundedFollowing
+    } else {
+        FrameBound::CurrentRow
+    };
+    WindowFrame {
+        mode,
+        start,
+        end,
+        explicit,
+        range,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn frames(input: &str) -> Vec<(FrameMode, FrameBound, FrameBound, bool, &str)> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        window_frames(&parser.finish().cst)
+            .into_iter()
+            .map(|f| (f.mode, f.start, f.end, f.explicit, &input[f.range]))
+            .collect()
+    }
+
+    #[test]
+    fn test_explicit_frames() {
+        assert_eq!(
+            frames("select sum(x) over (order by id rows between 2 preceding and current row), avg(x) over w from t window w as (partition by g groups 1 preceding);"),
+            vec![
+                (
+                    FrameMode::Rows,
+                    FrameBound::Preceding,
+                    FrameBound::CurrentRow,
+                    true,
+                    "rows between 2 preceding and current row"
+                ),
+                (
+                    FrameMode::Groups,
+                    FrameBound::Preceding,
+                    FrameBound::CurrentRow,
+                    true,
+                    "groups 1 preceding"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_implicit_frame() {
+        assert_eq!(
+            frames("select rank() over (partition by g order by id) from t;"),
+            vec![(
+                FrameMode::Range,
+                FrameBound::UnboundedPreceding,
+                FrameBound::CurrentRow,
+                false,
+                "(partition by g order by id)"
+            )]
+        );
+    }
+}
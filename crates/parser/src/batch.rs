@@ -0,0 +1,168 @@
+//! Linting of many source files at once, e.g. for a command line linter.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use cstree::text::TextRange;
+
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::lint::{run_lints, Config};
+use crate::parser::parse;
+
+/// The diagnostics of a source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileResult {
+    pub path: PathBuf,
+    /// The syntax errors and lint findings of the file, ordered by their position
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Parses every source file of `inputs`, given as pairs of a path and a text, and runs the lints
+/// that `config` enables on it
+///
+/// The files are independent, so they are processed on multiple threads. The results are in the
+/// order of `inputs`. If linting a file panics, its result has a single error diagnostic at the
+/// start of the file instead, and the other files are still linted.
+pub fn parse_files(inputs: &[(PathBuf, String)], config: &Config) -> Vec<FileResult> {
+    lint_files(inputs, |path, text| parse_file(path, text, config))
+}
+
+/// Runs `lint` on every source file of `inputs` on multiple threads, and turns its panics into
+/// error results
+fn lint_files(
+    inputs: &[(PathBuf, String)],
+    lint: impl Fn(&Path, &str) -> FileResult + Sync,
+) -> Vec<FileResult> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = inputs.len().div_ceil(threads).max(1);
+    let lint = &lint;
+    thread::scope(|scope| {
+        let handles = inputs
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let handle = scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|(path, text)| {
+                            panic::catch_unwind(AssertUnwindSafe(|| lint(path, text)))
+                                .unwrap_or_else(|payload| panicked(path, payload.as_ref()))
+                        })
+                        .collect::<Vec<_>>()
+                });
+                (chunk, handle)
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|(chunk, handle)| {
+                handle.join().unwrap_or_else(|payload| {
+                    chunk
+                        .iter()
+                        .map(|(path, _)| panicked(path, payload.as_ref()))
+                        .collect()
+                })
+            })
+            .collect()
+    })
+}
+
+/// Returns the result of the file at `path` whose linting panicked with `payload`
+fn panicked(path: &Path, payload: &(dyn Any + Send)) -> FileResult {
+    let reason = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown error");
+    FileResult {
+        path: path.to_path_buf(),
+        diagnostics: vec![Diagnostic::new(
+            format!("internal error while linting the file: {}", reason),
+            TextRange::empty(0.into()),
+        )
+        .with_severity(Severity::Error)],
+    }
+}
+
+fn parse_file(path: &Path, text: &str, config: &Config) -> FileResult {
+    let result = parse(text);
+    let mut diagnostics = result.diagnostics().to_vec();
+    diagnostics.extend(run_lints(result.root(), config));
+    diagnostics.sort_by_key(|d| d.range().start());
+    FileResult {
+        path: path.to_path_buf(),
+        diagnostics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_files() {
+        let inputs = vec![
+            (PathBuf::from("a.sql"), "select 1;;".to_string()),
+            (
+                PathBuf::from("b.sql"),
+                "select from;\nselect 2;".to_string(),
+            ),
+            (PathBuf::from("c.sql"), "select 3;".to_string()),
+        ];
+        let results = parse_files(&inputs, &Config::default());
+
+        let messages = results
+            .iter()
+            .map(|r| {
+                (
+                    r.path.to_str().unwrap(),
+                    r.diagnostics
+                        .iter()
+                        .map(|d| d.message().to_string())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                ("a.sql", vec!["redundant semicolon".to_string()]),
+                (
+                    "b.sql",
+                    vec!["Invalid statement: syntax error at or near \";\"".to_string()]
+                ),
+                ("c.sql", Vec::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_panicking_file() {
+        let inputs = vec![
+            (PathBuf::from("a.sql"), "select 1;".to_string()),
+            (PathBuf::from("b.sql"), "select 2;".to_string()),
+        ];
+        let results = lint_files(&inputs, |path, text| {
+            if path == Path::new("b.sql") {
+                panic!("cannot lint {}", text);
+            }
+            parse_file(path, text, &Config::default())
+        });
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].diagnostics.is_empty());
+        assert_eq!(results[1].path, PathBuf::from("b.sql"));
+        assert_eq!(
+            results[1]
+                .diagnostics
+                .iter()
+                .map(|d| (d.message(), d.severity()))
+                .collect::<Vec<_>>(),
+            vec![(
+                "internal error while linting the file: cannot lint select 2;",
+                Severity::Error
+            )]
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! The comments of a source file, e.g. to extract license headers or `TODO` markers.
+
+use cstree::text::TextRange;
+
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// The style of a `CommentInfo`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommentKind {
+    /// `-- ...`, which ends at the end of the line
+    Line,
+    /// `/* ... */`
+    Block,
+}
+
+/// A comment of a source file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommentInfo {
+    /// The text of the comment, including the `--` or `/*` and `*/` delimiters
+    pub text: String,
+    pub kind: CommentKind,
+    pub range: TextRange,
+}
+
+impl CommentInfo {
+    /// Returns the text of the comment without its delimiters
+    pub fn content(&self) -> &str {
+        match self.kind {
+            CommentKind::Line => self.text.strip_prefix("--").unwrap_or(&self.text),
+            CommentKind::Block => self
+                .text
+                .strip_prefix("/*")
+                .and_then(|t| t.strip_suffix("*/"))
+                .unwrap_or(&self.text),
+        }
+    }
+}
+
+/// Returns all comments of `root`, ordered by their position
+///
+/// Comments within statements are included, but not the contents of string literals that look
+/// like comments.
+pub fn comments(root: &SyntaxNode) -> Vec<CommentInfo> {
+    root.descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .filter(|t| t.kind() == SyntaxKind::Comment)
+        .map(|t| {
+            let text = t.resolved().text().to_string();
+            CommentInfo {
+                kind: if text.starts_with("/*") {
+                    CommentKind::Block
+                } else {
+                    CommentKind::Line
+                },
+                text,
+                range: t.text_range(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_comments() {
+        let input = "-- license header\nselect '-- not a comment' /* inline */ from t;\n";
+        let comments = comments(parse(input).root());
+
+        assert_eq!(
+            comments
+                .iter()
+                .map(|c| (c.kind, &input[c.range], c.content()))
+                .collect::<Vec<_>>(),
+            vec![
+                (CommentKind::Line, "-- license header", " license header"),
+                (CommentKind::Block, "/* inline */", " inline "),
+            ]
+        );
+        assert_eq!(comments[1].text, "/* inline */");
+    }
+}
@@ -0,0 +1,299 @@
+//! Completion of names at an offset in a source file.
+//!
+//! The statement that is edited usually contains syntax errors, e.g. `select * from `, so
+//! completion only looks at the tokens of the statement instead of its ast.
+
+use cstree::text::TextSize;
+
+use crate::ast::name::resolve;
+use crate::ast::{non_trivia_tokens, statements};
+use crate::schema::{QualifiedName, SchemaProvider, DEFAULT_SCHEMA};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// The kind of object that a `CompletionItem` suggests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompletionKind {
+    Table,
+    Column,
+    Function,
+}
+
+/// A suggestion for the name at the cursor
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompletionItem {
+    pub label: String,
+    pub kind: CompletionKind,
+    /// Details about the object, e.g. the type of a column
+    pub detail: Option<String>,
+}
+
+/// Returns the names that may be inserted at `offset` in the source file `root`
+///
+/// After `FROM`, `JOIN` and the commas of a `FROM` clause, the tables of `schema` are suggested.
+/// After a qualifier, e.g. `c.`, the columns of the table with that alias or name are suggested,
+/// or the tables of the schema with that name, e.g. for `public.`. Elsewhere, the columns of the
+/// tables that the statement refers to and the functions of `schema` are suggested. The name that
+/// is being typed at `offset` is not used to filter the suggestions, which is left to the editor.
+pub fn completions(
+    root: &SyntaxNode,
+    offset: TextSize,
+    schema: &dyn SchemaProvider,
+) -> Vec<CompletionItem> {
+    let stmt = match statements(root)
+        .into_iter()
+        .find(|s| s.text_range().contains_inclusive(offset))
+    {
+        Some(stmt) => stmt,
+        None => return Vec::new(),
+    };
+    let tokens = non_trivia_tokens(stmt);
+
+    let mut before = tokens
+        .iter()
+        .take_while(|t| t.text_range().end() <= offset)
+        .copied()
+        .collect::<Vec<_>>();
+    // the name that is being typed
+    if before
+        .last()
+        .is_some_and(|t| t.kind() == SyntaxKind::Ident && t.text_range().end() == offset)
+    {
+        before.pop();
+    }
+
+    let references = table_references(&tokens);
+    match before.as_slice() {
+        // a qualified name, e.g. `c.` or `public.`
+        [.., qualifier, dot]
+            if qualifier.kind() == SyntaxKind::Ident && dot.kind() == SyntaxKind::Ascii46 =>
+        {
+            let qualifier = identifier(qualifier);
+            match references.iter().find(|r| r.is_named(&qualifier)) {
+                Some(table) => column_items(schema, &[table]),
+                None => table_items(schema, &qualifier),
+            }
+        }
+        [.., previous]
+            if matches!(previous.kind(), SyntaxKind::From | SyntaxKind::Join)
+                || (previous.kind() == SyntaxKind::Ascii44 && is_in_from_clause(&before)) =>
+        {
+            table_items(schema, DEFAULT_SCHEMA)
+        }
+        _ => {
+            let mut items = column_items(schema, &references.iter().collect::<Vec<_>>());
+            items.extend(
+                schema
+                    .functions()
+                    .into_iter()
+                    .map(|function| CompletionItem {
+                        label: function.name.name,
+                        kind: CompletionKind::Function,
+                        detail: Some(format!(
+                            "({}) -> {}",
+                            function.arguments.join(", "),
+                            function.return_type
+                        )),
+                    }),
+            );
+            items
+        }
+    }
+}
+
+/// Returns the tables of `schema_name` as completion items
+fn table_items(schema: &dyn SchemaProvider, schema_name: &str) -> Vec<CompletionItem> {
+    schema
+        .tables(schema_name)
+        .into_iter()
+        .map(|name| CompletionItem {
+            label: name,
+            kind: CompletionKind::Table,
+            detail: None,
+        })
+        .collect()
+}
+
+/// Returns the columns of `tables` as completion items, without duplicate names
+fn column_items(schema: &dyn SchemaProvider, tables: &[&TableReference]) -> Vec<CompletionItem> {
+    let mut items = Vec::new();
+    for table in tables {
+        for column in schema.columns(&table.name) {
+            if !items
+                .iter()
+                .any(|i: &CompletionItem| i.label == column.name)
+            {
+                items.push(CompletionItem {
+                    label: column.name,
+                    kind: CompletionKind::Column,
+                    detail: Some(column.type_name),
+                });
+            }
+        }
+    }
+    items
+}
+
+/// A table that a statement refers to in a `FROM` clause or a join
+struct TableReference {
+    name: QualifiedName,
+    alias: Option<String>,
+}
+
+impl TableReference {
+    /// Whether the table is referred to as `name` in the statement, i.e. by its alias if it has
+    /// one, or else by its name
+    fn is_named(&self, name: &str) -> bool {
+        self.alias.as_ref().unwrap_or(&self.name.name) == name
+    }
+}
+
+/// Returns the tables that follow `FROM`, `JOIN` or a comma of a `FROM` clause in `tokens`
+fn table_references(tokens: &[&SyntaxToken]) -> Vec<TableReference> {
+    let mut references = Vec::new();
+    let mut in_from_clause = false;
+    for (idx, token) in tokens.iter().enumerate() {
+        let starts_table = match token.kind() {
+            SyntaxKind::From | SyntaxKind::Join => true,
+            SyntaxKind::Ascii44 => in_from_clause,
+            _ => false,
+        };
+        in_from_clause = continues_from_clause(in_from_clause, token.kind());
+        if !starts_table {
+            continue;
+        }
+
+        let rest = &tokens[idx + 1..];
+        let (name, len) = match qualified_name(rest) {
+            Some(name) => name,
+            None => continue,
+        };
+        let rest = match rest.get(len) {
+            Some(t) if t.kind() == SyntaxKind::As => &rest[len + 1..],
+            _ => &rest[len..],
+        };
+        let alias = rest
+            .first()
+            .filter(|t| t.kind() == SyntaxKind::Ident)
+            .map(|t| identifier(t));
+        references.push(TableReference { name, alias });
+    }
+    references
+}
+
+/// Whether the end of `tokens` is within a `FROM` clause
+fn is_in_from_clause(tokens: &[&SyntaxToken]) -> bool {
+    tokens.iter().fold(false, |in_from_clause, t| {
+        continues_from_clause(in_from_clause, t.kind())
+    })
+}
+
+/// Whether a `FROM` clause continues after a token of `kind`
+///
+/// Only simple lists of tables with aliases are considered, e.g. `from a as x, b join c`.
+fn continues_from_clause(in_from_clause: bool, kind: SyntaxKind) -> bool {
+    match kind {
+        SyntaxKind::From | SyntaxKind::Join => true,
+        SyntaxKind::Ident | SyntaxKind::Ascii44 | SyntaxKind::Ascii46 | SyntaxKind::As => {
+            in_from_clause
+        }
+        _ => false,
+    }
+}
+
+/// Returns the possibly qualified name at the start of `tokens`, and the number of its tokens
+fn qualified_name(tokens: &[&SyntaxToken]) -> Option<(QualifiedName, usize)> {
+    match tokens {
+        [schema, dot, name, ..]
+            if schema.kind() == SyntaxKind::Ident
+                && dot.kind() == SyntaxKind::Ascii46
+                && name.kind() == SyntaxKind::Ident =>
+        {
+            Some((
+                QualifiedName::new(Some(&identifier(schema)), &identifier(name)),
+                3,
+            ))
+        }
+        // an incomplete qualified name, e.g. `public.`
+        [_, dot, ..] if dot.kind() == SyntaxKind::Ascii46 => None,
+        [name, ..] if name.kind() == SyntaxKind::Ident => {
+            Some((QualifiedName::new(None, &identifier(name)), 1))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the name that the identifier `token` refers to
+fn identifier(token: &SyntaxToken) -> String {
+    resolve(token.resolved().text())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use crate::schema::StaticSchema;
+
+    fn complete(input: &str) -> Vec<(String, CompletionKind)> {
+        let schema = StaticSchema::new()
+            .with_table("customers", &[("id", "int4"), ("email", "text")])
+            .with_table("orders", &[("id", "int4"), ("customer_id", "int4")])
+            .with_table("audit.events", &[("id", "int8")])
+            .with_function("lower", &["text"], "text");
+        let offset = TextSize::from(input.find('|').unwrap() as u32);
+        let input = input.replace('|', "");
+        completions(parse(&input).root(), offset, &schema)
+            .into_iter()
+            .map(|item| (item.label, item.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_complete_tables() {
+        let tables = vec![
+            ("customers".to_string(), CompletionKind::Table),
+            ("orders".to_string(), CompletionKind::Table),
+        ];
+        assert_eq!(complete("select * from |"), tables);
+        assert_eq!(complete("select * from cu|"), tables);
+        assert_eq!(complete("select * from customers c join |;"), tables);
+        assert_eq!(complete("select * from customers as c, |"), tables);
+        assert_eq!(
+            complete("select * from audit.|"),
+            vec![("events".to_string(), CompletionKind::Table)]
+        );
+    }
+
+    #[test]
+    fn test_complete_columns() {
+        assert_eq!(
+            complete("select | from orders o, customers;"),
+            vec![
+                ("id".to_string(), CompletionKind::Column),
+                ("customer_id".to_string(), CompletionKind::Column),
+                ("email".to_string(), CompletionKind::Column),
+                ("lower".to_string(), CompletionKind::Function),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_complete_qualified_columns() {
+        let columns = vec![
+            ("id".to_string(), CompletionKind::Column),
+            ("email".to_string(), CompletionKind::Column),
+        ];
+        assert_eq!(
+            complete("select c.| from customers c join orders o on o.customer_id = c.id;"),
+            columns
+        );
+        assert_eq!(
+            complete("select 1 from customers where customers.em|"),
+            columns
+        );
+        assert_eq!(
+            complete("select e.| from audit.events as e;"),
+            vec![("id".to_string(), CompletionKind::Column)]
+        );
+    }
+}
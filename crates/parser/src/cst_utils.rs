@@ -0,0 +1,255 @@
+//! Utilities to query the cst.
+
+use cstree::text::TextRange;
+
+use crate::ast::nested_queries;
+use crate::fix::TextEdit;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the kind and range of all tokens of `root` that overlap `range`, optionally filtered by `kind`
+///
+/// Tokens that only partially overlap `range` are included. If `range` is empty, the token that
+/// contains it is returned.
+pub fn tokens_in_range(
+    root: &SyntaxNode,
+    range: TextRange,
+    kind: Option<SyntaxKind>,
+) -> Vec<(SyntaxKind, TextRange)> {
+    root.descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .filter(|t| kind.is_none_or(|k| t.kind() == k))
+        .map(|t| (t.kind(), t.text_range()))
+        .filter(|(_, r)| {
+            if range.is_empty() {
+                r.contains(range.start())
+            } else {
+                r.start() < range.end() && range.start() < r.end()
+            }
+        })
+        .collect()
+}
+
+/// Returns the text of `source` covered by `node`
+///
+/// `source` must be the text the tree of `node` was parsed from. The ranges of the tree are
+/// absolute, so this works for nodes of any statement in a source file.
+pub fn node_text<'a>(source: &'a str, node: &SyntaxNode) -> &'a str {
+    &source[node.text_range()]
+}
+
+/// Returns the text of `node`, concatenated from the texts of all its tokens in order
+///
+/// Unlike `node_text`, this does not need the source text, and reconstructs the text of the tree
+/// itself, including its trivia.
+pub fn subtree_text(node: &SyntaxNode) -> String {
+    node.descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .map(|t| t.resolved().text())
+        .collect()
+}
+
+/// Whether `edit` of the source file `root` only changes whitespace or the text of a comment
+///
+/// Such an edit cannot change the structure of the tree, only the ranges after it, so the source
+/// does not need to be parsed again. This is the case if a single whitespace or comment token
+/// contains the range of `edit`, and the token is still whitespace or the same comment after the
+/// edit. The whitespace after a line comment must still contain a line break, otherwise the
+/// comment would swallow the following tokens. Edits of string literals and all other tokens are
+/// significant.
+pub fn edit_touches_only_trivia(root: &SyntaxNode, edit: &TextEdit) -> bool {
+    let tokens = root
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .collect::<Vec<_>>();
+    tokens.iter().enumerate().any(|(idx, t)| {
+        if !t.kind().is_trivia() || !t.text_range().contains_range(edit.range) {
+            return false;
+        }
+        let mut text = t.resolved().text().to_string();
+        let range = edit.range - t.text_range().start();
+        text.replace_range(
+            usize::from(range.start())..usize::from(range.end()),
+            &edit.replacement,
+        );
+        match t.kind() {
+            SyntaxKind::Whitespace | SyntaxKind::Newline | SyntaxKind::Tab => {
+                let after_line_comment = idx.checked_sub(1).is_some_and(|prev| {
+                    tokens[prev].kind() == SyntaxKind::Comment
+                        && tokens[prev].resolved().text().starts_with("--")
+                });
+                !text.is_empty()
+                    && text.chars().all(char::is_whitespace)
+                    && (!after_line_comment || text.contains(['\n', '\r']))
+            }
+            SyntaxKind::Comment if t.resolved().text().starts_with("--") => {
+                text.starts_with("--") && !text.contains(['\n', '\r'])
+            }
+            SyntaxKind::Comment => {
+                text.len() >= 4
+                    && text.starts_with("/*")
+                    && text.ends_with("*/")
+                    && !text[2..text.len() - 2].contains("/*")
+                    && !text[2..text.len() - 2].contains("*/")
+            }
+            _ => false,
+        }
+    })
+}
+
+/// Returns the maximum nesting depth of the queries in `stmt`, i.e. of subqueries, derived tables
+/// and common table expressions
+///
+/// A statement without nested queries has the depth 0. Parentheses of expressions and lists, e.g.
+/// of `count(*)` or `in (1, 2)`, do not count.
+pub fn nesting_depth(stmt: &SyntaxNode) -> u32 {
+    let queries = nested_queries(stmt);
+    queries
+        .iter()
+        .map(|query| queries.iter().filter(|q| q.contains_range(*query)).count() as u32)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Returns the ancestors of `node`, from its parent up to the root
+pub fn ancestors(node: &SyntaxNode) -> impl Iterator<Item = SyntaxNode> + '_ {
+    node.ancestors().skip(1).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use cstree::text::TextSize;
+
+    use super::*;
+    use crate::parser::{parse, Parser};
+
+    #[test]
+    fn test_tokens_in_range() {
+        let input = "select id, name from users where id = 1;";
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+
+        // from the middle of `name` to the middle of `where`
+        let range = TextRange::new(TextSize::from(13), TextSize::from(30));
+
+        let tokens = tokens_in_range(&parsed.cst, range, None)
+            .iter()
+            .filter(|(kind, _)| !kind.is_trivia())
+            .map(|(_, r)| &input[*r])
+            .collect::<Vec<_>>();
+        assert_eq!(tokens, vec!["name", "from", "users", "where"]);
+
+        let keywords = [SyntaxKind::From, SyntaxKind::Where]
+            .into_iter()
+            .flat_map(|k| tokens_in_range(&parsed.cst, range, Some(k)))
+            .map(|(_, r)| &input[r])
+            .collect::<Vec<_>>();
+        assert_eq!(keywords, vec!["from", "where"]);
+    }
+
+    #[test]
+    fn test_node_text() {
+        let input = "select 1;\nselect * from users where id = 1 ;";
+        let result = parse(input);
+
+        let where_clause = result.statements()[1]
+            .descendants()
+            .find(|n| n.kind() == SyntaxKind::AExpr)
+            .unwrap();
+
+        assert_eq!(node_text(input, where_clause), "id = 1");
+        assert_eq!(
+            node_text(input, where_clause),
+            where_clause.resolved().text().to_string()
+        );
+    }
+
+    #[test]
+    fn test_subtree_text() {
+        let input = "select 1;\nselect * from users where id = 1 -- by id\n  and name = 'x';";
+        let result = parse(input);
+
+        assert_eq!(subtree_text(result.root()), input);
+        assert_eq!(
+            subtree_text(result.root()),
+            result.root().resolved().text().to_string()
+        );
+
+        let where_clause = result.statements()[1]
+            .descendants()
+            .find(|n| n.kind() == SyntaxKind::BoolExpr)
+            .unwrap();
+        assert_eq!(subtree_text(where_clause), node_text(input, where_clause));
+        assert!(subtree_text(where_clause).starts_with("id = 1 -- by id\n  and"));
+    }
+
+    #[test]
+    fn test_edit_touches_only_trivia() {
+        let input = "select 'a b' from t -- all rows\n/* block */ where x =  1;";
+        let result = parse(input);
+        let only_trivia = |text: &str, delta: usize, len: u32, replacement: &str| {
+            let offset = TextSize::from((input.find(text).unwrap() + delta) as u32);
+            let edit = TextEdit::replace(TextRange::at(offset, len.into()), replacement);
+            edit_touches_only_trivia(result.root(), &edit)
+        };
+
+        assert!(only_trivia("  1", 0, 1, ""));
+        assert!(only_trivia(" from", 0, 0, "\n  "));
+        assert!(only_trivia("all", 0, 3, "some"));
+        assert!(only_trivia("block", 0, 5, "comment"));
+
+        assert!(!only_trivia("a b", 1, 1, "  "));
+        assert!(!only_trivia(" from", 0, 1, ""));
+        assert!(!only_trivia(" from", 0, 0, "x"));
+        assert!(!only_trivia("all", 0, 0, "\n"));
+        assert!(!only_trivia("block", 0, 0, "*/"));
+        assert!(!only_trivia("\n/*", 0, 1, " "));
+        assert!(only_trivia("\n/*", 0, 1, "\n\n"));
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        let depth = |input: &str| {
+            let mut parser = Parser::new();
+            parser.parse_statement(input, None);
+            nesting_depth(&parser.finish().cst)
+        };
+
+        assert_eq!(depth("select 1;"), 0);
+        assert_eq!(
+            depth("select count(*), coalesce(a, (b)) from t where id in (1, 2);"),
+            0
+        );
+        assert_eq!(
+            depth(
+                "with x as (select 1) select * from x where id in (select id from (select 1) s);"
+            ),
+            2
+        );
+        assert_eq!(
+            depth("select * from (select * from (select * from (select 1) a) b) c;"),
+            3
+        );
+    }
+
+    #[test]
+    fn test_ancestors() {
+        let result = parse("select 1;\nselect a from t where lower(id) = 1;");
+
+        let column = result.statements()[1]
+            .descendants()
+            .find(|n| n.kind() == SyntaxKind::ColumnRef && n.resolved().text() == "id")
+            .unwrap();
+
+        assert_eq!(
+            ancestors(column).map(|n| n.kind()).collect::<Vec<_>>(),
+            vec![
+                SyntaxKind::FuncCall,
+                SyntaxKind::AExpr,
+                SyntaxKind::SelectStmt,
+                SyntaxKind::SourceFile
+            ]
+        );
+    }
+}
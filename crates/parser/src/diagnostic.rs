@@ -0,0 +1,94 @@
+use std::fmt;
+
+use cstree::text::TextRange;
+
+use crate::fix::Fix;
+use crate::syntax_error::SyntaxError;
+
+/// The severity of a `Diagnostic`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Hint,
+    Info,
+    Warning,
+    Error,
+}
+
+/// Represents a finding in a source file, e.g. a syntax error or a lint violation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    message: String,
+    range: TextRange,
+    severity: Severity,
+    fix: Option<Fix>,
+    rule: Option<String>,
+}
+
+impl Diagnostic {
+    /// Creates a new diagnostic with `Severity::Warning`
+    pub fn new(message: impl Into<String>, range: TextRange) -> Self {
+        Self {
+            message: message.into(),
+            range,
+            severity: Severity::Warning,
+            fix: None,
+            rule: None,
+        }
+    }
+
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    pub fn with_range(mut self, range: TextRange) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Attaches a `fix` that resolves the diagnostic
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+
+    /// Attaches the id of the lint `rule` that reported the diagnostic, e.g. `no-select-star`
+    pub fn with_rule(mut self, rule: impl Into<String>) -> Self {
+        self.rule = Some(rule.into());
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.range
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn fix(&self) -> Option<&Fix> {
+        self.fix.as_ref()
+    }
+
+    /// Returns the id of the lint rule that reported the diagnostic, which syntax errors do not
+    /// have
+    pub fn rule(&self) -> Option<&str> {
+        self.rule.as_deref()
+    }
+}
+
+impl From<SyntaxError> for Diagnostic {
+    fn from(error: SyntaxError) -> Self {
+        Diagnostic::new(error.to_string(), error.range()).with_severity(Severity::Error)
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.message.fmt(f)
+    }
+}
@@ -0,0 +1,210 @@
+//! A structural diff between two statements.
+//!
+//! Instead of a full tree edit distance, the children of two nodes of the same kind are aligned
+//! by their longest common subsequence, keyed on their kind and their tokens. Unaligned children
+//! of the same kind are diffed recursively, and all others are reported as added or removed.
+
+use cstree::text::TextRange;
+
+use crate::ast::non_trivia_tokens;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// A difference between two trees
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NodeDiff {
+    /// A node of the new tree at `range` has no counterpart in the old tree
+    Added { kind: SyntaxKind, range: TextRange },
+    /// A node of the old tree at `range` has no counterpart in the new tree
+    Removed { kind: SyntaxKind, range: TextRange },
+    /// The tokens of a node changed, while the nodes within it did not
+    Changed {
+        kind: SyntaxKind,
+        old: TextRange,
+        new: TextRange,
+    },
+}
+
+/// Returns the structural differences from statement `a` to statement `b`
+pub fn diff_statements(a: &SyntaxNode, b: &SyntaxNode) -> Vec<NodeDiff> {
+    let mut diffs = Vec::new();
+    if a.kind() == b.kind() {
+        diff_nodes(a, b, &mut diffs);
+    } else {
+        diffs.push(removed(a));
+        diffs.push(added(b));
+    }
+    diffs
+}
+
+/// The key of a node, made up of its kind and the text of its non-trivia tokens
+fn key(node: &SyntaxNode) -> (SyntaxKind, Vec<String>) {
+    let tokens = non_trivia_tokens(node)
+        .into_iter()
+        .map(|t| t.resolved().text().to_string())
+        .collect();
+    (node.kind(), tokens)
+}
+
+fn added(node: &SyntaxNode) -> NodeDiff {
+    NodeDiff::Added {
+        kind: node.kind(),
+        range: node.text_range(),
+    }
+}
+
+fn removed(node: &SyntaxNode) -> NodeDiff {
+    NodeDiff::Removed {
+        kind: node.kind(),
+        range: node.text_range(),
+    }
+}
+
+/// Diffs two nodes of the same kind
+fn diff_nodes(a: &SyntaxNode, b: &SyntaxNode, diffs: &mut Vec<NodeDiff>) {
+    if key(a) == key(b) {
+        return;
+    }
+
+    let a_children = a.children().collect::<Vec<_>>();
+    let b_children = b.children().collect::<Vec<_>>();
+    let a_keys = a_children.iter().map(|n| key(n)).collect::<Vec<_>>();
+    let b_keys = b_children.iter().map(|n| key(n)).collect::<Vec<_>>();
+
+    let diffs_before = diffs.len();
+    let mut a_start = 0;
+    let mut b_start = 0;
+    for (a_idx, b_idx) in lcs(&a_keys, &b_keys)
+        .into_iter()
+        .chain([(a_children.len(), b_children.len())])
+    {
+        diff_gap(
+            &a_children[a_start..a_idx],
+            &b_children[b_start..b_idx],
+            diffs,
+        );
+        a_start = a_idx + 1;
+        b_start = b_idx + 1;
+    }
+
+    // the children are the same, so the tokens of the node itself changed
+    if diffs.len() == diffs_before {
+        diffs.push(NodeDiff::Changed {
+            kind: a.kind(),
+            old: a.text_range(),
+            new: b.text_range(),
+        });
+    }
+}
+
+/// Diffs the unaligned children between two aligned pairs
+///
+/// Every old child is paired with the next new child of the same kind. The paired children are
+/// diffed recursively, all others are reported as removed or added.
+fn diff_gap(a: &[&SyntaxNode], b: &[&SyntaxNode], diffs: &mut Vec<NodeDiff>) {
+    let mut b_used = vec![false; b.len()];
+    let mut b_next = 0;
+    for a_node in a {
+        match (b_next..b.len()).find(|idx| b[*idx].kind() == a_node.kind()) {
+            Some(idx) => {
+                diffs.extend(b[b_next..idx].iter().map(|n| added(n)));
+                b_used[b_next..=idx].iter_mut().for_each(|u| *u = true);
+                b_next = idx + 1;
+                diff_nodes(a_node, b[idx], diffs);
+            }
+            None => diffs.push(removed(a_node)),
+        }
+    }
+    diffs.extend(
+        b.iter()
+            .zip(b_used)
+            .filter(|(_, used)| !used)
+            .map(|(n, _)| added(n)),
+    );
+}
+
+/// Returns the index pairs of the longest common subsequence of `a` and `b`
+fn lcs<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    let mut lengths = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn parse(input: &str) -> SyntaxNode {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        parser.finish().cst.syntax().clone()
+    }
+
+    #[test]
+    fn test_added_target() {
+        let b = "select a, b from t;";
+
+        let diffs = diff_statements(&parse("select a from t;"), &parse(b));
+
+        assert_eq!(diffs.len(), 1);
+        match &diffs[0] {
+            NodeDiff::Added { kind, range } => {
+                assert_eq!(*kind, SyntaxKind::ResTarget);
+                assert_eq!(&b[*range], "b");
+            }
+            diff => panic!("unexpected diff {:?}", diff),
+        }
+    }
+
+    #[test]
+    fn test_changed_and_removed() {
+        let diffs = diff_statements(
+            &parse("select a, b from t where id = 1;"),
+            &parse("select a from t where id = 2;"),
+        );
+
+        let kinds = diffs
+            .iter()
+            .map(|d| match d {
+                NodeDiff::Added { kind, .. } => ("added", *kind),
+                NodeDiff::Removed { kind, .. } => ("removed", *kind),
+                NodeDiff::Changed { kind, .. } => ("changed", *kind),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                ("removed", SyntaxKind::ResTarget),
+                ("changed", SyntaxKind::AConst)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identical() {
+        assert!(diff_statements(&parse("select 1;"), &parse("select  1;")).is_empty());
+    }
+}
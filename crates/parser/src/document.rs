@@ -0,0 +1,201 @@
+//! A source file that is reparsed incrementally.
+//!
+//! Every statement of the source is parsed and linted on its own, and its diagnostics are cached.
+//! After an edit, only the statements that overlap the edit are parsed and linted again. The
+//! cached diagnostics of all statements after the edit are shifted by the change in length.
+
+use cstree::text::{TextRange, TextSize};
+
+use crate::diagnostic::Diagnostic;
+use crate::fix::{Fix, TextEdit};
+use crate::parser::parse;
+use crate::source_file::{SourceFileSplitter, SourceFileToken};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// A statement of a `Document`, with its cached diagnostics
+#[derive(Debug, Clone)]
+struct Statement {
+    range: TextRange,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Statement {
+    fn shift(&mut self, delta: i64) {
+        self.range = shift(self.range, delta);
+        for diagnostic in self.diagnostics.iter_mut() {
+            let mut shifted = diagnostic
+                .clone()
+                .with_range(shift(diagnostic.range(), delta));
+            if let Some(fix) = diagnostic.fix() {
+                let edits = fix
+                    .edits()
+                    .iter()
+                    .map(|e| TextEdit::replace(shift(e.range, delta), e.replacement.clone()))
+                    .collect();
+                shifted = shifted.with_fix(Fix::new(fix.title(), edits));
+            }
+            *diagnostic = shifted;
+        }
+    }
+}
+
+fn shift(range: TextRange, delta: i64) -> TextRange {
+    let offset = |size: TextSize| TextSize::from((i64::from(u32::from(size)) + delta) as u32);
+    TextRange::new(offset(range.start()), offset(range.end()))
+}
+
+/// A source file that keeps the diagnostics of every statement, and only reparses and relints
+/// the statements that are touched by an edit
+///
+/// `lint` is run on the node of every statement that is parsed. The diagnostics of the syntax
+/// errors and of `lint` are reported together.
+pub struct Document<L> {
+    text: String,
+    statements: Vec<Statement>,
+    lint: L,
+}
+
+impl<L: FnMut(&SyntaxNode) -> Vec<Diagnostic>> Document<L> {
+    pub fn new(text: impl Into<String>, lint: L) -> Self {
+        let mut document = Self {
+            text: text.into(),
+            statements: Vec::new(),
+            lint,
+        };
+        document.statements = document.parse_region(0, document.text.len());
+        document
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The diagnostics of all statements, ordered by their position
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.statements
+            .iter()
+            .flat_map(|s| s.diagnostics.iter().cloned())
+            .collect()
+    }
+
+    /// Replaces the text at `range` with `replacement`
+    ///
+    /// The statements that overlap `range` or touch it are parsed again. If the last of them is
+    /// no longer terminated by a semicolon, e.g. because the semicolon was removed, it now
+    /// extends into the next statement, which is parsed again as well.
+    pub fn apply_edit(&mut self, range: TextRange, replacement: &str) {
+        let delta = replacement.len() as i64 - i64::from(u32::from(range.len()));
+        self.text.replace_range(
+            usize::from(range.start())..usize::from(range.end()),
+            replacement,
+        );
+
+        let first = self
+            .statements
+            .iter()
+            .position(|s| s.range.end() >= range.start())
+            .unwrap_or(self.statements.len());
+        let mut last = self
+            .statements
+            .iter()
+            .rposition(|s| s.range.start() <= range.end())
+            .map_or(first, |idx| idx + 1)
+            .max(first);
+
+        // the region between the unchanged statements before and after the edit
+        let start = match first {
+            0 => 0,
+            _ => usize::from(self.statements[first - 1].range.end()),
+        };
+        let reparsed = loop {
+            let end = match self.statements.get(last) {
+                Some(s) => usize::from(shift(s.range, delta).start()),
+                None => self.text.len(),
+            };
+            let reparsed = self.parse_region(start, end);
+            let terminated = reparsed
+                .last()
+                .is_none_or(|s| self.text[s.range].ends_with(';'));
+            if terminated || last == self.statements.len() {
+                break reparsed;
+            }
+            last += 1;
+        };
+
+        for statement in self.statements[last..].iter_mut() {
+            statement.shift(delta);
+        }
+        self.statements.splice(first..last, reparsed);
+    }
+
+    /// Parses and lints the statements of the text from `start` to `end`
+    fn parse_region(&mut self, start: usize, end: usize) -> Vec<Statement> {
+        let text = &self.text[start..end];
+        let lint = &mut self.lint;
+        SourceFileSplitter::new(text)
+            .filter(|(token, _)| *token == SourceFileToken::Statement)
+            .map(|(_, span)| {
+                let result = parse(&text[span.clone()]);
+                let mut diagnostics = result.diagnostics().to_vec();
+                for stmt in result.statements() {
+                    if stmt.kind() != SyntaxKind::EmptyStatement {
+                        diagnostics.extend(lint(stmt));
+                    }
+                }
+                diagnostics.sort_by_key(|d| d.range().start());
+
+                // the statement was parsed on its own, so its ranges start at zero
+                let mut statement = Statement {
+                    range: TextRange::up_to(TextSize::from(span.len() as u32)),
+                    diagnostics,
+                };
+                statement.shift((start + span.start) as i64);
+                statement
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn test_diagnostics_are_shifted() {
+        let runs = Cell::new(0);
+        let lint = |stmt: &SyntaxNode| {
+            runs.set(runs.get() + 1);
+            vec![Diagnostic::new("statement", stmt.text_range())]
+        };
+        let mut document = Document::new("select 1;\nselect 2;\nselect 3;", lint);
+        assert_eq!(runs.get(), 3);
+
+        // `select 1;` becomes `select 100;`
+        document.apply_edit(TextRange::empty(TextSize::from(8)), "00");
+
+        assert_eq!(runs.get(), 4);
+        assert_eq!(document.text(), "select 100;\nselect 2;\nselect 3;");
+        let ranges = document
+            .diagnostics()
+            .iter()
+            .map(|d| &document.text()[d.range()])
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec!["select 100;", "select 2;", "select 3;"]);
+    }
+
+    #[test]
+    fn test_removed_semicolon() {
+        let mut document = Document::new("select 1;\nselect 2;\nselect 3;", |_: &SyntaxNode| {
+            Vec::new()
+        });
+
+        document.apply_edit(TextRange::at(TextSize::from(8), TextSize::from(1)), "");
+
+        let fresh = Document::new(document.text().to_string(), |_: &SyntaxNode| Vec::new());
+        assert_eq!(document.diagnostics(), fresh.diagnostics());
+        assert_eq!(document.diagnostics().len(), 1);
+    }
+}
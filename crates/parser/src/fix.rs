@@ -0,0 +1,110 @@
+//! Automatic fixes for diagnostics.
+//!
+//! A `Fix` is a set of text edits that resolves a diagnostic, e.g. the removal of a redundant
+//! semicolon. Editors offer them as code actions, and `apply_fixes` applies them to a source.
+
+use cstree::text::TextRange;
+
+/// Replaces the text at `range` with `replacement`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextEdit {
+    pub range: TextRange,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn replace(range: TextRange, replacement: impl Into<String>) -> Self {
+        Self {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+
+    pub fn delete(range: TextRange) -> Self {
+        Self::replace(range, "")
+    }
+}
+
+/// The edits that resolve a diagnostic, with a title that describes them, e.g. for a code action
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fix {
+    title: String,
+    edits: Vec<TextEdit>,
+}
+
+impl Fix {
+    pub fn new(title: impl Into<String>, edits: Vec<TextEdit>) -> Self {
+        Self {
+            title: title.into(),
+            edits,
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn edits(&self) -> &[TextEdit] {
+        &self.edits
+    }
+}
+
+/// Applies `fixes` to `text` and returns the result
+///
+/// The edits of a fix are applied together. A fix with an edit that overlaps an edit of an
+/// earlier fix is skipped, so that every fix is either applied completely or not at all.
+pub fn apply_fixes(text: &str, fixes: &[Fix]) -> String {
+    let mut edits: Vec<&TextEdit> = Vec::new();
+    for fix in fixes {
+        let overlaps = fix.edits.iter().enumerate().any(|(idx, edit)| {
+            edits
+                .iter()
+                .copied()
+                .chain(fix.edits[..idx].iter())
+                .any(|e| overlap(e.range, edit.range))
+        });
+        if !overlaps {
+            edits.extend(fix.edits.iter());
+        }
+    }
+    edits.sort_by_key(|e| e.range.start());
+
+    let mut result = String::with_capacity(text.len());
+    let mut pos = 0;
+    for edit in edits {
+        result.push_str(&text[pos..usize::from(edit.range.start())]);
+        result.push_str(&edit.replacement);
+        pos = usize::from(edit.range.end());
+    }
+    result.push_str(&text[pos..]);
+    result
+}
+
+/// Whether two edits at `a` and `b` conflict
+///
+/// Two insertions at the same position conflict, since their order would be ambiguous.
+fn overlap(a: TextRange, b: TextRange) -> bool {
+    (a.start() < b.end() && b.start() < a.end()) || a == b
+}
+
+#[cfg(test)]
+mod tests {
+    use cstree::text::TextSize;
+
+    use super::*;
+
+    fn range(start: u32, end: u32) -> TextRange {
+        TextRange::new(TextSize::from(start), TextSize::from(end))
+    }
+
+    #[test]
+    fn test_overlapping_fixes() {
+        let fixes = vec![
+            Fix::new("upper", vec![TextEdit::replace(range(0, 6), "SELECT")]),
+            Fix::new("rename", vec![TextEdit::replace(range(2, 4), "x")]),
+            Fix::new("semicolon", vec![TextEdit::replace(range(8, 8), ";")]),
+        ];
+
+        assert_eq!(apply_fixes("select 1", &fixes), "SELECT 1;");
+    }
+}
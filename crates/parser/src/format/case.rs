@@ -0,0 +1,108 @@
+use crate::parser::parse;
+use crate::syntax_kind::SyntaxKind;
+
+/// The letter case of a word in the source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// All letters are upper case, e.g. `SELECT`
+    Upper,
+    /// All letters are lower case, e.g. `select`
+    Lower,
+    /// Both cases occur, e.g. `Select`
+    Mixed,
+}
+
+/// Returns the case of the letters of `token_text`
+///
+/// Words without letters, e.g. `_`, are lower case.
+pub fn original_case(token_text: &str) -> Case {
+    let has_upper = token_text.chars().any(char::is_uppercase);
+    let has_lower = token_text.chars().any(char::is_lowercase);
+    match (has_upper, has_lower) {
+        (true, true) => Case::Mixed,
+        (true, false) => Case::Upper,
+        (false, _) => Case::Lower,
+    }
+}
+
+/// How `format_keyword_case` writes keywords
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum KeywordCase {
+    /// Every keyword keeps its `original_case`
+    #[default]
+    Preserve,
+    Upper,
+    Lower,
+}
+
+impl KeywordCase {
+    pub(super) fn apply(&self, keyword: &str) -> String {
+        match self {
+            KeywordCase::Preserve => keyword.to_string(),
+            KeywordCase::Upper => keyword.to_uppercase(),
+            KeywordCase::Lower => keyword.to_lowercase(),
+        }
+    }
+}
+
+/// Rewrites the keywords of the source `text` in the case `case`
+///
+/// Keywords are the words that pg_query.rs scans as keywords, e.g. `select` or `int`. This includes
+/// unreserved keywords that are used as names, e.g. the column `name`, which is recased as well.
+/// Since unquoted names are folded to lower case, this does not change what they refer to. Other
+/// unquoted identifiers, quoted identifiers, literals and comments are kept verbatim, and so are
+/// all words of statements that pg_query.rs fails to scan.
+pub fn format_keyword_case(text: &str, case: KeywordCase) -> String {
+    let result = parse(text);
+
+    let mut formatted = String::with_capacity(text.len());
+    for token in result
+        .root()
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+    {
+        let token_text = token.resolved().text();
+        if is_keyword(token.kind(), token_text) {
+            formatted.push_str(&case.apply(token_text));
+        } else {
+            formatted.push_str(token_text);
+        }
+    }
+    formatted
+}
+
+/// Whether a token of `kind` with the source `text` is scanned as a keyword, regardless of
+/// whether it is used as a name
+pub(super) fn is_keyword(kind: SyntaxKind, text: &str) -> bool {
+    !kind.is_trivia()
+        && !matches!(kind, SyntaxKind::Ident | SyntaxKind::Word)
+        && text.starts_with(|c: char| c.is_alphabetic() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_original_case() {
+        assert_eq!(original_case("SELECT"), Case::Upper);
+        assert_eq!(original_case("select"), Case::Lower);
+        assert_eq!(original_case("Select"), Case::Mixed);
+    }
+
+    #[test]
+    fn test_format_keyword_case() {
+        // `name` is an unreserved keyword, and is recased even though it is used as a column
+        let input = "Select id, Name, \"Type\" from Users where x = 'Select' -- Select\n  AND y IS not null;";
+
+        assert_eq!(format_keyword_case(input, KeywordCase::Preserve), input);
+        assert_eq!(
+            format_keyword_case(input, KeywordCase::Upper),
+            "SELECT id, NAME, \"Type\" FROM Users WHERE x = 'Select' -- Select\n  AND y IS NOT NULL;"
+        );
+        assert_eq!(
+            format_keyword_case(input, KeywordCase::Lower),
+            "select id, name, \"Type\" from Users where x = 'Select' -- Select\n  and y is not null;"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use cstree::text::{TextRange, TextSize};
+
+use crate::format::case::{is_keyword, KeywordCase};
+use crate::format::reindent::{reindent_tokens, IndentOptions};
+use crate::parser::parse;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// Options for `format_with_mapping`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FormatOptions {
+    /// The case of the keywords
+    pub keyword_case: KeywordCase,
+    /// How lines are re-indented, or `None` to keep their indentation
+    pub indent: Option<IndentOptions>,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            keyword_case: KeywordCase::Preserve,
+            indent: Some(IndentOptions::default()),
+        }
+    }
+}
+
+/// Formats the source `text` like `format_keyword_case` and `reindent`, and returns the formatted
+/// text along with the ranges of all tokens that are not trivia in `text` and in the formatted
+/// text
+///
+/// The mapping is ordered by position, which lets an editor move a cursor or a diagnostic from
+/// the old text into the formatted one.
+pub fn format_with_mapping(
+    text: &str,
+    opts: FormatOptions,
+) -> (String, Vec<(TextRange, TextRange)>) {
+    let result = parse(text);
+    let root: &SyntaxNode = result.root();
+    let tokens = root
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token());
+
+    let mut formatted = String::with_capacity(text.len());
+    let mut mapping = Vec::new();
+    let mut emit = |token: &SyntaxToken, indent: &str| {
+        formatted.push_str(indent);
+        let token_text = token.resolved().text();
+        let new_text = if is_keyword(token.kind(), token_text) {
+            opts.keyword_case.apply(token_text)
+        } else {
+            token_text.to_string()
+        };
+        if !token.kind().is_trivia() {
+            mapping.push((
+                token.text_range(),
+                TextRange::at(
+                    TextSize::of(formatted.as_str()),
+                    TextSize::of(new_text.as_str()),
+                ),
+            ));
+        }
+        formatted.push_str(&new_text);
+    };
+    match opts.indent {
+        Some(indent) => reindent_tokens(tokens, indent, emit),
+        None => tokens.for_each(|token| emit(token, "")),
+    }
+    (formatted, mapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_with_mapping() {
+        let input = "select id,\n      email\n    from users where id = 1;";
+        let (formatted, mapping) = format_with_mapping(
+            input,
+            FormatOptions {
+                keyword_case: KeywordCase::Upper,
+                indent: Some(IndentOptions::default()),
+            },
+        );
+        assert_eq!(formatted, "SELECT id,\n  email\nFROM users WHERE id = 1;");
+
+        let old = TextRange::at(
+            TextSize::from(input.find("users").unwrap() as u32),
+            5.into(),
+        );
+        let (_, new) = mapping.iter().find(|(o, _)| *o == old).unwrap();
+        assert_eq!(&formatted[*new], "users");
+        assert_eq!(mapping.len(), 11);
+        assert!(mapping
+            .iter()
+            .all(|(old, new)| input[*old].to_uppercase() == formatted[*new].to_uppercase()));
+    }
+
+    #[test]
+    fn test_keep_indentation() {
+        let input = "select 1\n    from t;";
+        let opts = FormatOptions {
+            keyword_case: KeywordCase::Lower,
+            indent: None,
+        };
+        assert_eq!(format_with_mapping(input, opts).0, input);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::parser::parse;
+
+/// Whether `c` is part of a word-like token, i.e. a keyword, identifier, number or quoted string
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '$' | '\'' | '"')
+}
+
+/// Whether `c` is part of an operator, e.g. `<=` or `--`
+fn is_operator_char(c: char) -> bool {
+    matches!(
+        c,
+        '+' | '-'
+            | '*'
+            | '/'
+            | '<'
+            | '>'
+            | '='
+            | '~'
+            | '!'
+            | '@'
+            | '#'
+            | '%'
+            | '^'
+            | '&'
+            | '|'
+            | '`'
+            | '?'
+    )
+}
+
+/// Returns the separator to emit between two tokens that are separated by trivia in the source
+///
+/// A space is kept if the tokens would otherwise fuse into one, e.g. `select 1` or `- -1`.
+fn separator(prev: &str, next: &str) -> &'static str {
+    match (prev.chars().last(), next.chars().next()) {
+        (Some(a), Some(b))
+            if (is_word_char(a) && is_word_char(b))
+                || (is_operator_char(a) && is_operator_char(b)) =>
+        {
+            " "
+        }
+        _ => "",
+    }
+}
+
+/// Minifies the source `text` into a single line
+///
+/// Comments are removed, and every run of whitespace is collapsed into a single space, or removed
+/// if the surrounding tokens do not need to be separated. The content of all other tokens,
+/// including string literals and dollar quoted strings, is kept verbatim. String literals that
+/// are continued on the next line are a single token, so they keep their newline.
+pub fn minify(text: &str) -> String {
+    let result = parse(text);
+
+    let mut minified = String::with_capacity(text.len());
+    let mut prev: Option<String> = None;
+    let mut has_trivia = false;
+    for token in result
+        .root()
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+    {
+        let token_text = token.resolved().text();
+        if token.kind().is_trivia() {
+            has_trivia = true;
+            continue;
+        }
+
+        if let Some(prev_text) = &prev {
+            if has_trivia {
+                minified.push_str(separator(prev_text, token_text));
+            }
+        }
+        minified.push_str(token_text);
+        prev = Some(token_text.to_string());
+        has_trivia = false;
+    }
+    minified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deparse(text: &str) -> String {
+        pg_query::deparse(&pg_query::parse(text).unwrap().protobuf).unwrap()
+    }
+
+    #[test]
+    fn test_minify() {
+        let input = "-- all admins
+select id,   name, /* the email */ email
+from users
+where role = 'admin'   -- a comment
+  and id > - -1
+  and bio = $$ keep   this $$;
+
+select 'con'
+  'cat';
+";
+
+        let minified = minify(input);
+
+        assert_eq!(
+            minified,
+            "select id,name,email from users where role='admin' and id> - -1 and bio=$$ keep   this $$;select 'con'\n  'cat';"
+        );
+        assert_eq!(deparse(&minified), deparse(input));
+    }
+}
@@ -0,0 +1,14 @@
+//! Source transformations that re-emit the text of a cst.
+//!
+//! All transformations only touch trivia and the case of keywords, and leave the content of all
+//! other tokens untouched.
+
+mod case;
+mod mapping;
+mod minify;
+mod reindent;
+
+pub use case::{format_keyword_case, original_case, Case, KeywordCase};
+pub use mapping::{format_with_mapping, FormatOptions};
+pub use minify::minify;
+pub use reindent::{reindent, IndentOptions};
@@ -0,0 +1,167 @@
+use crate::parser::Parser;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// Options for `reindent`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IndentOptions {
+    /// The number of spaces per indentation level. Ignored if `use_tabs` is true.
+    pub width: usize,
+    /// Whether to indent with one tab per level instead of spaces
+    pub use_tabs: bool,
+}
+
+impl Default for IndentOptions {
+    fn default() -> Self {
+        Self {
+            width: 2,
+            use_tabs: false,
+        }
+    }
+}
+
+impl IndentOptions {
+    fn indent(&self, level: usize) -> String {
+        if self.use_tabs {
+            "\t".repeat(level)
+        } else {
+            " ".repeat(level * self.width)
+        }
+    }
+}
+
+/// Whether a line that starts with a token of `kind` starts a clause of a statement
+fn starts_clause(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Select
+            | SyntaxKind::From
+            | SyntaxKind::Where
+            | SyntaxKind::GroupP
+            | SyntaxKind::Having
+            | SyntaxKind::Window
+            | SyntaxKind::Order
+            | SyntaxKind::Limit
+            | SyntaxKind::Offset
+            | SyntaxKind::Union
+            | SyntaxKind::Intersect
+            | SyntaxKind::Except
+            | SyntaxKind::With
+            | SyntaxKind::Insert
+            | SyntaxKind::Update
+            | SyntaxKind::DeleteP
+            | SyntaxKind::Set
+            | SyntaxKind::Values
+            | SyntaxKind::Returning
+    )
+}
+
+/// Re-indents the statement `stmt_text`, replacing the leading whitespace of every line
+///
+/// The indentation level of a line is the number of parentheses that are open at its start. Lines
+/// that do not start a clause, e.g. the continuation of a select list, are indented by one more
+/// level. Only the whitespace at the start of lines is changed, so the content of tokens, including
+/// multi-line string literals and comments, is left untouched.
+pub fn reindent(stmt_text: &str, opts: IndentOptions) -> String {
+    let mut parser = Parser::new();
+    parser.parse_statement(stmt_text, None);
+    let parsed = parser.finish();
+    let root: &SyntaxNode = &parsed.cst;
+
+    let mut result = String::with_capacity(stmt_text.len());
+    reindent_tokens(
+        root.descendants_with_tokens()
+            .filter_map(|e| e.into_token()),
+        opts,
+        |token, indent| {
+            result.push_str(indent);
+            result.push_str(token.resolved().text());
+        },
+    );
+    result
+}
+
+/// Re-indents `tokens` like `reindent`, and calls `emit` with every token that is kept along
+/// with the indentation to write before it
+pub(super) fn reindent_tokens<'a>(
+    tokens: impl IntoIterator<Item = &'a SyntaxToken>,
+    opts: IndentOptions,
+    mut emit: impl FnMut(&'a SyntaxToken, &str),
+) {
+    let mut depth: usize = 0;
+    let mut at_line_start = true;
+    for token in tokens {
+        let kind = token.kind();
+        if at_line_start && matches!(kind, SyntaxKind::Whitespace | SyntaxKind::Tab) {
+            continue;
+        }
+
+        if kind == SyntaxKind::Ascii41 {
+            depth = depth.saturating_sub(1);
+        }
+        let mut indent = String::new();
+        if at_line_start && kind != SyntaxKind::Newline {
+            let level = if kind == SyntaxKind::Ascii41 || starts_clause(kind) {
+                depth
+            } else {
+                depth + 1
+            };
+            indent = opts.indent(level);
+        }
+        if kind == SyntaxKind::Ascii40 {
+            depth += 1;
+        }
+
+        emit(token, &indent);
+        at_line_start = kind == SyntaxKind::Newline;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reindent() {
+        let input = "   select id,
+        name,
+\t  'multi
+    line'
+ from users
+      where id in (
+ select user_id
+            from admins
+    )
+  order by id;";
+
+        assert_eq!(
+            reindent(input, IndentOptions::default()),
+            "select id,
+  name,
+  'multi
+    line'
+from users
+where id in (
+  select user_id
+  from admins
+)
+order by id;"
+        );
+    }
+
+    #[test]
+    fn test_reindent_with_tabs() {
+        let input = "select id,\n    name\n  from users;";
+
+        assert_eq!(
+            reindent(
+                input,
+                IndentOptions {
+                    width: 4,
+                    use_tabs: true
+                }
+            ),
+            "select id,\n\tname\nfrom users;"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Highlighting scopes for tokens.
+//!
+//! Complements the semantic tokens of the language server for editors that highlight with
+//! TextMate grammars.
+
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxToken;
+
+/// Returns the TextMate scope of `token`
+///
+/// Keywords are tokenized with their own kind, e.g. `Select`, which is shared with the keywords
+/// that are used as names. Hence, the caller decides with `is_keyword` whether the token is used
+/// as a keyword. Both comment styles are tokens of the `Comment` kind, which are told apart by
+/// their text.
+pub fn textmate_scope(token: &SyntaxToken, is_keyword: bool) -> &'static str {
+    if is_keyword {
+        return "keyword.other.sql";
+    }
+    match token.kind() {
+        SyntaxKind::Sconst | SyntaxKind::Usconst => "string.quoted.single.sql",
+        SyntaxKind::Iconst | SyntaxKind::Fconst | SyntaxKind::Bconst | SyntaxKind::Xconst => {
+            "constant.numeric.sql"
+        }
+        SyntaxKind::Comment if token.resolved().text().starts_with("--") => {
+            "comment.line.double-dash.sql"
+        }
+        SyntaxKind::Comment => "comment.block.sql",
+        SyntaxKind::Shebang => "comment.line.shebang.sql",
+        SyntaxKind::CopyData => "string.unquoted.copy-data.sql",
+        SyntaxKind::Param => "variable.parameter.sql",
+        SyntaxKind::Uident => "string.quoted.double.sql",
+        SyntaxKind::ComparisonOp => "keyword.operator.comparison.sql",
+        SyntaxKind::ArithmeticOp => "keyword.operator.arithmetic.sql",
+        SyntaxKind::LogicalOp => "keyword.operator.logical.sql",
+        SyntaxKind::JsonOp => "keyword.operator.json.sql",
+        SyntaxKind::PatternOp => "keyword.operator.pattern.sql",
+        SyntaxKind::Op | SyntaxKind::Typecast | SyntaxKind::Ascii42 => "keyword.operator.sql",
+        SyntaxKind::Ascii44 => "punctuation.separator.comma.sql",
+        SyntaxKind::Ascii46 => "punctuation.separator.period.sql",
+        SyntaxKind::Ascii59 => "punctuation.terminator.statement.sql",
+        SyntaxKind::Ascii40 | SyntaxKind::Ascii41 => "punctuation.section.parens.sql",
+        SyntaxKind::Ascii91 | SyntaxKind::Ascii93 => "punctuation.section.brackets.sql",
+        _ => "source.sql",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_textmate_scope() {
+        let input = "select 'a', x -- line\n/* block */ from t;";
+        let result = parse(input);
+        let scopes = result
+            .root()
+            .descendants_with_tokens()
+            .filter_map(|e| e.into_token())
+            .filter(|t| !t.kind().is_trivia() || t.kind() == SyntaxKind::Comment)
+            .map(|t| {
+                let is_keyword = matches!(t.kind(), SyntaxKind::Select | SyntaxKind::From);
+                (t.resolved().text(), textmate_scope(t, is_keyword))
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            scopes,
+            vec![
+                ("select", "keyword.other.sql"),
+                ("'a'", "string.quoted.single.sql"),
+                (",", "punctuation.separator.comma.sql"),
+                ("x", "source.sql"),
+                ("-- line", "comment.line.double-dash.sql"),
+                ("/* block */", "comment.block.sql"),
+                ("from", "keyword.other.sql"),
+                ("t", "source.sql"),
+                (";", "punctuation.terminator.statement.sql"),
+            ]
+        );
+    }
+}
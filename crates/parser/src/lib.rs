@@ -2,7 +2,8 @@
 //!
 //! This crate provides a parser for the Postgres SQL dialect.
 //! It is based in the pg_query.rs crate, which is a wrapper around the PostgreSQL query parser.
-//! The main `Parser` struct parses a source file and individual statements.
+//! The main `Parser` struct parses a source file and individual statements, and `parse` is a shortcut
+//! to parse a source file.
 //! The `Parse` struct contains the resulting concrete syntax tree, syntax errors, and the abtract syntax tree, which is a list of pg_query statements and their positions.
 //!
 //! The idea is to offload the heavy lifting to the same parser that the PostgreSQL server uses,
@@ -15,15 +16,103 @@
 //!
 //! To see how these drawbacks are mitigated, see the `statement.rs` and the `source_file.rs` module.
 
+mod ast;
 mod ast_node;
+mod batch;
+mod comments;
+mod completion;
+mod cst_utils;
+mod diagnostic;
+mod diff;
+mod document;
+mod fix;
+mod format;
+mod highlight;
+mod line_index;
+mod lint;
+#[cfg(feature = "lsp")]
+mod lsp;
+mod metrics;
+mod migration;
+mod navigation;
+mod output;
 mod parser;
 mod pg_query_utils;
+mod refactor;
+mod schema;
+mod sexp;
 mod source_file;
 mod statement;
+mod symbols;
 mod syntax_error;
 mod syntax_kind;
 mod syntax_node;
 
-pub use crate::parser::{Parse, Parser};
-pub use crate::syntax_kind::SyntaxKind;
+pub use crate::ast::{
+    aggregates, all_set_operations, case_expressions, clause_context, collations,
+    column_definitions, column_lineage, copy_statement, deallocate_statement, distinct,
+    execute_statement, foreign_key_actions, from_items, generated_columns, having, index_statement,
+    insert_values, is_idempotent_ddl, lateral_joins, limit, limit_clause, order_by,
+    prepare_statement, returning_clause, select_targets, set_operations, statement_category,
+    subqueries, table_constraints, tablesample, transaction_blocks, type_references, values_rows,
+    view_statement, window_frames, AggregateCall, CaseArm, CaseExpr, ClauseContext,
+    ColumnDefinition, ConstraintKind, CopyDirection, CopySource, CopyStmt, CopyTarget,
+    DeallocateStmt, DistinctClause, ExecuteStmt, FkAction, FrameBound, FrameMode, FromItem,
+    FromItemKind, GeneratedColumn, GeneratedKind, IndexElement, IndexStmt, LimitClause,
+    LineageEdge, LineageSource, MatchType, PrepareStmt, ReferentialAction, SelectStmt,
+    SelectTarget, SetOp, SetOpKind, SetOperand, StatementCategory, Subquery, SubqueryKind,
+    TableConstraint, TableSample, TransactionBlock, ViewStmt, WindowFrame,
+};
+pub use crate::batch::{parse_files, FileResult};
+pub use crate::comments::{comments, CommentInfo, CommentKind};
+pub use crate::completion::{completions, CompletionItem, CompletionKind};
+pub use crate::cst_utils::{
+    ancestors, edit_touches_only_trivia, nesting_depth, node_text, subtree_text, tokens_in_range,
+};
+pub use crate::diagnostic::{Diagnostic, Severity};
+pub use crate::diff::{diff_statements, NodeDiff};
+pub use crate::document::Document;
+pub use crate::fix::{apply_fixes, Fix, TextEdit};
+pub use crate::format::{
+    format_keyword_case, format_with_mapping, minify, original_case, reindent, Case, FormatOptions,
+    IndentOptions, KeywordCase,
+};
+pub use crate::highlight::textmate_scope;
+pub use crate::line_index::LineIndex;
+pub use crate::lint::{
+    ambiguous_columns, blocking_index_creation, case_without_else, check_statement_policy,
+    concurrently_in_transaction, discourage_varchar, distinct_on_without_order, duplicate_columns,
+    duplicate_output_aliases, equality_with_null, excessive_nesting, having_without_group_by,
+    implicit_coercions, insert_value_counts, insert_without_columns, likely_missing_not_null,
+    limit_without_order, line_length, naming_violations, natural_joins, non_idempotent_ddl,
+    non_sargable_predicates, not_in_subquery, or_chains_to_in, order_by_ordinals,
+    prepared_statement_usage, redundant_semicolons, reversed_between, run_lints, select_star,
+    tables_without_pk, todo_comments, trailing_whitespace, unbounded_range_frames,
+    unnecessary_union, values_row_lengths, volatile_generated_columns, BlockingIndexExemptions,
+    CaseElseExemptions, Config, IdempotentDdlOptions, LineLengthOptions, Lint, LintRegistry,
+    NamingConvention, PrimaryKeyExemptions, RuleConfig, SargableOptions, StatementPolicy,
+    VarcharOptions, DEFAULT_MAX_NESTING_DEPTH, DEFAULT_NOT_NULL_PATTERNS, DEFAULT_TODO_MARKERS,
+};
+#[cfg(feature = "lsp")]
+pub use crate::lsp::to_lsp_document_symbols;
+pub use crate::metrics::{statement_metrics, Metrics};
+pub use crate::migration::{affected_tables, StatementKind};
+pub use crate::navigation::{find_references, goto_definition, statement_after, statement_before};
+pub use crate::output::{
+    format_diagnostics, DiagnosticFormatter, JsonFormatter, OutputFormat, SarifFormatter,
+    TextFormatter,
+};
+pub use crate::parser::{
+    parse, parse_bytes, parse_streaming, validate, Parse, ParseResult, Parser,
+};
+pub use crate::refactor::{extractable_literals, parameterize};
+pub use crate::schema::{
+    ColumnInfo, FunctionInfo, QualifiedName, SchemaProvider, StaticSchema, DEFAULT_SCHEMA,
+};
+pub use crate::sexp::to_sexp;
+pub use crate::symbols::{document_symbols, DocumentSymbol, SymbolKind};
+pub use crate::syntax_kind::{syntax_kind_name, SyntaxKind};
 pub use crate::syntax_node::{SyntaxElement, SyntaxNode, SyntaxToken};
+pub use cstree::syntax::ResolvedNode;
+pub use cstree::text::{TextRange, TextSize};
+pub use encoding_rs::Encoding;
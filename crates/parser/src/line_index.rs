@@ -0,0 +1,101 @@
+//! Conversion of offsets into lines and columns.
+
+use cstree::text::{TextRange, TextSize};
+
+/// The offsets of the starts of all lines of a source text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    text: String,
+    line_starts: Vec<TextSize>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(idx, _)| idx + 1))
+            .map(|idx| TextSize::from(idx as u32))
+            .collect();
+        Self {
+            text: text.to_string(),
+            line_starts,
+        }
+    }
+
+    /// Returns the zero-based line and column of `offset`
+    ///
+    /// The column counts the characters from the start of the line, not the bytes.
+    pub fn line_col(&self, offset: TextSize) -> (u32, u32) {
+        let (line, prefix) = self.line_prefix(offset);
+        (line, prefix.chars().count() as u32)
+    }
+
+    /// Returns the zero-based line and column of `offset`, with the column in UTF-16 code units
+    /// as in the language server protocol
+    pub fn line_col_utf16(&self, offset: TextSize) -> (u32, u32) {
+        let (line, prefix) = self.line_prefix(offset);
+        (line, prefix.encode_utf16().count() as u32)
+    }
+
+    /// Returns the number of lines, which is one more than the number of newlines
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the range of the zero-based line `line` without its line break, if the text has
+    /// this line
+    pub fn line_range(&self, line: usize) -> Option<TextRange> {
+        let start = *self.line_starts.get(line)?;
+        let end = match self.line_starts.get(line + 1) {
+            Some(next) => *next - TextSize::of('\n'),
+            None => TextSize::of(self.text.as_str()),
+        };
+        let text = &self.text[usize::from(start)..usize::from(end)];
+        Some(TextRange::new(
+            start,
+            start + TextSize::of(text.trim_end_matches('\r')),
+        ))
+    }
+
+    /// Returns the line of `offset`, and the text of the line before `offset`
+    fn line_prefix(&self, offset: TextSize) -> (u32, &str) {
+        let offset = offset.min(TextSize::of(self.text.as_str()));
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let start = usize::from(self.line_starts[line]);
+        (line as u32, &self.text[start..usize::from(offset)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_col() {
+        let index = LineIndex::new("select 1;\nselect 'ä', x;\n");
+        let col = |offset: u32| index.line_col(TextSize::from(offset));
+
+        assert_eq!(col(0), (0, 0));
+        assert_eq!(col(9), (0, 9));
+        assert_eq!(col(10), (1, 0));
+        // `ä` takes two bytes
+        assert_eq!(col(23), (1, 12));
+        assert_eq!(col(100), (2, 0));
+
+        let index = LineIndex::new("select '😀', x;");
+        assert_eq!(index.line_col(TextSize::from(13)), (0, 10));
+        assert_eq!(index.line_col_utf16(TextSize::from(13)), (0, 11));
+    }
+
+    #[test]
+    fn test_line_range() {
+        let text = "select 1;\r\n\nselect 2;";
+        let index = LineIndex::new(text);
+        let line = |line: usize| index.line_range(line).map(|r| &text[r]);
+
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(line(0), Some("select 1;"));
+        assert_eq!(line(1), Some(""));
+        assert_eq!(line(2), Some("select 2;"));
+        assert_eq!(line(3), None);
+    }
+}
@@ -0,0 +1,170 @@
+use pg_query::protobuf::{Node, SelectStmt};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{location_to_offset, name_token_at, selects_with_parent};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::pg_query_utils::get_children;
+use crate::schema::{QualifiedName, SchemaProvider};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns an error for every unqualified column reference in `stmt` that matches a column of
+/// more than one table in the `FROM` clause of its select
+///
+/// The columns of the tables are looked up in `schema`, and tables that `schema` does not know are
+/// ignored. Columns that are merged by `JOIN ... USING` are not ambiguous. The diagnostic covers
+/// the column reference.
+pub fn ambiguous_columns(stmt: &SyntaxNode, schema: &dyn SchemaProvider) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for (select, _) in selects_with_parent(stmt) {
+        let ast = select.ast();
+        let mut tables = Vec::new();
+        let mut merged = Vec::new();
+        for item in ast.from_clause.iter() {
+            collect_tables(item, &mut tables, &mut merged);
+        }
+        let tables = tables
+            .into_iter()
+            .map(|table| (schema.columns(&table), table))
+            .filter(|(columns, _)| !columns.is_empty())
+            .collect::<Vec<_>>();
+        if tables.len() < 2 {
+            continue;
+        }
+
+        let mut references = Vec::new();
+        for clause in clauses(ast) {
+            collect_references(clause.to_ref(), &mut references);
+        }
+        for (name, location) in references {
+            if merged.contains(&name) {
+                continue;
+            }
+            let matches = tables
+                .iter()
+                .filter(|(columns, _)| columns.iter().any(|c| c.name == name))
+                .map(|(_, table)| format!("`{}`", table))
+                .collect::<Vec<_>>();
+            if matches.len() < 2 {
+                continue;
+            }
+            let range = match location_to_offset(stmt, location)
+                .and_then(|offset| name_token_at(stmt, offset))
+            {
+                Some(token) => token.text_range(),
+                None => continue,
+            };
+            diagnostics.push(
+                Diagnostic::new(
+                    format!(
+                        "column reference `{}` is ambiguous, it exists in {}",
+                        name,
+                        matches.join(" and ")
+                    ),
+                    range,
+                )
+                .with_severity(Severity::Error),
+            );
+        }
+    }
+    diagnostics.sort_by_key(|d| d.range().start());
+    diagnostics
+}
+
+/// Collects the tables of the `FROM` clause item `node`, and the columns merged by `USING`
+fn collect_tables(node: &Node, tables: &mut Vec<QualifiedName>, merged: &mut Vec<String>) {
+    match node.node.as_ref() {
+        Some(NodeEnum::RangeVar(range_var)) => tables.push(QualifiedName::new(
+            Some(range_var.schemaname.as_str()).filter(|s| !s.is_empty()),
+            &range_var.relname,
+        )),
+        Some(NodeEnum::JoinExpr(join)) => {
+            for arg in [&join.larg, &join.rarg].into_iter().flatten() {
+                collect_tables(arg, tables, merged);
+            }
+            merged.extend(
+                join.using_clause
+                    .iter()
+                    .filter_map(|n| match n.node.as_ref() {
+                        Some(NodeEnum::String(s)) => Some(s.str.clone()),
+                        _ => None,
+                    }),
+            );
+        }
+        _ => {}
+    }
+}
+
+/// The ast nodes of the clauses of `select` that may reference the columns of its tables
+fn clauses(select: &SelectStmt) -> impl Iterator<Item = &NodeEnum> {
+    select
+        .target_list
+        .iter()
+        .chain(select.from_clause.iter())
+        .chain(select.where_clause.as_deref())
+        .chain(select.group_clause.iter())
+        .chain(select.having_clause.as_deref())
+        .filter_map(|n| n.node.as_ref())
+}
+
+/// Collects the unqualified column references of `node`, without the ones of nested selects
+fn collect_references(node: NodeRef, references: &mut Vec<(String, i32)>) {
+    match node {
+        NodeRef::SelectStmt(_) => {}
+        NodeRef::ColumnRef(column) => {
+            if let [Node {
+                node: Some(NodeEnum::String(s)),
+            }] = column.fields.as_slice()
+            {
+                references.push((s.str.clone(), column.location));
+            }
+        }
+        _ => {
+            for child in get_children(node) {
+                collect_references(child, references);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::schema::StaticSchema;
+
+    fn lint(input: &str) -> Vec<(String, &str)> {
+        let schema = StaticSchema::new()
+            .with_table("users", &[("id", "int4"), ("name", "text")])
+            .with_table(
+                "orders",
+                &[("id", "int4"), ("user_id", "int4"), ("total", "numeric")],
+            );
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        ambiguous_columns(&parsed.cst, &schema)
+            .iter()
+            .map(|d| (d.message().to_string(), &input[d.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_ambiguous_columns() {
+        assert_eq!(
+            lint("select id, name, total from users u join orders o on o.user_id = u.id;"),
+            vec![(
+                "column reference `id` is ambiguous, it exists in `users` and `orders`".to_string(),
+                "id"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_unambiguous_columns() {
+        assert!(
+            lint("select u.id, name from users u join orders o on o.user_id = u.id;").is_empty()
+        );
+        assert!(lint("select id from users join orders using (id);").is_empty());
+        assert!(lint("select id from users;").is_empty());
+    }
+}
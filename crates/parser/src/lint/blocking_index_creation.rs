@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+
+use pg_query::protobuf::ObjectType;
+use pg_query::NodeEnum;
+
+use crate::ast::{parse_pg_query_stmt, qualified_name, statements, transaction_blocks};
+use crate::diagnostic::Diagnostic;
+use crate::syntax_node::SyntaxNode;
+
+/// Configures which statements `blocking_index_creation` exempts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockingIndexExemptions {
+    /// Exempt statements within a transaction block, where `CONCURRENTLY` is not allowed
+    pub in_transaction: bool,
+    /// Exempt indexes on tables that are created earlier in the same source, which nobody else
+    /// can be using yet
+    pub new_tables: bool,
+}
+
+impl Default for BlockingIndexExemptions {
+    fn default() -> Self {
+        Self {
+            in_transaction: true,
+            new_tables: true,
+        }
+    }
+}
+
+/// Returns a diagnostic for every `CREATE INDEX` and `DROP INDEX` statement in `root` without
+/// `CONCURRENTLY`, unless it is exempted by `exemptions`
+///
+/// Without `CONCURRENTLY`, the statement locks the table against writes until it is done.
+pub fn blocking_index_creation(
+    root: &SyntaxNode,
+    exemptions: BlockingIndexExemptions,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let blocks = transaction_blocks(root);
+    let mut new_tables = HashSet::new();
+    for stmt in statements(root) {
+        let message = match parse_pg_query_stmt(stmt) {
+            Some(NodeEnum::CreateStmt(create)) => {
+                new_tables.extend(create.relation.as_ref().map(qualified_name));
+                continue;
+            }
+            Some(NodeEnum::IndexStmt(index)) if !index.concurrent => {
+                let table = index.relation.as_ref().map(qualified_name);
+                if exemptions.new_tables && table.is_some_and(|t| new_tables.contains(&t)) {
+                    continue;
+                }
+                "creating an index without `CONCURRENTLY` blocks writes to the table"
+            }
+            Some(NodeEnum::DropStmt(drop))
+                if drop.remove_type == ObjectType::ObjectIndex as i32 && !drop.concurrent =>
+            {
+                "dropping an index without `CONCURRENTLY` blocks access to the table"
+            }
+            _ => continue,
+        };
+        if exemptions.in_transaction && blocks.iter().any(|b| b.contains(stmt.text_range())) {
+            continue;
+        }
+        diagnostics.push(Diagnostic::new(message, stmt.text_range()));
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn lint(input: &str, exemptions: BlockingIndexExemptions) -> Vec<&str> {
+        blocking_index_creation(parse(input).root(), exemptions)
+            .iter()
+            .map(|d| &input[d.range()])
+            .collect()
+    }
+
+    #[test]
+    fn test_blocking_index_creation() {
+        let input = "create index users_email_idx on users (email);
+create index concurrently users_name_idx on users (name);
+drop index users_email_idx;
+drop index concurrently users_name_idx;";
+
+        assert_eq!(
+            lint(input, BlockingIndexExemptions::default()),
+            vec![
+                "create index users_email_idx on users (email);",
+                "drop index users_email_idx;"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exemptions() {
+        let input = "create table t (id int);
+create index on t (id);
+begin;
+create index on users (email);
+commit;";
+
+        assert!(lint(input, BlockingIndexExemptions::default()).is_empty());
+        assert_eq!(
+            lint(
+                input,
+                BlockingIndexExemptions {
+                    in_transaction: false,
+                    new_tables: false,
+                }
+            ),
+            vec!["create index on t (id);", "create index on users (email);"]
+        );
+    }
+
+    #[test]
+    fn test_unclosed_transaction_exemption() {
+        let input = "begin;\ncreate index on users (email);";
+
+        assert!(lint(input, BlockingIndexExemptions::default()).is_empty());
+    }
+}
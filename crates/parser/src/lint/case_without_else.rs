@@ -0,0 +1,93 @@
+use cstree::text::TextRange;
+
+use crate::ast::{case_expressions, clause_context, ClauseContext};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Configures which `CASE` expressions `case_without_else` exempts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CaseElseExemptions {
+    /// Exempt `CASE` expressions in `WHERE` and `HAVING` clauses, where a `NULL` filters the row
+    /// just like `false` does
+    pub in_conditions: bool,
+}
+
+impl Default for CaseElseExemptions {
+    fn default() -> Self {
+        Self {
+            in_conditions: true,
+        }
+    }
+}
+
+/// Returns the ranges of the `CASE` keywords of all `CASE` expressions in `stmt` without an
+/// `ELSE`, unless they are exempted by `exemptions`
+///
+/// A `CASE` without `ELSE` silently results in `NULL` for all rows that no arm matches.
+pub fn case_without_else(stmt: &SyntaxNode, exemptions: CaseElseExemptions) -> Vec<TextRange> {
+    case_expressions(stmt)
+        .into_iter()
+        .filter(|case| case.default.is_none())
+        .filter_map(|case| {
+            let node = stmt
+                .descendants()
+                .find(|n| n.kind() == SyntaxKind::CaseExpr && n.text_range() == case.range)?;
+            if exemptions.in_conditions
+                && matches!(
+                    clause_context(node),
+                    ClauseContext::WhereClause | ClauseContext::Having
+                )
+            {
+                return None;
+            }
+            node.descendants_with_tokens()
+                .filter_map(|e| e.into_token())
+                .find(|t| t.kind() == SyntaxKind::Case)
+                .map(|t| t.text_range())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str, exemptions: CaseElseExemptions) -> Vec<TextRange> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        case_without_else(&parser.finish().cst, exemptions)
+    }
+
+    #[test]
+    fn test_case_without_else() {
+        let input = "select case when x > 1 then 'big' end from t;";
+
+        let ranges = lint(input, CaseElseExemptions::default());
+
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(&input[ranges[0]], "case");
+        assert!(lint(
+            "select case when x > 1 then 'big' else 'small' end from t;",
+            CaseElseExemptions::default()
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_exemptions() {
+        let input = "select * from t where case when x > 1 then y end;";
+
+        assert!(lint(input, CaseElseExemptions::default()).is_empty());
+        assert_eq!(
+            lint(
+                input,
+                CaseElseExemptions {
+                    in_conditions: false
+                }
+            )
+            .len(),
+            1
+        );
+    }
+}
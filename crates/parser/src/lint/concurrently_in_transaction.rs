@@ -0,0 +1,88 @@
+use pg_query::protobuf::ObjectType;
+use pg_query::NodeEnum;
+
+use crate::ast::{index_statement, parse_pg_query_stmt, statements, transaction_blocks};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns a diagnostic for every `CREATE INDEX CONCURRENTLY`, `DROP INDEX CONCURRENTLY` and
+/// `REINDEX CONCURRENTLY` statement in `root` within a transaction block
+///
+/// Postgres refuses to run these statements in a transaction block.
+pub fn concurrently_in_transaction(root: &SyntaxNode) -> Vec<Diagnostic> {
+    let blocks = transaction_blocks(root);
+    statements(root)
+        .into_iter()
+        .filter(|stmt| blocks.iter().any(|b| b.contains(stmt.text_range())))
+        .filter_map(|stmt| {
+            let message = if index_statement(stmt).is_some_and(|i| i.concurrently) {
+                "`CREATE INDEX CONCURRENTLY` cannot run inside a transaction block"
+            } else {
+                match parse_pg_query_stmt(stmt)? {
+                    NodeEnum::DropStmt(drop)
+                        if drop.remove_type == ObjectType::ObjectIndex as i32
+                            && drop.concurrent =>
+                    {
+                        "`DROP INDEX CONCURRENTLY` cannot run inside a transaction block"
+                    }
+                    NodeEnum::ReindexStmt(reindex) if reindex.concurrent => {
+                        "`REINDEX CONCURRENTLY` cannot run inside a transaction block"
+                    }
+                    _ => return None,
+                }
+            };
+            Some(Diagnostic::new(message, stmt.text_range()).with_severity(Severity::Error))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn lint(input: &str) -> Vec<&str> {
+        concurrently_in_transaction(parse(input).root())
+            .iter()
+            .map(|d| &input[d.range()])
+            .collect()
+    }
+
+    #[test]
+    fn test_concurrently_in_transaction() {
+        let input = "begin;
+create index concurrently users_email_idx on users (email);
+drop index concurrently users_name_idx;
+reindex index concurrently users_id_idx;
+create index users_id_idx on users (id);
+commit;";
+
+        assert_eq!(
+            lint(input),
+            vec![
+                "create index concurrently users_email_idx on users (email);",
+                "drop index concurrently users_name_idx;",
+                "reindex index concurrently users_id_idx;"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_concurrently_in_unclosed_transaction() {
+        assert_eq!(
+            lint("begin;\ncreate index concurrently i on t (a);"),
+            vec!["create index concurrently i on t (a);"]
+        );
+    }
+
+    #[test]
+    fn test_concurrently_outside_transaction() {
+        assert!(lint(
+            "create index concurrently users_email_idx on users (email);
+begin;
+commit;
+drop index concurrently users_email_idx;"
+        )
+        .is_empty());
+    }
+}
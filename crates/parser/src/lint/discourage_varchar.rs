@@ -0,0 +1,103 @@
+use pg_query::NodeEnum;
+
+use crate::ast::{statements, type_names};
+use crate::diagnostic::Diagnostic;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Configures `discourage_varchar`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VarcharOptions {
+    /// The message of the diagnostics
+    pub message: String,
+    /// Also flag `varchar` without a length, which is the same as `text`
+    pub without_length: bool,
+}
+
+impl Default for VarcharOptions {
+    fn default() -> Self {
+        Self {
+            message: "prefer `text` with a `CHECK` constraint on its length over `varchar(n)` and `char(n)`".to_string(),
+            without_length: false,
+        }
+    }
+}
+
+/// Returns a diagnostic at the type of every column definition in `root` that is a `varchar(n)`
+/// or a `char(n)`
+///
+/// Both types are no faster than `text`, but changing their length later requires rewriting the
+/// table, while a `CHECK` constraint can be replaced cheaply. `char` without a length is
+/// `char(1)`, and is flagged as well.
+pub fn discourage_varchar(root: &SyntaxNode, options: &VarcharOptions) -> Vec<Diagnostic> {
+    statements(root)
+        .into_iter()
+        .flat_map(|stmt| {
+            let column_types = stmt
+                .descendants()
+                .filter(|n| n.kind() == SyntaxKind::ColumnDef)
+                .flat_map(|n| n.children())
+                .filter(|n| n.kind() == SyntaxKind::TypeName)
+                .map(|n| n.text_range())
+                .collect::<Vec<_>>();
+            type_names(stmt)
+                .into_iter()
+                .filter(move |(_, range)| column_types.contains(range))
+        })
+        .filter(|(type_name, _)| {
+            let last_name = type_name.names.last().and_then(|n| match n.node.as_ref() {
+                Some(NodeEnum::String(s)) => Some(s.str.as_str()),
+                _ => None,
+            });
+            match last_name {
+                Some("bpchar") => true,
+                Some("varchar") => options.without_length || !type_name.typmods.is_empty(),
+                _ => false,
+            }
+        })
+        .map(|(_, range)| Diagnostic::new(options.message.clone(), range))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn lint<'a>(input: &'a str, options: &VarcharOptions) -> Vec<&'a str> {
+        discourage_varchar(parse(input).root(), options)
+            .iter()
+            .map(|d| &input[d.range()])
+            .collect()
+    }
+
+    #[test]
+    fn test_discourage_varchar() {
+        let input = "create table users (email varchar(255), code char(2), name varchar, bio text);
+select name::varchar(10) from users;";
+
+        assert_eq!(
+            lint(input, &VarcharOptions::default()),
+            vec!["varchar(255)", "char(2)"]
+        );
+        assert_eq!(
+            lint(
+                input,
+                &VarcharOptions {
+                    without_length: true,
+                    ..VarcharOptions::default()
+                }
+            ),
+            vec!["varchar(255)", "char(2)", "varchar"]
+        );
+    }
+
+    #[test]
+    fn test_text_columns() {
+        assert!(lint(
+            "create table users (email text, bio text);",
+            &VarcharOptions::default()
+        )
+        .is_empty());
+    }
+}
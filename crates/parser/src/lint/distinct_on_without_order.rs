@@ -0,0 +1,150 @@
+use cstree::text::TextRange;
+use pg_query::NodeEnum;
+
+use crate::ast::name::resolve;
+use crate::ast::{
+    distinct, non_trivia_tokens, selects_with_parent, syntax_node_for, token_before, DistinctClause,
+};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// The text of the non-trivia tokens of `node`, used to compare expressions
+///
+/// Unquoted identifiers and keywords are folded to lower case, while quoted identifiers and
+/// literals are compared as written.
+fn key(node: &SyntaxNode) -> Vec<String> {
+    non_trivia_tokens(node)
+        .into_iter()
+        .map(|t| {
+            let text = t.resolved().text();
+            match t.kind() {
+                SyntaxKind::Ident => resolve(text),
+                SyntaxKind::Sconst
+                | SyntaxKind::Usconst
+                | SyntaxKind::Bconst
+                | SyntaxKind::Xconst
+                | SyntaxKind::Uident => text.to_string(),
+                _ => text.to_lowercase(),
+            }
+        })
+        .collect()
+}
+
+/// Returns the ranges of all `DISTINCT ON (...)` clauses of selects in `stmt` whose `ORDER BY`
+/// does not begin with the same expressions
+///
+/// `DISTINCT ON` keeps the first row of every set of equal rows, which is arbitrary unless the
+/// rows are ordered by the `DISTINCT ON` expressions first. The expressions may be ordered by in
+/// any order, and are compared by their tokens. An ordinal of the `ORDER BY`, e.g. `order by 1`,
+/// stands for the expression of its target.
+pub fn distinct_on_without_order(stmt: &SyntaxNode) -> Vec<TextRange> {
+    selects_with_parent(stmt)
+        .iter()
+        .filter_map(|(select, _)| {
+            let exprs = match distinct(select)? {
+                DistinctClause::DistinctOn(exprs) if !exprs.is_empty() => exprs,
+                _ => return None,
+            };
+
+            let mut sort_keys = select
+                .ast()
+                .sort_clause
+                .iter()
+                .take(exprs.len())
+                .filter_map(|n| match n.node.as_ref() {
+                    Some(NodeEnum::SortBy(s)) => s.node.as_ref()?.node.as_ref(),
+                    _ => None,
+                })
+                // an ordinal refers to the expression of a target, e.g. `order by 1`
+                .filter_map(|n| match n {
+                    NodeEnum::AConst(c) => match c.val.as_ref()?.node.as_ref()? {
+                        NodeEnum::Integer(i) => {
+                            let target = select
+                                .ast()
+                                .target_list
+                                .get(usize::try_from(i.ival).ok()?.checked_sub(1)?)?;
+                            match target.node.as_ref()? {
+                                NodeEnum::ResTarget(t) => t.val.as_ref()?.node.as_ref(),
+                                _ => None,
+                            }
+                        }
+                        _ => Some(n),
+                    },
+                    _ => Some(n),
+                })
+                .filter_map(|n| syntax_node_for(select.syntax(), &n.to_ref()))
+                .map(key)
+                .collect::<Vec<_>>();
+            let mut distinct_keys = exprs.iter().map(|n| key(n)).collect::<Vec<_>>();
+            sort_keys.sort();
+            distinct_keys.sort();
+            if sort_keys == distinct_keys {
+                return None;
+            }
+
+            let first = exprs.first()?.text_range();
+            let last = exprs.last()?.text_range();
+            let keyword = token_before(select.syntax(), SyntaxKind::Distinct, first.start())?;
+            let close = select
+                .syntax()
+                .descendants_with_tokens()
+                .filter_map(|e| e.into_token())
+                .find(|t| t.kind() == SyntaxKind::Ascii41 && t.text_range().start() >= last.end())
+                .map_or(last, |t| t.text_range());
+            Some(keyword.text_range().cover(close))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<&str> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        distinct_on_without_order(&parsed.cst)
+            .into_iter()
+            .map(|r| &input[r])
+            .collect()
+    }
+
+    #[test]
+    fn test_distinct_on_without_order() {
+        assert_eq!(
+            lint("select distinct on (user_id) * from events order by created_at desc;"),
+            vec!["distinct on (user_id)"]
+        );
+        assert_eq!(
+            lint("select distinct on (user_id, kind) * from events;"),
+            vec!["distinct on (user_id, kind)"]
+        );
+        assert_eq!(
+            lint("select distinct on (\"Kind\") * from events order by kind;"),
+            vec!["distinct on (\"Kind\")"]
+        );
+        assert_eq!(
+            lint("select distinct on (kind = 'A') * from events order by kind = 'a';"),
+            vec!["distinct on (kind = 'A')"]
+        );
+        assert_eq!(
+            lint("select distinct on (user_id) kind, user_id from events order by 1;"),
+            vec!["distinct on (user_id)"]
+        );
+    }
+
+    #[test]
+    fn test_distinct_on_with_order() {
+        assert!(lint(
+            "select distinct on (user_id, kind) * from events order by kind, User_Id, created_at desc;"
+        )
+        .is_empty());
+        assert!(lint("select distinct user_id from events;").is_empty());
+        assert!(
+            lint("select distinct on (user_id) user_id, kind from events order by 1, kind;")
+                .is_empty()
+        );
+    }
+}
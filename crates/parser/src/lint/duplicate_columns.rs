@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+
+use pg_query::NodeEnum;
+
+use crate::ast::{location_to_offset, name_token_at, parse_pg_query_stmt};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns an error for every column of the `CREATE TABLE` statement `stmt` whose name is already
+/// used by a previous column
+///
+/// pg_query reports column names the way Postgres resolves them, i.e. unquoted names are folded
+/// to lower case, and quoted names are case-sensitive. Hence, `Id` and `"id"` are duplicates,
+/// while `id` and `"ID"` are not.
+pub fn duplicate_columns(stmt: &SyntaxNode) -> Vec<Diagnostic> {
+    let create = match parse_pg_query_stmt(stmt) {
+        Some(NodeEnum::CreateStmt(create)) => create,
+        _ => return Vec::new(),
+    };
+
+    let mut seen = HashSet::new();
+    create
+        .table_elts
+        .iter()
+        .filter_map(|n| match n.node.as_ref() {
+            Some(NodeEnum::ColumnDef(c)) => Some(c),
+            _ => None,
+        })
+        .filter(|c| !seen.insert(c.colname.as_str()))
+        .filter_map(|c| {
+            let token = name_token_at(stmt, location_to_offset(stmt, c.location)?)?;
+            Some(
+                Diagnostic::new(
+                    format!("column `{}` is specified more than once", c.colname),
+                    token.text_range(),
+                )
+                .with_severity(Severity::Error),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<String> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        duplicate_columns(&parsed.cst)
+            .iter()
+            .map(|d| format!("{} {}", &input[d.range()], d))
+            .collect()
+    }
+
+    #[test]
+    fn test_duplicate_columns() {
+        assert_eq!(
+            lint("create table t (id int, name text, Id text);"),
+            vec!["Id column `id` is specified more than once"]
+        );
+        assert_eq!(
+            lint("create table t (Id int, \"id\" text);"),
+            vec!["\"id\" column `id` is specified more than once"]
+        );
+    }
+
+    #[test]
+    fn test_quoted_columns_are_case_sensitive() {
+        assert!(lint("create table t (id int, \"ID\" text);").is_empty());
+    }
+}
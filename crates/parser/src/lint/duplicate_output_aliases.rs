@@ -0,0 +1,79 @@
+use crate::ast::{select_targets, selects_with_parent};
+use crate::diagnostic::Diagnostic;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns a warning for every output column of a select in `stmt` that has the same name as a
+/// previous output column of the select, e.g. the second `a` of `select a, b as a from t`
+///
+/// Clients that access the columns of a row by their names cannot tell the columns apart. The
+/// names are given with `AS` or inferred like Postgres does, and are compared after case folding,
+/// so `A` and `a` are the same name while `"A"` is another. Expressions that Postgres names
+/// `?column?` are not flagged.
+pub fn duplicate_output_aliases(stmt: &SyntaxNode) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for (select, _) in selects_with_parent(stmt) {
+        let targets = select_targets(&select);
+        for (idx, target) in targets.iter().enumerate() {
+            let alias = match target.alias.as_deref() {
+                Some("?column?") | None => continue,
+                Some(alias) => alias,
+            };
+            if targets[..idx]
+                .iter()
+                .any(|t| t.alias.as_deref() == Some(alias))
+            {
+                diagnostics.push(Diagnostic::new(
+                    format!("the output column `{}` is defined more than once", alias),
+                    target.range,
+                ));
+            }
+        }
+    }
+    diagnostics.sort_by_key(|d| d.range().start());
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<(String, &str)> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        duplicate_output_aliases(&parsed.cst)
+            .iter()
+            .map(|d| (d.message().to_string(), &input[d.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_duplicate_output_aliases() {
+        assert_eq!(
+            lint("select a, b as a, t.A, count(*), count(x) from t;"),
+            vec![
+                (
+                    "the output column `a` is defined more than once".to_string(),
+                    "b as a"
+                ),
+                (
+                    "the output column `a` is defined more than once".to_string(),
+                    "t.A"
+                ),
+                (
+                    "the output column `count` is defined more than once".to_string(),
+                    "count(x)"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distinct_output_aliases() {
+        assert!(lint("select a, b as \"A\", 1, 2, * from t;").is_empty());
+        assert!(
+            lint("select a from (select a, b from t) s where a in (select a from u);").is_empty()
+        );
+    }
+}
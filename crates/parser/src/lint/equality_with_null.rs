@@ -0,0 +1,137 @@
+use pg_query::protobuf::{AExprKind, Node};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{parse_pg_query_stmt, token_at};
+use crate::diagnostic::Diagnostic;
+use crate::fix::{Fix, TextEdit};
+use crate::pg_query_utils::{get_nodes, get_position_for_pg_query_node};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns a warning for every comparison in `stmt` of a value with `NULL` by `=` or `<>`, e.g.
+/// `x = NULL`
+///
+/// Such a comparison is always `NULL`, so it never matches. The diagnostic covers the comparison,
+/// and if `NULL` is the right operand, its fix replaces the operator and `NULL` with `IS NULL` or
+/// `IS NOT NULL`.
+pub fn equality_with_null(stmt: &SyntaxNode) -> Vec<Diagnostic> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+
+    let mut diagnostics = Vec::new();
+    for (node, _) in get_nodes(ast.to_ref()) {
+        let expr = match node {
+            NodeRef::AExpr(expr) if expr.kind == AExprKind::AexprOp as i32 => expr,
+            _ => continue,
+        };
+        let negated = match expr.name.as_slice() {
+            [Node {
+                node: Some(NodeEnum::String(s)),
+            }] if s.str == "=" => false,
+            [Node {
+                node: Some(NodeEnum::String(s)),
+            }] if s.str == "<>" || s.str == "!=" => true,
+            _ => continue,
+        };
+        let (left_null, right_null) = (
+            expr.lexpr.as_deref().is_some_and(is_null),
+            expr.rexpr.as_deref().is_some_and(is_null),
+        );
+        if !left_null && !right_null {
+            continue;
+        }
+
+        let positions = get_nodes(node)
+            .iter()
+            .map(|(n, _)| get_position_for_pg_query_node(n))
+            .filter(|p| *p >= 0)
+            .collect::<Vec<_>>();
+        let (first, last) = match (
+            positions.iter().min().and_then(|p| token_at(stmt, *p)),
+            positions.iter().max().and_then(|p| token_at(stmt, *p)),
+        ) {
+            (Some(first), Some(last)) => (first, last),
+            _ => continue,
+        };
+        let replacement = if negated { "IS NOT NULL" } else { "IS NULL" };
+        let mut diagnostic = Diagnostic::new(
+            format!(
+                "comparing to `NULL` with `{}` is never true, use `{}` instead",
+                if negated { "<>" } else { "=" },
+                replacement
+            ),
+            first.text_range().cover(last.text_range()),
+        );
+        if right_null {
+            if let Some(operator) = token_at(stmt, expr.location) {
+                diagnostic = diagnostic.with_fix(Fix::new(
+                    format!("Replace with `{}`", replacement),
+                    vec![TextEdit::replace(
+                        operator.text_range().cover(last.text_range()),
+                        replacement,
+                    )],
+                ));
+            }
+        }
+        diagnostics.push(diagnostic);
+    }
+    diagnostics.sort_by_key(|d| d.range().start());
+    diagnostics
+}
+
+fn is_null(node: &Node) -> bool {
+    matches!(
+        node.node.as_ref(),
+        Some(NodeEnum::AConst(value))
+            if matches!(value.val.as_ref().and_then(|v| v.node.as_ref()), Some(NodeEnum::Null(_)))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fix::apply_fixes;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<Diagnostic> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        equality_with_null(&parser.finish().cst)
+    }
+
+    #[test]
+    fn test_equality_with_null() {
+        let input = "select * from t where x = NULL or t.y <> null;";
+        let diagnostics = lint(input);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.message(), &input[d.range()]))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "comparing to `NULL` with `=` is never true, use `IS NULL` instead",
+                    "x = NULL"
+                ),
+                (
+                    "comparing to `NULL` with `<>` is never true, use `IS NOT NULL` instead",
+                    "t.y <> null"
+                ),
+            ]
+        );
+        let fixes = diagnostics
+            .iter()
+            .filter_map(|d| d.fix().cloned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            apply_fixes(input, &fixes),
+            "select * from t where x IS NULL or t.y IS NOT NULL;"
+        );
+    }
+
+    #[test]
+    fn test_is_null() {
+        assert!(lint("select * from t where x is null and y is not null and z = 1;").is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+use cstree::text::TextRange;
+
+use crate::ast::{nested_queries, statements};
+use crate::cst_utils::nesting_depth;
+use crate::diagnostic::Diagnostic;
+use crate::syntax_node::SyntaxNode;
+
+/// The maximum nesting depth of the `excessive-nesting` rule of `run_lints`
+pub const DEFAULT_MAX_NESTING_DEPTH: u32 = 3;
+
+/// Returns a diagnostic for every statement in `root` whose queries are nested deeper than `max`,
+/// e.g. by subqueries, derived tables or common table expressions
+///
+/// The diagnostic covers the first nested query that exceeds `max`, including its parentheses.
+pub fn excessive_nesting(root: &SyntaxNode, max: u32) -> Vec<Diagnostic> {
+    statements(root)
+        .into_iter()
+        .filter_map(|stmt| {
+            let depth = nesting_depth(stmt);
+            if depth <= max {
+                return None;
+            }
+            Some(Diagnostic::new(
+                format!(
+                    "queries are nested {} levels deep, the maximum is {}",
+                    depth, max
+                ),
+                first_query_deeper_than(stmt, max)?,
+            ))
+        })
+        .collect()
+}
+
+/// Returns the range of the first query of `stmt` that is nested in more than `max` queries,
+/// counting itself
+fn first_query_deeper_than(stmt: &SyntaxNode, max: u32) -> Option<TextRange> {
+    let queries = nested_queries(stmt);
+    queries
+        .iter()
+        .copied()
+        .find(|query| queries.iter().filter(|q| q.contains_range(*query)).count() as u32 > max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_excessive_nesting() {
+        let input = "select 1;\nselect * from (select * from (select * from (select 1) a) b) c;";
+
+        let diagnostics = excessive_nesting(parse(input).root(), 2);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(&input[diagnostics[0].range()], "(select 1)");
+        assert_eq!(
+            diagnostics[0].message(),
+            "queries are nested 3 levels deep, the maximum is 2"
+        );
+        assert!(excessive_nesting(parse(input).root(), 3).is_empty());
+        assert!(excessive_nesting(
+            parse("select coalesce(max((a + (b * c))), 0) from t where id in (1, 2);").root(),
+            2
+        )
+        .is_empty());
+    }
+}
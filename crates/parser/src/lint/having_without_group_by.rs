@@ -0,0 +1,64 @@
+use cstree::text::TextRange;
+
+use crate::ast::{aggregates, having, select_targets, selects_with_parent, token_before};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the ranges of all `HAVING` clauses of selects in `stmt` without a `GROUP BY` and
+/// without aggregates
+///
+/// Such a `HAVING` clause is usually meant to be a `WHERE` clause. A `HAVING` clause that
+/// aggregates over the whole table, e.g. `select count(*) from t having count(*) > 1`, is not
+/// flagged.
+pub fn having_without_group_by(stmt: &SyntaxNode) -> Vec<TextRange> {
+    let calls = aggregates(stmt);
+    selects_with_parent(stmt)
+        .iter()
+        .filter(|(select, _)| select.ast().group_clause.is_empty())
+        .filter_map(|(select, _)| {
+            let condition = having(select)?.text_range();
+            let aggregated = select_targets(select)
+                .iter()
+                .map(|t| t.range)
+                .chain([condition])
+                .any(|range| calls.iter().any(|c| range.contains_range(c.range)));
+            if aggregated {
+                return None;
+            }
+            let keyword = token_before(select.syntax(), SyntaxKind::Having, condition.start())?;
+            Some(keyword.text_range().cover(condition))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<&str> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        having_without_group_by(&parsed.cst)
+            .into_iter()
+            .map(|r| &input[r])
+            .collect()
+    }
+
+    #[test]
+    fn test_having_without_group_by() {
+        assert_eq!(
+            lint("select id from users having id > 10;"),
+            vec!["having id > 10"]
+        );
+    }
+
+    #[test]
+    fn test_having_with_group_by_or_aggregate() {
+        assert!(lint("select kind from users group by kind having kind <> 'a';").is_empty());
+        assert!(lint("select count(*) from users having count(*) > 1;").is_empty());
+        assert!(lint("select 1 from users having stddev(age) > 1;").is_empty());
+        assert!(lint("select max(id) from users having true;").is_empty());
+    }
+}
@@ -0,0 +1,355 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::{AConst, AExprKind, ColumnRef, Node, RangeVar};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{
+    location_to_offset, name_token_at, non_trivia_tokens, parse_pg_query_stmt, token_at,
+};
+use crate::diagnostic::Diagnostic;
+use crate::pg_query_utils::{get_children, get_nodes};
+use crate::schema::{QualifiedName, SchemaProvider};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// A category of types whose values are compared without casts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeCategory {
+    Integer,
+    /// Numbers with a fractional part, e.g. `numeric` or `1.5`
+    Decimal,
+    String,
+    DateTime,
+}
+
+impl TypeCategory {
+    /// Returns the category of the type `type_name`, e.g. `int4`
+    fn of(type_name: &str) -> Option<Self> {
+        let base = type_name.split(['(', '[']).next()?.trim().to_lowercase();
+        let category = match base.as_str() {
+            "int2" | "int4" | "int8" | "smallint" | "integer" | "int" | "bigint"
+            | "smallserial" | "serial" | "bigserial" => TypeCategory::Integer,
+            "numeric" | "decimal" | "real" | "float4" | "float8" | "double precision" => {
+                TypeCategory::Decimal
+            }
+            "text" | "varchar" | "character varying" | "char" | "character" | "bpchar" => {
+                TypeCategory::String
+            }
+            "date" | "time" | "timetz" | "timestamp" | "timestamptz" | "interval" => {
+                TypeCategory::DateTime
+            }
+            _ => return None,
+        };
+        Some(category)
+    }
+
+    /// Returns the category of the literal `value`
+    fn of_literal(value: &AConst) -> Option<Self> {
+        match value.val.as_ref()?.node.as_ref()? {
+            NodeEnum::Integer(_) => Some(TypeCategory::Integer),
+            // integers that do not fit into an `int4` are `Float` nodes as well
+            NodeEnum::Float(f) if f.str.bytes().all(|b| b.is_ascii_digit()) => {
+                Some(TypeCategory::Integer)
+            }
+            NodeEnum::Float(_) => Some(TypeCategory::Decimal),
+            NodeEnum::String(_) => Some(TypeCategory::String),
+            _ => None,
+        }
+    }
+
+    /// Whether a literal of the category `literal` is compared to a column of this category
+    /// without a cast of the column, e.g. an integer literal to a `numeric` column
+    fn accepts(self, literal: Self) -> bool {
+        self == literal || (self == TypeCategory::Decimal && literal == TypeCategory::Integer)
+    }
+}
+
+/// Returns a warning for every comparison in `stmt` of a column with a literal of another type,
+/// e.g. `id = '5'` or `id IN ('1', '2')` where `id` is an integer
+///
+/// The literal is implicitly cast to the type of the column, which hides mistakes, or the column
+/// is cast to the type of the literal, e.g. for `id = 1.5`, which keeps the planner from using an
+/// index. The types of the columns are looked up in `schema`, and literals with an explicit cast
+/// are not flagged. The diagnostic covers the comparison, or the whole `IN` list.
+pub fn implicit_coercions(stmt: &SyntaxNode, schema: &dyn SchemaProvider) -> Vec<Diagnostic> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+
+    let mut diagnostics = Vec::new();
+    for (node, _) in get_nodes(ast.to_ref()) {
+        let tables = match tables_of(node) {
+            Some(tables) => tables,
+            None => continue,
+        };
+        let mut comparisons = Vec::new();
+        for child in get_children(node) {
+            collect_comparisons(child, &mut comparisons);
+        }
+
+        for (column, values, is_list) in comparisons {
+            let (name, type_name) = match column_type(column, &tables, schema) {
+                Some(column) => column,
+                None => continue,
+            };
+            let column_category = match TypeCategory::of(&type_name) {
+                Some(category) => category,
+                None => continue,
+            };
+            let literal_category = match values
+                .iter()
+                .filter_map(|v| TypeCategory::of_literal(v))
+                .find(|l| !column_category.accepts(*l))
+            {
+                Some(category) => category,
+                None => continue,
+            };
+            let last_value = values
+                .iter()
+                .filter_map(|v| token_at(stmt, v.location))
+                .max_by_key(|t| t.text_range().start());
+            let range = match (
+                token_at(stmt, column.location),
+                location_to_offset(stmt, column.location).and_then(|o| name_token_at(stmt, o)),
+                last_value,
+            ) {
+                (Some(first), Some(last), Some(value)) => {
+                    let end = if is_list {
+                        closing_parenthesis_after(stmt, value)
+                    } else {
+                        value.text_range()
+                    };
+                    first.text_range().cover(last.text_range()).cover(end)
+                }
+                _ => continue,
+            };
+            diagnostics.push(Diagnostic::new(
+                format!(
+                    "`{}` of type `{}` is compared to {}, which is implicitly cast",
+                    name,
+                    type_name,
+                    match literal_category {
+                        TypeCategory::Integer => "a numeric literal",
+                        TypeCategory::Decimal => "a decimal literal",
+                        _ => "a string literal",
+                    }
+                ),
+                range,
+            ));
+        }
+    }
+    diagnostics.sort_by_key(|d| d.range().start());
+    diagnostics
+}
+
+/// Returns the tables of `node` with the names they are referred to by, if `node` is a statement
+/// with its own tables, e.g. a select
+fn tables_of(node: NodeRef) -> Option<Vec<(String, QualifiedName)>> {
+    let (relation, items) = match node {
+        NodeRef::SelectStmt(n) => (None, &n.from_clause),
+        NodeRef::UpdateStmt(n) => (n.relation.as_ref(), &n.from_clause),
+        NodeRef::DeleteStmt(n) => (n.relation.as_ref(), &n.using_clause),
+        _ => return None,
+    };
+    let mut tables = Vec::new();
+    if let Some(relation) = relation {
+        push_table(relation, &mut tables);
+    }
+    for item in items {
+        collect_tables(item, &mut tables);
+    }
+    Some(tables)
+}
+
+fn collect_tables(node: &Node, tables: &mut Vec<(String, QualifiedName)>) {
+    match node.node.as_ref() {
+        Some(NodeEnum::RangeVar(range_var)) => push_table(range_var, tables),
+        Some(NodeEnum::JoinExpr(join)) => {
+            for arg in [&join.larg, &join.rarg].into_iter().flatten() {
+                collect_tables(arg, tables);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn push_table(range_var: &RangeVar, tables: &mut Vec<(String, QualifiedName)>) {
+    let alias = range_var
+        .alias
+        .as_ref()
+        .map_or(&range_var.relname, |a| &a.aliasname);
+    tables.push((
+        alias.clone(),
+        QualifiedName::new(
+            Some(range_var.schemaname.as_str()).filter(|s| !s.is_empty()),
+            &range_var.relname,
+        ),
+    ));
+}
+
+/// A comparison of a column with literals, and whether the literals are an `IN` list
+type Comparison<'a> = (&'a ColumnRef, Vec<&'a AConst>, bool);
+
+/// Collects the comparisons of a column with a literal or a list of literals in `node`, without
+/// the ones of nested statements
+fn collect_comparisons<'a>(node: NodeRef<'a>, comparisons: &mut Vec<Comparison<'a>>) {
+    match node {
+        NodeRef::SelectStmt(_) => {}
+        NodeRef::AExpr(expr) if expr.kind == AExprKind::AexprIn as i32 => {
+            let operands = (
+                expr.lexpr.as_deref().and_then(|n| n.node.as_ref()),
+                expr.rexpr.as_deref().and_then(|n| n.node.as_ref()),
+            );
+            if let (Some(NodeEnum::ColumnRef(c)), Some(NodeEnum::List(list))) = operands {
+                let values = list
+                    .items
+                    .iter()
+                    .filter_map(|item| match item.node.as_ref() {
+                        Some(NodeEnum::AConst(v)) => Some(v.as_ref()),
+                        _ => None,
+                    })
+                    .collect();
+                comparisons.push((c, values, true));
+            }
+            for child in get_children(node) {
+                collect_comparisons(child, comparisons);
+            }
+        }
+        NodeRef::AExpr(expr) if expr.kind == AExprKind::AexprOp as i32 => {
+            let is_comparison = matches!(
+                expr.name.as_slice(),
+                [Node { node: Some(NodeEnum::String(s)) }]
+                    if ["=", "<>", "!=", "<", "<=", ">", ">="].contains(&s.str.as_str())
+            );
+            let operands = (
+                expr.lexpr.as_deref().and_then(|n| n.node.as_ref()),
+                expr.rexpr.as_deref().and_then(|n| n.node.as_ref()),
+            );
+            match operands {
+                (Some(NodeEnum::ColumnRef(c)), Some(NodeEnum::AConst(v)))
+                | (Some(NodeEnum::AConst(v)), Some(NodeEnum::ColumnRef(c)))
+                    if is_comparison =>
+                {
+                    comparisons.push((c, vec![v], false))
+                }
+                _ => {
+                    for child in get_children(node) {
+                        collect_comparisons(child, comparisons);
+                    }
+                }
+            }
+        }
+        _ => {
+            for child in get_children(node) {
+                collect_comparisons(child, comparisons);
+            }
+        }
+    }
+}
+
+/// Returns the range of the parenthesis that closes the list after its last item `last`
+fn closing_parenthesis_after(stmt: &SyntaxNode, last: &SyntaxToken) -> TextRange {
+    non_trivia_tokens(stmt)
+        .into_iter()
+        .skip_while(|t| *t != last)
+        .find(|t| t.kind() == SyntaxKind::Ascii41)
+        .map_or(last.text_range(), |t| t.text_range())
+}
+
+/// Returns the name and the type name of the column `column` of one of `tables`
+fn column_type(
+    column: &ColumnRef,
+    tables: &[(String, QualifiedName)],
+    schema: &dyn SchemaProvider,
+) -> Option<(String, String)> {
+    let fields = column
+        .fields
+        .iter()
+        .map(|f| match f.node.as_ref() {
+            Some(NodeEnum::String(s)) => Some(s.str.as_str()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let (qualifier, name) = match fields.as_slice() {
+        [name] => (None, *name),
+        [qualifier, name] => (Some(*qualifier), *name),
+        _ => return None,
+    };
+    tables
+        .iter()
+        .filter(|(alias, _)| qualifier.is_none_or(|q| q == alias))
+        .find_map(|(_, table)| schema.columns(table).into_iter().find(|c| c.name == name))
+        .map(|c| (fields.join("."), c.type_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::schema::StaticSchema;
+
+    fn lint(input: &str) -> Vec<(String, &str)> {
+        let schema = StaticSchema::new()
+            .with_table("users", &[("id", "int4"), ("name", "text")])
+            .with_table("orders", &[("user_id", "int8"), ("placed_at", "date")]);
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        implicit_coercions(&parsed.cst, &schema)
+            .iter()
+            .map(|d| (d.message().to_string(), &input[d.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_implicit_coercions() {
+        assert_eq!(
+            lint("select * from users u where id = '5' or u.name = 5;"),
+            vec![
+                (
+                    "`id` of type `int4` is compared to a string literal, which is implicitly cast"
+                        .to_string(),
+                    "id = '5'"
+                ),
+                (
+                    "`u.name` of type `text` is compared to a numeric literal, which is implicitly cast"
+                        .to_string(),
+                    "u.name = 5"
+                ),
+            ]
+        );
+        assert_eq!(
+            lint("delete from orders where '2020-01-01' > placed_at;").len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_decimal_and_list_coercions() {
+        assert_eq!(
+            lint("select * from users where id = 1.5 and id in ('1', '2') and id in (1, 2);"),
+            vec![
+                (
+                    "`id` of type `int4` is compared to a decimal literal, which is implicitly cast"
+                        .to_string(),
+                    "id = 1.5"
+                ),
+                (
+                    "`id` of type `int4` is compared to a string literal, which is implicitly cast"
+                        .to_string(),
+                    "id in ('1', '2')"
+                ),
+            ]
+        );
+        assert!(lint("select * from orders where user_id = 9999999999;").is_empty());
+    }
+
+    #[test]
+    fn test_matching_types() {
+        assert!(lint(
+            "select * from users u join orders o on o.user_id = u.id where u.id = 5 and name = 'x' and o.placed_at > '2020-01-01'::date;"
+        )
+        .is_empty());
+        assert!(lint("select * from unknown where id = '5';").is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+use pg_query::NodeEnum;
+
+use crate::ast::{parse_pg_query_stmt, values_row_extents};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns an error for every row of the `INSERT ... VALUES` statement `stmt` whose number of
+/// values does not match its explicit column list
+///
+/// The values are counted in the ast of the statement. The diagnostic covers the values of the row.
+pub fn insert_value_counts(stmt: &SyntaxNode) -> Vec<Diagnostic> {
+    let columns = match parse_pg_query_stmt(stmt) {
+        Some(NodeEnum::InsertStmt(insert)) if !insert.cols.is_empty() => insert.cols.len(),
+        _ => return Vec::new(),
+    };
+
+    values_row_extents(stmt)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(len, _)| *len != columns)
+        .filter_map(|(len, range)| {
+            Some(
+                Diagnostic::new(
+                    format!("expected {} values, found {}", columns, len),
+                    range?,
+                )
+                .with_severity(Severity::Error),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<String> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        insert_value_counts(&parsed.cst)
+            .iter()
+            .map(|d| format!("{} {}", &input[d.range()], d))
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_value_counts() {
+        assert_eq!(
+            lint("insert into users (id, name) values (1, 'a'), (2), (3, 'c', true);"),
+            vec![
+                "2 expected 2 values, found 1",
+                "3, 'c', true expected 2 values, found 3"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matching_value_counts() {
+        assert!(lint("insert into users (id, name) values (1, 'a'), (2, 'b');").is_empty());
+        assert!(lint("insert into users values (1), (2, 'b');").is_empty());
+    }
+
+    #[test]
+    fn test_values_without_location() {
+        // `DEFAULT` and nested rows are values of their own
+        assert_eq!(
+            lint("insert into users (id, name, tags) values (default, row(1, 2), array[1]), (1, 'a');"),
+            vec!["1, 'a' expected 3 values, found 2"]
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use cstree::text::TextRange;
+use pg_query::NodeEnum;
+
+use crate::ast::{location_to_offset, name_token_at, parse_pg_query_stmt, statements};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the ranges of the table names of all `INSERT` statements in `root` without an
+/// explicit column list
+///
+/// Without a column list, the values are assigned to the columns in the order of the table
+/// definition, which silently changes when the schema changes. `INSERT ... DEFAULT VALUES` is
+/// not flagged.
+pub fn insert_without_columns(root: &SyntaxNode) -> Vec<TextRange> {
+    statements(root)
+        .into_iter()
+        .filter_map(|stmt| {
+            let insert = match parse_pg_query_stmt(stmt)? {
+                NodeEnum::InsertStmt(insert) => insert,
+                _ => return None,
+            };
+            if !insert.cols.is_empty() || insert.select_stmt.is_none() {
+                return None;
+            }
+            let offset = location_to_offset(stmt, insert.relation.as_ref()?.location)?;
+            let name = name_token_at(stmt, offset)?;
+            Some(TextRange::new(offset, name.text_range().end()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn lint(input: &str) -> Vec<&str> {
+        insert_without_columns(parse(input).root())
+            .into_iter()
+            .map(|r| &input[r])
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_without_columns() {
+        assert_eq!(
+            lint("insert into users values (1, 'a');\ninsert into public.users select * from old_users;"),
+            vec!["users", "public.users"]
+        );
+    }
+
+    #[test]
+    fn test_insert_with_columns() {
+        assert!(lint(
+            "insert into users (id, name) values (1, 'a');\ninsert into users default values;"
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::ast::column_definitions;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::syntax_node::SyntaxNode;
+
+/// The column names that `likely_missing_not_null` checks by default
+pub const DEFAULT_NOT_NULL_PATTERNS: &[&str] = &["*_id", "created_at", "updated_at"];
+
+/// Returns a hint at every nullable column of the `CREATE TABLE` statement `stmt` whose name
+/// matches one of `patterns`, e.g. `user_id`
+///
+/// Such columns, like foreign keys and timestamps, usually always have a value, and `NOT NULL`
+/// documents this and keeps rows without it out. A `*` in a pattern matches any characters, and
+/// names are matched case insensitively. Since this is a heuristic, the diagnostics are infos.
+pub fn likely_missing_not_null(stmt: &SyntaxNode, patterns: &[&str]) -> Vec<Diagnostic> {
+    column_definitions(stmt)
+        .into_iter()
+        .filter(|column| column.nullable)
+        .filter(|column| {
+            let name = column.name.to_lowercase();
+            patterns
+                .iter()
+                .any(|pattern| matches_pattern(&pattern.to_lowercase(), &name))
+        })
+        .map(|column| {
+            Diagnostic::new(
+                format!(
+                    "column `{}` is probably never `NULL`, consider adding `NOT NULL`",
+                    column.name
+                ),
+                column.range,
+            )
+            .with_severity(Severity::Info)
+        })
+        .collect()
+}
+
+/// Whether `name` matches `pattern`, in which every `*` matches any characters
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match name.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let (last, middle) = match parts.split_last() {
+        Some(parts) => parts,
+        // without a `*`, the whole name is the pattern
+        None => return rest.is_empty(),
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str, patterns: &[&str]) -> Vec<String> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        likely_missing_not_null(&parser.finish().cst, patterns)
+            .iter()
+            .map(|d| input[d.range()].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_likely_missing_not_null() {
+        let input = "create table posts (
+            id bigserial primary key,
+            user_id bigint references users,
+            author_id bigint not null,
+            description text,
+            created_at timestamptz default now(),
+            updated_at timestamptz not null
+        );";
+        assert_eq!(
+            lint(input, DEFAULT_NOT_NULL_PATTERNS),
+            vec!["user_id", "created_at"]
+        );
+        assert_eq!(lint(input, &["desc*"]), vec!["description"]);
+        assert!(lint(input, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("*_id", "user_id"));
+        assert!(!matches_pattern("*_id", "identity"));
+        assert!(matches_pattern("created_at", "created_at"));
+        assert!(!matches_pattern("created_at", "created_at2"));
+        assert!(matches_pattern("*_by_*", "created_by_user"));
+    }
+}
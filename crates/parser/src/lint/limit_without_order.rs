@@ -0,0 +1,68 @@
+use cstree::text::TextRange;
+
+use crate::ast::{limit, order_by, selects_with_parent};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the ranges of all `LIMIT` clauses of selects in `stmt` that are not ordered
+///
+/// Without an `ORDER BY`, the rows returned by a `LIMIT` are nondeterministic. A subquery is not
+/// flagged if the query it is nested in is ordered.
+pub fn limit_without_order(stmt: &SyntaxNode) -> Vec<TextRange> {
+    selects_with_parent(stmt)
+        .iter()
+        .filter(|(select, parent)| {
+            order_by(select).is_empty() && parent.as_ref().is_none_or(|p| order_by(p).is_empty())
+        })
+        .filter_map(|(select, _)| limit(select))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<TextRange> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        limit_without_order(&parsed.cst)
+    }
+
+    #[test]
+    fn test_limit_without_order() {
+        let input = "select * from t limit 10;";
+
+        let ranges = lint(input);
+
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(&input[ranges[0]], "limit 10");
+
+        let input = "select * from t fetch first 10 rows only;";
+        let ranges = lint(input);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(&input[ranges[0]], "fetch first 10");
+
+        let input = "select * from (select * from u fetch first row only) s fetch next row only;";
+        let ranges = lint(input);
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(&input[ranges[0]], "fetch next row only");
+        assert_eq!(&input[ranges[1]], "fetch first row only");
+    }
+
+    #[test]
+    fn test_limit_with_order() {
+        assert!(lint("select * from t order by id limit 10;").is_empty());
+        assert!(lint("select * from t limit all;").is_empty());
+    }
+
+    #[test]
+    fn test_limit_in_subquery() {
+        assert!(lint("select * from (select * from t limit 10) s order by id;").is_empty());
+
+        let input = "select * from (select * from t limit 10) s;";
+        let ranges = lint(input);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(&input[ranges[0]], "limit 10");
+    }
+}
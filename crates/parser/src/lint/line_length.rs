@@ -0,0 +1,100 @@
+use cstree::text::{TextRange, TextSize};
+
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::line_index::LineIndex;
+use crate::syntax_node::SyntaxNode;
+
+/// Configures `line_length`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LineLengthOptions {
+    /// The maximum number of columns of a line
+    pub max: usize,
+    /// The number of columns between tab stops
+    pub tab_width: usize,
+}
+
+impl Default for LineLengthOptions {
+    fn default() -> Self {
+        Self {
+            max: 100,
+            tab_width: 4,
+        }
+    }
+}
+
+/// Returns a diagnostic for every line of `root` that is longer than `options.max` columns
+///
+/// A tab advances to the next tab stop, and every other character takes one column. The
+/// diagnostic covers the part of the line after the maximum.
+pub fn line_length(root: &SyntaxNode, options: &LineLengthOptions) -> Vec<Diagnostic> {
+    let text = root.resolved().text().to_string();
+    let index = LineIndex::new(&text);
+
+    let mut diagnostics = Vec::new();
+    for line in (0..index.line_count()).filter_map(|line| index.line_range(line)) {
+        let mut width = 0;
+        let mut overflow = None;
+        for (idx, c) in text[line].char_indices() {
+            if width >= options.max && overflow.is_none() {
+                overflow = Some(line.start() + TextSize::from(idx as u32));
+            }
+            width += match c {
+                '\t' => options.tab_width - width % options.tab_width.max(1),
+                _ => 1,
+            };
+        }
+        if let Some(start) = overflow.filter(|_| width > options.max) {
+            diagnostics.push(
+                Diagnostic::new(
+                    format!(
+                        "line is {} columns long, which is more than the maximum of {}",
+                        width, options.max
+                    ),
+                    TextRange::new(start, line.end()),
+                )
+                .with_severity(Severity::Hint),
+            );
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn lint(input: &str, max: usize) -> Vec<(String, &str)> {
+        let options = LineLengthOptions { max, tab_width: 4 };
+        line_length(parse(input).root(), &options)
+            .iter()
+            .map(|d| (d.message().to_string(), &input[d.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_line_length() {
+        let input = "select id, name\nfrom users where id = 1;\nselect 1;";
+        assert_eq!(
+            lint(input, 20),
+            vec![(
+                "line is 24 columns long, which is more than the maximum of 20".to_string(),
+                "= 1;"
+            )]
+        );
+        assert!(lint(input, 24).is_empty());
+    }
+
+    #[test]
+    fn test_tabs() {
+        // the tab takes the columns up to the next tab stop at 8
+        let input = "select\t1, 2;";
+        assert_eq!(
+            lint(input, 10),
+            vec![(
+                "line is 13 columns long, which is more than the maximum of 10".to_string(),
+                " 2;"
+            )]
+        );
+    }
+}
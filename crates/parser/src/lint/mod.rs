@@ -0,0 +1,86 @@
+//! Lints for statements.
+//!
+//! The public lint functions check either a statement node or the whole source file, and take
+//! their options as arguments. The `LintRegistry` adapts each of them to a `Lint`, a
+//! `fn(&SyntaxNode) -> Vec<Diagnostic>` that gets the root of a source file, and keys it by a rule
+//! id like `no-select-star`. Most lints run by default; the ones that are registered as disabled,
+//! e.g. `excessive-nesting`, only run if a `Config` enables them. The registry sets the rule id
+//! and the configured severity of every diagnostic, and skips the diagnostics that comments like
+//! `-- pglsp-disable-next-line no-select-star` suppress, see the `suppression` module.
+
+mod ambiguous_columns;
+mod blocking_index_creation;
+mod case_without_else;
+mod concurrently_in_transaction;
+mod discourage_varchar;
+mod distinct_on_without_order;
+mod duplicate_columns;
+mod duplicate_output_aliases;
+mod equality_with_null;
+mod excessive_nesting;
+mod having_without_group_by;
+mod implicit_coercions;
+mod insert_value_counts;
+mod insert_without_columns;
+mod likely_missing_not_null;
+mod limit_without_order;
+mod line_length;
+mod naming;
+mod natural_joins;
+mod non_idempotent_ddl;
+mod non_sargable_predicates;
+mod not_in_subquery;
+mod or_chains_to_in;
+mod order_by_ordinals;
+mod prepared_statements;
+mod redundant_semicolons;
+mod reversed_between;
+mod run;
+mod select_star;
+mod statement_policy;
+mod suppression;
+mod tables_without_pk;
+mod todo_comments;
+mod trailing_whitespace;
+mod unbounded_range_frames;
+mod unnecessary_union;
+mod values_row_lengths;
+mod volatile_generated_columns;
+
+pub use ambiguous_columns::ambiguous_columns;
+pub use blocking_index_creation::{blocking_index_creation, BlockingIndexExemptions};
+pub use case_without_else::{case_without_else, CaseElseExemptions};
+pub use concurrently_in_transaction::concurrently_in_transaction;
+pub use discourage_varchar::{discourage_varchar, VarcharOptions};
+pub use distinct_on_without_order::distinct_on_without_order;
+pub use duplicate_columns::duplicate_columns;
+pub use duplicate_output_aliases::duplicate_output_aliases;
+pub use equality_with_null::equality_with_null;
+pub use excessive_nesting::{excessive_nesting, DEFAULT_MAX_NESTING_DEPTH};
+pub use having_without_group_by::having_without_group_by;
+pub use implicit_coercions::implicit_coercions;
+pub use insert_value_counts::insert_value_counts;
+pub use insert_without_columns::insert_without_columns;
+pub use likely_missing_not_null::{likely_missing_not_null, DEFAULT_NOT_NULL_PATTERNS};
+pub use limit_without_order::limit_without_order;
+pub use line_length::{line_length, LineLengthOptions};
+pub use naming::{naming_violations, NamingConvention};
+pub use natural_joins::natural_joins;
+pub use non_idempotent_ddl::{non_idempotent_ddl, IdempotentDdlOptions};
+pub use non_sargable_predicates::{non_sargable_predicates, SargableOptions};
+pub use not_in_subquery::not_in_subquery;
+pub use or_chains_to_in::or_chains_to_in;
+pub use order_by_ordinals::order_by_ordinals;
+pub use prepared_statements::prepared_statement_usage;
+pub use redundant_semicolons::redundant_semicolons;
+pub use reversed_between::reversed_between;
+pub use run::{run_lints, Config, Lint, LintRegistry, RuleConfig};
+pub use select_star::select_star;
+pub use statement_policy::{check_statement_policy, StatementPolicy};
+pub use tables_without_pk::{tables_without_pk, PrimaryKeyExemptions};
+pub use todo_comments::{todo_comments, DEFAULT_TODO_MARKERS};
+pub use trailing_whitespace::trailing_whitespace;
+pub use unbounded_range_frames::unbounded_range_frames;
+pub use unnecessary_union::unnecessary_union;
+pub use values_row_lengths::values_row_lengths;
+pub use volatile_generated_columns::volatile_generated_columns;
@@ -0,0 +1,130 @@
+use pg_query::NodeEnum;
+
+use crate::ast::name::{is_quoted, needs_quoting, unquote};
+use crate::ast::{location_to_offset, name_token_at, parse_pg_query_stmt, statements};
+use crate::diagnostic::Diagnostic;
+use crate::syntax_node::SyntaxNode;
+
+/// A naming convention for identifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NamingConvention {
+    /// `my_table`
+    SnakeCase,
+    /// `MyTable`
+    CamelCase,
+}
+
+impl NamingConvention {
+    fn is_followed_by(&self, name: &str) -> bool {
+        match self {
+            NamingConvention::SnakeCase => name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+            NamingConvention::CamelCase => {
+                name.starts_with(|c: char| c.is_ascii_uppercase())
+                    && name.chars().all(|c| c.is_ascii_alphanumeric())
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            NamingConvention::SnakeCase => "snake_case",
+            NamingConvention::CamelCase => "CamelCase",
+        }
+    }
+}
+
+/// Returns a diagnostic for every table and column name defined in `root` that does not follow
+/// `convention`
+///
+/// Only definition sites in `CREATE TABLE` statements are checked. Quoted identifiers with
+/// special characters are reported as always needing quotes instead.
+pub fn naming_violations(root: &SyntaxNode, convention: NamingConvention) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for stmt in statements(root) {
+        let create = match parse_pg_query_stmt(stmt) {
+            Some(NodeEnum::CreateStmt(create)) => create,
+            _ => continue,
+        };
+
+        let table = create.relation.as_ref().map(|r| ("table", r.location));
+        let columns = create
+            .table_elts
+            .iter()
+            .filter_map(|n| match n.node.as_ref() {
+                Some(NodeEnum::ColumnDef(c)) => Some(("column", c.location)),
+                _ => None,
+            });
+
+        for (what, location) in table.into_iter().chain(columns) {
+            let token =
+                match location_to_offset(stmt, location).and_then(|o| name_token_at(stmt, o)) {
+                    Some(token) => token,
+                    None => continue,
+                };
+            let text = token.resolved().text();
+            let name = unquote(text);
+            if is_quoted(text) && needs_quoting(&name) {
+                diagnostics.push(Diagnostic::new(
+                    format!(
+                        "{} name `{}` contains special characters and must always be quoted",
+                        what, name
+                    ),
+                    token.text_range(),
+                ));
+            } else if !convention.is_followed_by(&name) {
+                diagnostics.push(Diagnostic::new(
+                    format!("{} name `{}` is not {}", what, name, convention.name()),
+                    token.text_range(),
+                ));
+            }
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str, convention: NamingConvention) -> Vec<String> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        naming_violations(&parsed.cst, convention)
+            .iter()
+            .map(|d| format!("{} {}", &input[d.range()], d))
+            .collect()
+    }
+
+    #[test]
+    fn test_snake_case() {
+        let violations = lint(
+            "create table public.user_accounts (id int, CamelCase text);",
+            NamingConvention::SnakeCase,
+        );
+
+        assert_eq!(
+            violations,
+            vec!["CamelCase column name `CamelCase` is not snake_case"]
+        );
+    }
+
+    #[test]
+    fn test_camel_case_and_quoting() {
+        let violations = lint(
+            "create table \"UserAccounts\" (\"Id\" int, \"first name\" text, last_name text);",
+            NamingConvention::CamelCase,
+        );
+
+        assert_eq!(
+            violations,
+            vec![
+                "\"first name\" column name `first name` contains special characters and must always be quoted",
+                "last_name column name `last_name` is not CamelCase",
+            ]
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use cstree::text::TextRange;
+use pg_query::NodeRef;
+
+use crate::ast::{location_to_offset, parse_pg_query_stmt, token_before};
+use crate::pg_query_utils::{get_nodes, get_position_for_pg_query_node};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the ranges of the `NATURAL ... JOIN` keywords of all natural joins in `stmt`
+///
+/// The columns of a natural join are implied by the columns both sides have in common, which
+/// silently changes when the schema changes.
+pub fn natural_joins(stmt: &SyntaxNode) -> Vec<TextRange> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+
+    let mut ranges = get_nodes(ast.to_ref())
+        .into_iter()
+        .filter_map(|(node, _)| match node {
+            NodeRef::JoinExpr(join) if join.is_natural => Some(join),
+            _ => None,
+        })
+        .filter_map(|join| {
+            // a join has no location, but the keywords precede its right side
+            let rarg = join.rarg.as_ref()?.node.as_ref()?;
+            let position = get_nodes(rarg.to_ref())
+                .iter()
+                .map(|(n, _)| get_position_for_pg_query_node(n))
+                .filter(|p| *p >= 0)
+                .min()?;
+            let offset = location_to_offset(stmt, position)?;
+            let natural = token_before(stmt, SyntaxKind::Natural, offset)?;
+            let join = token_before(stmt, SyntaxKind::Join, offset)?;
+            Some(natural.text_range().cover(join.text_range()))
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|r| r.start());
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<&str> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        natural_joins(&parsed.cst)
+            .into_iter()
+            .map(|r| &input[r])
+            .collect()
+    }
+
+    #[test]
+    fn test_natural_joins() {
+        assert_eq!(
+            lint("select * from a natural join b natural left join (select * from c) c;"),
+            vec!["natural join", "natural left join"]
+        );
+    }
+
+    #[test]
+    fn test_join_on() {
+        assert!(lint("select * from a join b on a.id = b.id;").is_empty());
+    }
+}
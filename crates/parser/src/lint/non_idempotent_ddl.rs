@@ -0,0 +1,130 @@
+use pg_query::NodeEnum;
+
+use crate::ast::{
+    is_idempotent_ddl, non_trivia_tokens, parse_pg_query_stmt, statements, tokens_range,
+};
+use crate::diagnostic::Diagnostic;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Configures which statements `non_idempotent_ddl` checks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotentDdlOptions {
+    /// The kinds of the checked statements, e.g. `SyntaxKind::DropStmt`
+    pub kinds: Vec<SyntaxKind>,
+}
+
+impl Default for IdempotentDdlOptions {
+    /// Checks the `CREATE` and `DROP` statements that support `IF [NOT] EXISTS` or `OR REPLACE`
+    fn default() -> Self {
+        Self {
+            kinds: vec![
+                SyntaxKind::CreateStmt,
+                SyntaxKind::CreateTableAsStmt,
+                SyntaxKind::IndexStmt,
+                SyntaxKind::CreateSeqStmt,
+                SyntaxKind::CreateSchemaStmt,
+                SyntaxKind::CreateExtensionStmt,
+                SyntaxKind::ViewStmt,
+                SyntaxKind::CreateFunctionStmt,
+                SyntaxKind::DropStmt,
+            ],
+        }
+    }
+}
+
+/// Returns a warning for every DDL statement of the source file `root` whose kind is one of
+/// `options` and that fails or has another result when it runs again, e.g. `DROP TABLE` without
+/// `IF EXISTS`
+///
+/// This lint is meant for migrations that may be applied more than once, so its rule
+/// `non-idempotent-ddl` is disabled by default. The diagnostic covers the leading keywords of the
+/// statement, e.g. `CREATE TABLE`.
+pub fn non_idempotent_ddl(root: &SyntaxNode, options: &IdempotentDdlOptions) -> Vec<Diagnostic> {
+    statements(root)
+        .into_iter()
+        .filter(|stmt| is_idempotent_ddl(stmt) == Some(false))
+        .filter_map(|stmt| {
+            let tokens = non_trivia_tokens(stmt);
+            let keywords = tokens
+                .iter()
+                .take_while(|t| {
+                    t.kind() != SyntaxKind::Ident
+                        && t.resolved().text().starts_with(char::is_alphabetic)
+                })
+                .count();
+            let range = tokens_range(&tokens[..keywords.max(1)])?;
+            let ast = parse_pg_query_stmt(stmt)?;
+            if !options
+                .kinds
+                .contains(&SyntaxKind::from_pg_query_node(&ast.to_ref()))
+            {
+                return None;
+            }
+            let hint = match ast {
+                NodeEnum::DropStmt(_) => ", add `IF EXISTS`",
+                NodeEnum::CreateStmt(_)
+                | NodeEnum::CreateTableAsStmt(_)
+                | NodeEnum::IndexStmt(_)
+                | NodeEnum::CreateSeqStmt(_)
+                | NodeEnum::CreateSchemaStmt(_)
+                | NodeEnum::CreateExtensionStmt(_) => ", add `IF NOT EXISTS`",
+                NodeEnum::ViewStmt(_) | NodeEnum::CreateFunctionStmt(_) => {
+                    ", use `CREATE OR REPLACE`"
+                }
+                _ => "",
+            };
+            Some(Diagnostic::new(
+                format!("this statement fails when it runs again{}", hint),
+                range,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn lint<'a>(input: &'a str, options: &IdempotentDdlOptions) -> Vec<(String, &'a str)> {
+        non_idempotent_ddl(parse(input).root(), options)
+            .iter()
+            .map(|d| (d.message().to_string(), &input[d.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_non_idempotent_ddl() {
+        assert_eq!(
+            lint(
+                "create table if not exists a (id int);\ncreate table b (id int);\ndrop table x;\ndrop table if exists x;\nalter table b add column c int;\ninsert into b values (1);",
+                &IdempotentDdlOptions::default()
+            ),
+            vec![
+                (
+                    "this statement fails when it runs again, add `IF NOT EXISTS`".to_string(),
+                    "create table"
+                ),
+                (
+                    "this statement fails when it runs again, add `IF EXISTS`".to_string(),
+                    "drop table"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_configured_kinds() {
+        let options = IdempotentDdlOptions {
+            kinds: vec![SyntaxKind::AlterTableStmt],
+        };
+        assert_eq!(
+            lint("drop table x;\nalter table b add column c int;", &options),
+            vec![(
+                "this statement fails when it runs again".to_string(),
+                "alter table"
+            )]
+        );
+    }
+}
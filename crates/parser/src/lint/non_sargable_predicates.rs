@@ -0,0 +1,195 @@
+use pg_query::protobuf::{AExprKind, Node};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{
+    closing_parenthesis, location_to_offset, name_token_at, non_trivia_tokens, parse_pg_query_stmt,
+};
+use crate::diagnostic::Diagnostic;
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_node::SyntaxNode;
+
+/// Configures `non_sargable_predicates`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SargableOptions {
+    /// The functions that keep an index on their argument from being used, e.g. `lower`
+    pub functions: Vec<String>,
+}
+
+impl Default for SargableOptions {
+    fn default() -> Self {
+        Self {
+            functions: [
+                "abs",
+                "btrim",
+                "coalesce",
+                "date",
+                "date_part",
+                "date_trunc",
+                "length",
+                "lower",
+                "ltrim",
+                "round",
+                "rtrim",
+                "substr",
+                "substring",
+                "to_char",
+                "trim",
+                "upper",
+            ]
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        }
+    }
+}
+
+/// Returns a warning for every comparison in a `WHERE` or `JOIN ... ON` condition of `stmt` that
+/// wraps a column in one of the functions of `options`, e.g. `lower(name) = 'x'`
+///
+/// An index on the column cannot be used for such a comparison, only an index on the same
+/// expression. Functions that are applied to literals only, e.g. `name = lower('X')`, are not
+/// flagged. The diagnostic covers the function call.
+pub fn non_sargable_predicates(stmt: &SyntaxNode, options: &SargableOptions) -> Vec<Diagnostic> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+    let tokens = non_trivia_tokens(stmt);
+
+    let mut calls = get_nodes(ast.to_ref())
+        .into_iter()
+        .filter_map(|(node, _)| match node {
+            NodeRef::SelectStmt(n) => n.where_clause.as_deref(),
+            NodeRef::UpdateStmt(n) => n.where_clause.as_deref(),
+            NodeRef::DeleteStmt(n) => n.where_clause.as_deref(),
+            NodeRef::JoinExpr(n) => n.quals.as_deref(),
+            _ => None,
+        })
+        .filter_map(|condition| condition.node.as_ref())
+        .flat_map(|condition| get_nodes(condition.to_ref()))
+        .filter_map(|(node, _)| match node {
+            NodeRef::AExpr(expr) if expr.kind == AExprKind::AexprOp as i32 => Some(expr),
+            _ => None,
+        })
+        .filter(|expr| {
+            matches!(
+                expr.name.as_slice(),
+                [Node { node: Some(NodeEnum::String(s)) }]
+                    if ["=", "<>", "!=", "<", "<=", ">", ">="].contains(&s.str.as_str())
+            )
+        })
+        .flat_map(|expr| [&expr.lexpr, &expr.rexpr])
+        .filter_map(|operand| match operand.as_deref()?.node.as_ref()? {
+            NodeEnum::FuncCall(call) => match call.funcname.last()?.node.as_ref()? {
+                NodeEnum::String(name) => Some((name.str.as_str(), &call.args, call.location)),
+                _ => None,
+            },
+            // `coalesce` is an expression of its own rather than a function call
+            NodeEnum::CoalesceExpr(expr) => Some(("coalesce", &expr.args, expr.location)),
+            _ => None,
+        })
+        .filter_map(|(name, args, location)| {
+            if !options.functions.iter().any(|f| f == name) {
+                return None;
+            }
+            Some((name.to_string(), column_of(args)?, location))
+        })
+        .collect::<Vec<_>>();
+    // a condition of a nested select is also part of the condition it is nested in
+    calls.sort_by_key(|(_, _, location)| *location);
+    calls.dedup_by_key(|(_, _, location)| *location);
+
+    calls
+        .into_iter()
+        .filter_map(|(function, column, location)| {
+            let start = location_to_offset(stmt, location)?;
+            let first = tokens
+                .iter()
+                .position(|t| t.text_range().start() == start)?;
+            let name = name_token_at(stmt, start)?;
+            let open = tokens.iter().position(|t| t == &name)? + 1;
+            let close = closing_parenthesis(&tokens, open)?;
+            Some(Diagnostic::new(
+                format!(
+                    "`{}` wraps the column `{}`, which prevents the use of an index on `{}`",
+                    function, column, column
+                ),
+                tokens[first].text_range().cover(tokens[close].text_range()),
+            ))
+        })
+        .collect()
+}
+
+/// Returns the name of the first column that the arguments `args` of a call refer to
+fn column_of(args: &[Node]) -> Option<String> {
+    args.iter()
+        .filter_map(|arg| arg.node.as_ref())
+        .flat_map(|arg| get_nodes(arg.to_ref()))
+        .find_map(|(node, _)| match node {
+            NodeRef::ColumnRef(column) => column
+                .fields
+                .iter()
+                .map(|f| match f.node.as_ref() {
+                    Some(NodeEnum::String(s)) => Some(s.str.as_str()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(|fields| fields.join(".")),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<(String, &str)> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        non_sargable_predicates(&parsed.cst, &SargableOptions::default())
+            .iter()
+            .map(|d| (d.message().to_string(), &input[d.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_non_sargable_predicates() {
+        assert_eq!(
+            lint("select * from users u join logins l on date_trunc('day', l.ts) = u.created_at where lower(name) = 'x';"),
+            vec![
+                (
+                    "`date_trunc` wraps the column `l.ts`, which prevents the use of an index on `l.ts`"
+                        .to_string(),
+                    "date_trunc('day', l.ts)"
+                ),
+                (
+                    "`lower` wraps the column `name`, which prevents the use of an index on `name`"
+                        .to_string(),
+                    "lower(name)"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_coalesce() {
+        assert_eq!(
+            lint("select * from t where coalesce(x, 0) = 1;"),
+            vec![(
+                "`coalesce` wraps the column `x`, which prevents the use of an index on `x`"
+                    .to_string(),
+                "coalesce(x, 0)"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_sargable_predicates() {
+        assert!(lint("select * from users where name = lower('X');").is_empty());
+        assert!(lint("select * from users where x = coalesce(1, 2);").is_empty());
+        assert!(lint("select lower(name) = 'x' from users where id = abs(-1);").is_empty());
+        assert!(lint("select * from users where my_function(name) = 'x';").is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+use pg_query::protobuf::{BoolExprType, SubLinkType};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{
+    closing_parenthesis, non_trivia_tokens, parse_pg_query_stmt, query_range, token_at,
+};
+use crate::diagnostic::Diagnostic;
+use crate::pg_query_utils::{get_nodes, get_position_for_pg_query_node};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns a warning for every `NOT IN` with a subquery in `stmt`, e.g.
+/// `x NOT IN (SELECT y FROM t)`
+///
+/// If the subquery returns a `NULL`, the `NOT IN` is never true, so no rows match. `NOT EXISTS`
+/// does not have this pitfall. A `NOT IN` with a list of values is not flagged, since its values
+/// are visible. The diagnostic ranges from the tested value to the closing parenthesis of the
+/// subquery, or over the whole negation if `NOT` precedes the tested value, as in
+/// `NOT (x IN (SELECT y FROM t))`.
+pub fn not_in_subquery(stmt: &SyntaxNode) -> Vec<Diagnostic> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+    let tokens = non_trivia_tokens(stmt);
+
+    let mut diagnostics = Vec::new();
+    for (node, _) in get_nodes(ast.to_ref()) {
+        let (sub_link, expr_location) = match node {
+            NodeRef::BoolExpr(expr) if expr.boolop == BoolExprType::NotExpr as i32 => {
+                match expr.args.as_slice() {
+                    [arg] => match arg.node.as_ref() {
+                        Some(NodeEnum::SubLink(sub_link)) => (sub_link, expr.location),
+                        _ => continue,
+                    },
+                    _ => continue,
+                }
+            }
+            _ => continue,
+        };
+        // `IN` has no operator, unlike `NOT (x = ANY (...))`
+        if sub_link.sub_link_type != SubLinkType::AnySublink as i32
+            || !sub_link.oper_name.is_empty()
+        {
+            continue;
+        }
+
+        let (first, subquery) = match (
+            token_at(
+                stmt,
+                get_position_for_pg_query_node(&NodeRef::SubLink(sub_link)),
+            ),
+            sub_link
+                .subselect
+                .as_deref()
+                .and_then(|query| query_range(stmt, &tokens, query)),
+        ) {
+            (Some(first), Some(subquery)) => (first.text_range(), subquery),
+            _ => continue,
+        };
+        let range = match token_at(stmt, expr_location) {
+            // `NOT (x IN (...))` or `NOT x IN (...)`, where `NOT` precedes the tested value
+            Some(not) if not.text_range().start() < first.start() => {
+                let idx = tokens.iter().position(|t| *t == not);
+                let end = idx
+                    .and_then(|idx| closing_parenthesis(&tokens, idx + 1))
+                    .map(|close| tokens[close].text_range())
+                    .filter(|close| close.start() >= subquery.end())
+                    .unwrap_or(subquery);
+                not.text_range().cover(end)
+            }
+            _ => first.cover(subquery),
+        };
+        diagnostics.push(Diagnostic::new(
+            "`NOT IN` with a subquery matches no rows if the subquery returns a `NULL`, use `NOT EXISTS` instead",
+            range,
+        ));
+    }
+    diagnostics.sort_by_key(|d| d.range().start());
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<&str> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        not_in_subquery(&parser.finish().cst)
+            .iter()
+            .map(|d| &input[d.range()])
+            .collect()
+    }
+
+    #[test]
+    fn test_not_in_subquery() {
+        assert_eq!(
+            lint("select * from users u where u.id not in (select user_id from orders) and id in (select 1);"),
+            vec!["u.id not in (select user_id from orders)"]
+        );
+        assert_eq!(
+            lint("delete from t where (a, b) not in (select a, b from u);"),
+            vec!["(a, b) not in (select a, b from u)"]
+        );
+    }
+
+    #[test]
+    fn test_negated_in_subquery() {
+        assert_eq!(
+            lint("select * from t where not (x in (select y from u)) and z = 1;"),
+            vec!["not (x in (select y from u))"]
+        );
+        assert_eq!(
+            lint("select * from t where not x in (select y from u);"),
+            vec!["not x in (select y from u)"]
+        );
+    }
+
+    #[test]
+    fn test_not_in_list() {
+        assert!(lint("select * from users where id not in (1, 2, 3);").is_empty());
+        assert!(lint(
+            "select * from users u where not exists (select 1 from orders o where o.user_id = u.id);"
+        )
+        .is_empty());
+    }
+}
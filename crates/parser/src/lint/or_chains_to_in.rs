@@ -0,0 +1,202 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::{AExprKind, BoolExprType, Node};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{constant_range, location_to_offset, name_token_at, parse_pg_query_stmt};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_node::SyntaxNode;
+
+/// The kind of the constant of a comparison
+///
+/// Integers and decimals are both numeric constants, which an `IN` list can mix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LiteralKind {
+    Number,
+    String,
+    BitString,
+    Null,
+}
+
+/// A comparison `column = constant` within an `OR` chain
+struct Comparison {
+    /// The lowercase name of the column, e.g. `t.id`
+    column: String,
+    /// The kind of the constant
+    kind: LiteralKind,
+    /// The range of the column in the source
+    column_range: TextRange,
+    /// The range of the constant in the source
+    value_range: TextRange,
+}
+
+/// Returns the comparison of `node`, if it is `column = constant` or `constant = column`
+fn comparison(stmt: &SyntaxNode, node: &Node) -> Option<Comparison> {
+    let expr = match node.node.as_ref()? {
+        NodeEnum::AExpr(e) if e.kind == AExprKind::AexprOp as i32 => e,
+        _ => return None,
+    };
+    let is_equals = matches!(
+        expr.name.as_slice(),
+        [Node { node: Some(NodeEnum::String(s)) }] if s.str == "="
+    );
+    if !is_equals {
+        return None;
+    }
+
+    let (column, value) = match (
+        expr.lexpr.as_deref()?.node.as_ref()?,
+        expr.rexpr.as_deref()?.node.as_ref()?,
+    ) {
+        (NodeEnum::ColumnRef(c), NodeEnum::AConst(v))
+        | (NodeEnum::AConst(v), NodeEnum::ColumnRef(c)) => (c, v),
+        _ => return None,
+    };
+    let kind = match value.val.as_ref()?.node.as_ref()? {
+        NodeEnum::Integer(_) | NodeEnum::Float(_) => LiteralKind::Number,
+        NodeEnum::String(_) => LiteralKind::String,
+        NodeEnum::BitString(_) => LiteralKind::BitString,
+        NodeEnum::Null(_) => LiteralKind::Null,
+        _ => return None,
+    };
+    let names = column
+        .fields
+        .iter()
+        .map(|f| match f.node.as_ref() {
+            Some(NodeEnum::String(s)) => Some(s.str.to_lowercase()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let column_start = location_to_offset(stmt, column.location)?;
+    Some(Comparison {
+        column: names.join("."),
+        kind,
+        column_range: TextRange::new(
+            column_start,
+            name_token_at(stmt, column_start)?.text_range().end(),
+        ),
+        value_range: constant_range(stmt, value.location)?,
+    })
+}
+
+/// Returns a diagnostic for every run of at least two `column = constant` comparisons on the same
+/// column within an `OR` chain of `stmt`
+///
+/// Such a chain is clearer as `column IN (...)`. The diagnostic covers the comparisons of the run.
+/// Comparisons on different columns or with constants of another kind break the run, e.g.
+/// `a = 1 OR b = 2 OR a = 3` and `a = 1 OR a = 'x'` are not flagged.
+pub fn or_chains_to_in(stmt: &SyntaxNode) -> Vec<Diagnostic> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+    let text = stmt.resolved().text().to_string();
+    let source = |range: TextRange| {
+        let range = range - stmt.text_range().start();
+        text[range].to_string()
+    };
+
+    let mut diagnostics = Vec::new();
+    for (node, _) in get_nodes(ast.to_ref()) {
+        let args = match node {
+            NodeRef::BoolExpr(e) if e.boolop == BoolExprType::OrExpr as i32 => &e.args,
+            _ => continue,
+        };
+
+        let mut runs: Vec<Vec<Comparison>> = Vec::new();
+        for arg in args {
+            match (comparison(stmt, arg), runs.last_mut()) {
+                (Some(c), Some(run))
+                    if run
+                        .first()
+                        .is_some_and(|r| r.column == c.column && r.kind == c.kind) =>
+                {
+                    run.push(c)
+                }
+                (Some(c), _) => runs.push(vec![c]),
+                (None, _) => runs.push(Vec::new()),
+            }
+        }
+
+        for run in runs.iter().filter(|run| run.len() > 1) {
+            let range = run
+                .iter()
+                .map(|c| c.column_range.cover(c.value_range))
+                .fold(run[0].column_range, |a, b| a.cover(b));
+            let values = run
+                .iter()
+                .map(|c| source(c.value_range))
+                .collect::<Vec<_>>();
+            diagnostics.push(
+                Diagnostic::new(
+                    format!(
+                        "this chain of `OR` comparisons is clearer as `{} IN ({})`",
+                        source(run[0].column_range),
+                        values.join(", ")
+                    ),
+                    range,
+                )
+                .with_severity(Severity::Hint),
+            );
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<(String, &str)> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        or_chains_to_in(&parsed.cst)
+            .iter()
+            .map(|d| (d.message().to_string(), &input[d.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_or_chain_on_same_column() {
+        assert_eq!(
+            lint("select * from t where x = 1 or x = 2 or 3 = x;"),
+            vec![(
+                "this chain of `OR` comparisons is clearer as `x IN (1, 2, 3)`".to_string(),
+                "x = 1 or x = 2 or 3 = x"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_or_chain_on_different_columns() {
+        assert!(lint("select * from t where x = 1 or y = 2;").is_empty());
+        assert!(lint("select * from t where x = 1 or y = 2 or x = 3;").is_empty());
+        assert!(lint("select * from t where x = 1 or y > 2 or x = 3;").is_empty());
+    }
+
+    #[test]
+    fn test_or_chain_with_negative_numbers() {
+        assert_eq!(
+            lint("select * from t where x = -1 or x = - 2.5;"),
+            vec![(
+                "this chain of `OR` comparisons is clearer as `x IN (-1, - 2.5)`".to_string(),
+                "x = -1 or x = - 2.5"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_or_chain_with_different_literal_kinds() {
+        assert!(lint("select * from t where x = 1 or x = 'a';").is_empty());
+        assert_eq!(
+            lint("select * from t where x = 'a' or x = 1 or x = 2.5;"),
+            vec![(
+                "this chain of `OR` comparisons is clearer as `x IN (1, 2.5)`".to_string(),
+                "x = 1 or x = 2.5"
+            )]
+        );
+    }
+}
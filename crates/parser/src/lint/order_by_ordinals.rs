@@ -0,0 +1,85 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::Node;
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{parse_pg_query_stmt, token_at};
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the ranges of all positions of output columns in the `ORDER BY` and `GROUP BY`
+/// clauses of `stmt`, e.g. `1` in `ORDER BY 1`
+///
+/// An ordinal silently refers to another column when the select list changes, so naming the
+/// column is more robust. Numbers within expressions, e.g. `ORDER BY col + 1`, are not positions
+/// and are not flagged.
+pub fn order_by_ordinals(stmt: &SyntaxNode) -> Vec<TextRange> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+
+    let mut ranges = get_nodes(ast.to_ref())
+        .into_iter()
+        .filter_map(|(node, _)| match node {
+            NodeRef::SelectStmt(select) => Some(select),
+            _ => None,
+        })
+        .flat_map(|select| {
+            let order_by = select
+                .sort_clause
+                .iter()
+                .filter_map(|n| match n.node.as_ref() {
+                    Some(NodeEnum::SortBy(sort)) => sort.node.as_deref(),
+                    _ => None,
+                });
+            order_by
+                .chain(select.group_clause.iter())
+                .filter_map(ordinal_location)
+                .collect::<Vec<_>>()
+        })
+        .filter_map(|location| token_at(stmt, location).map(|t| t.text_range()))
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|r| r.start());
+    ranges
+}
+
+/// Returns the location of `node` if it is an integer constant
+fn ordinal_location(node: &Node) -> Option<i32> {
+    match node.node.as_ref()? {
+        NodeEnum::AConst(value) => match value.val.as_ref()?.node.as_ref()? {
+            NodeEnum::Integer(_) => Some(value.location),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<&str> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        order_by_ordinals(&parser.finish().cst)
+            .into_iter()
+            .map(|r| &input[r])
+            .collect()
+    }
+
+    #[test]
+    fn test_order_by_ordinals() {
+        let input = "select a, count(*) from t group by 1 order by 2 desc, a;";
+        let ranges = lint(input);
+        assert_eq!(ranges, vec!["1", "2"]);
+    }
+
+    #[test]
+    fn test_expressions_with_numbers() {
+        assert!(lint(
+            "select a from t group by a, b + 1 order by col + 1, 'x', row_number() over (order by 1);"
+        )
+        .is_empty());
+    }
+}
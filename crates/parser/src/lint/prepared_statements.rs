@@ -0,0 +1,101 @@
+use cstree::text::TextRange;
+
+use crate::ast::{deallocate_statement, execute_statement, prepare_statement, statements};
+use crate::diagnostic::Diagnostic;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns a warning for every `EXECUTE` in `root` of a name that is not prepared before, and
+/// for every `PREPARE` that is never executed
+///
+/// A prepared statement lasts until it is deallocated, either by its name or with
+/// `DEALLOCATE ALL`. Statements that are prepared or executed by another file of the same session
+/// are flagged as well, so the warnings are only a hint.
+pub fn prepared_statement_usage(root: &SyntaxNode) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    // the name, the range of the name and whether it is executed of every prepared statement
+    let mut prepared: Vec<(String, TextRange, bool)> = Vec::new();
+    let unused = |(name, range, executed): (String, TextRange, bool)| {
+        (!executed).then(|| {
+            Diagnostic::new(
+                format!("prepared statement `{}` is never executed", name),
+                range,
+            )
+        })
+    };
+
+    for stmt in statements(root) {
+        if let Some(prepare) = prepare_statement(stmt) {
+            if let Some(idx) = prepared.iter().position(|(n, _, _)| *n == prepare.name) {
+                diagnostics.extend(unused(prepared.remove(idx)));
+            }
+            prepared.push((prepare.name, prepare.name_range, false));
+        } else if let Some(execute) = execute_statement(stmt) {
+            match prepared.iter_mut().find(|(n, _, _)| *n == execute.name) {
+                Some((_, _, executed)) => *executed = true,
+                None => diagnostics.push(Diagnostic::new(
+                    format!("prepared statement `{}` does not exist", execute.name),
+                    execute.name_range,
+                )),
+            }
+        } else if let Some(deallocate) = deallocate_statement(stmt) {
+            match deallocate.name {
+                Some((name, _)) => {
+                    if let Some(idx) = prepared.iter().position(|(n, _, _)| *n == name) {
+                        diagnostics.extend(unused(prepared.remove(idx)));
+                    }
+                }
+                None => diagnostics.extend(prepared.drain(..).filter_map(unused)),
+            }
+        }
+    }
+    diagnostics.extend(prepared.into_iter().filter_map(unused));
+    diagnostics.sort_by_key(|d| d.range().start());
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn lint(input: &str) -> Vec<(String, &str)> {
+        prepared_statement_usage(parse(input).root())
+            .iter()
+            .map(|d| (d.message().to_string(), &input[d.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_prepared_and_executed() {
+        assert!(lint(
+            "prepare find (int) as select * from t where id = $1;\nexecute find (1);\ndeallocate find;"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_prepared_statement_usage() {
+        let input = "execute unknown;\nprepare unused as select 1;\nprepare find as select 2;\ndeallocate all;\nexecute find;";
+        assert_eq!(
+            lint(input),
+            vec![
+                (
+                    "prepared statement `unknown` does not exist".to_string(),
+                    "unknown"
+                ),
+                (
+                    "prepared statement `unused` is never executed".to_string(),
+                    "unused"
+                ),
+                (
+                    "prepared statement `find` is never executed".to_string(),
+                    "find"
+                ),
+                (
+                    "prepared statement `find` does not exist".to_string(),
+                    "find"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use crate::diagnostic::Diagnostic;
+use crate::fix::{Fix, TextEdit};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns a diagnostic for every semicolon in `root` that does not terminate a statement
+///
+/// The diagnostic covers the semicolon, and its fix removes it.
+pub fn redundant_semicolons(root: &SyntaxNode) -> Vec<Diagnostic> {
+    root.children()
+        .filter(|n| n.kind() == SyntaxKind::EmptyStatement)
+        .filter_map(|n| {
+            n.children_with_tokens()
+                .filter_map(|e| e.into_token())
+                .find(|t| t.kind() == SyntaxKind::Ascii59)
+                .map(|t| {
+                    Diagnostic::new("redundant semicolon", t.text_range()).with_fix(Fix::new(
+                        "Remove the semicolon",
+                        vec![TextEdit::delete(t.text_range())],
+                    ))
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use cstree::text::{TextRange, TextSize};
+
+    use super::*;
+    use crate::fix::apply_fixes;
+    use crate::parser::parse;
+
+    fn lint(input: &str) -> Vec<TextRange> {
+        redundant_semicolons(parse(input).root())
+            .iter()
+            .map(|d| d.range())
+            .collect()
+    }
+
+    #[test]
+    fn test_redundant_semicolons() {
+        assert_eq!(
+            lint("select 1;;"),
+            vec![TextRange::at(TextSize::from(9), TextSize::from(1))]
+        );
+        assert_eq!(
+            lint(";\nselect 1;"),
+            vec![TextRange::at(TextSize::from(0), TextSize::from(1))]
+        );
+    }
+
+    #[test]
+    fn test_terminating_semicolons() {
+        assert!(lint("select 1;\nselect 2;").is_empty());
+    }
+
+    #[test]
+    fn test_remove_redundant_semicolons() {
+        let input = "select 1;;\n;select 2;";
+        let fixes = redundant_semicolons(parse(input).root())
+            .iter()
+            .filter_map(|d| d.fix().cloned())
+            .collect::<Vec<_>>();
+
+        assert_eq!(apply_fixes(input, &fixes), "select 1;\nselect 2;");
+    }
+}
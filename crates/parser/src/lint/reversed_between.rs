@@ -0,0 +1,182 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::{AConst, AExprKind, Node};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{non_trivia_tokens, parse_pg_query_stmt, token_at};
+use crate::diagnostic::Diagnostic;
+use crate::pg_query_utils::{get_nodes, get_position_for_pg_query_node};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// The value of a constant bound of a `BETWEEN`
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+enum Bound {
+    Number(f64),
+    /// A date in the ISO format `YYYY-MM-DD`, which sorts like its text
+    Date(String),
+}
+
+impl Bound {
+    fn of(node: &Node) -> Option<Self> {
+        match node.node.as_ref()? {
+            NodeEnum::AConst(value) => Self::of_number(value),
+            NodeEnum::TypeCast(cast) => {
+                let is_date = matches!(
+                    cast.type_name.as_ref()?.names.last()?.node.as_ref()?,
+                    NodeEnum::String(s) if s.str.eq_ignore_ascii_case("date")
+                );
+                let text = match cast.arg.as_deref()?.node.as_ref()? {
+                    NodeEnum::AConst(value) => match value.val.as_ref()?.node.as_ref()? {
+                        NodeEnum::String(s) => s.str.as_str(),
+                        _ => return None,
+                    },
+                    _ => return None,
+                };
+                let is_iso = text.len() == 10
+                    && text.char_indices().all(|(idx, c)| match idx {
+                        4 | 7 => c == '-',
+                        _ => c.is_ascii_digit(),
+                    });
+                (is_date && is_iso).then(|| Bound::Date(text.to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    fn of_number(value: &AConst) -> Option<Self> {
+        match value.val.as_ref()?.node.as_ref()? {
+            NodeEnum::Integer(i) => Some(Bound::Number(i.ival as f64)),
+            NodeEnum::Float(f) => f.str.parse().ok().map(Bound::Number),
+            _ => None,
+        }
+    }
+}
+
+/// Returns a warning for every `BETWEEN` in `stmt` whose lower bound is greater than its upper
+/// bound, e.g. `x BETWEEN 10 AND 1`, which never matches any row
+///
+/// Only bounds that are both numeric literals or both date literals are compared, and
+/// `BETWEEN SYMMETRIC` is not flagged because it swaps the bounds itself. The diagnostic covers
+/// the whole expression.
+pub fn reversed_between(stmt: &SyntaxNode) -> Vec<Diagnostic> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+
+    let mut diagnostics = Vec::new();
+    for (node, _) in get_nodes(ast.to_ref()) {
+        let expr = match node {
+            NodeRef::AExpr(expr)
+                if expr.kind == AExprKind::AexprBetween as i32
+                    || expr.kind == AExprKind::AexprNotBetween as i32 =>
+            {
+                expr
+            }
+            _ => continue,
+        };
+        let (low, high) = match expr.rexpr.as_deref().and_then(|n| n.node.as_ref()) {
+            Some(NodeEnum::List(list)) if list.items.len() == 2 => (&list.items[0], &list.items[1]),
+            _ => continue,
+        };
+        let reversed = match (Bound::of(low), Bound::of(high)) {
+            (Some(low @ Bound::Number(_)), Some(high @ Bound::Number(_)))
+            | (Some(low @ Bound::Date(_)), Some(high @ Bound::Date(_))) => low > high,
+            _ => false,
+        };
+        if !reversed {
+            continue;
+        }
+
+        let positions = get_nodes(node)
+            .iter()
+            .map(|(n, _)| get_position_for_pg_query_node(n))
+            .filter(|p| *p >= 0)
+            .collect::<Vec<_>>();
+        let range = match (
+            positions.iter().min().and_then(|p| token_at(stmt, *p)),
+            positions.iter().max().and_then(|p| token_at(stmt, *p)),
+        ) {
+            (Some(first), Some(last)) => first.text_range().cover(last_token(stmt, last)),
+            _ => continue,
+        };
+        diagnostics.push(Diagnostic::new(
+            format!(
+                "the lower bound of `BETWEEN` is greater than its upper bound, so the expression is always {}",
+                if expr.kind == AExprKind::AexprBetween as i32 {
+                    "false"
+                } else {
+                    "true"
+                }
+            ),
+            range,
+        ));
+    }
+    diagnostics.sort_by_key(|d| d.range().start());
+    diagnostics
+}
+
+/// Returns the range of the last token of the expression whose last positioned token is `last`
+///
+/// A negative constant is positioned at its sign, so its number follows the sign.
+fn last_token(stmt: &SyntaxNode, last: &SyntaxToken) -> TextRange {
+    if last.kind() != SyntaxKind::ArithmeticOp {
+        return last.text_range();
+    }
+    non_trivia_tokens(stmt)
+        .into_iter()
+        .skip_while(|t| *t != last)
+        .nth(1)
+        .map_or(last.text_range(), |t| t.text_range())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<(String, &str)> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        reversed_between(&parser.finish().cst)
+            .iter()
+            .map(|d| (d.message().to_string(), &input[d.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_reversed_between() {
+        assert_eq!(
+            lint("select * from t where t.x between 10 and 1 or d not between '2020-02-01'::date and '2020-01-01'::date;"),
+            vec![
+                (
+                    "the lower bound of `BETWEEN` is greater than its upper bound, so the expression is always false".to_string(),
+                    "t.x between 10 and 1"
+                ),
+                (
+                    "the lower bound of `BETWEEN` is greater than its upper bound, so the expression is always true".to_string(),
+                    "d not between '2020-02-01'::date and '2020-01-01'::date"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reversed_between_negative_bounds() {
+        assert_eq!(
+            lint("select * from t where x between -1 and -10;"),
+            vec![(
+                "the lower bound of `BETWEEN` is greater than its upper bound, so the expression is always false".to_string(),
+                "x between -1 and -10"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_ordered_between() {
+        assert!(lint(
+            "select * from t where x between a and b or x between 1 and 10 or x between 1.5 and -2 * 3 or x between symmetric 10 and 1;"
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,415 @@
+use std::collections::{BTreeMap, HashMap};
+
+use cstree::text::TextRange;
+
+use crate::ast::statements;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::lint::suppression::suppressions;
+use crate::lint::{
+    blocking_index_creation, case_without_else, check_statement_policy,
+    concurrently_in_transaction, discourage_varchar, distinct_on_without_order, duplicate_columns,
+    duplicate_output_aliases, equality_with_null, excessive_nesting, having_without_group_by,
+    insert_value_counts, insert_without_columns, likely_missing_not_null, limit_without_order,
+    line_length, naming_violations, natural_joins, non_idempotent_ddl, non_sargable_predicates,
+    not_in_subquery, or_chains_to_in, order_by_ordinals, prepared_statement_usage,
+    redundant_semicolons, reversed_between, select_star, tables_without_pk, todo_comments,
+    trailing_whitespace, unbounded_range_frames, unnecessary_union, values_row_lengths,
+    volatile_generated_columns, IdempotentDdlOptions, LineLengthOptions, NamingConvention,
+    SargableOptions, StatementPolicy, VarcharOptions, DEFAULT_MAX_NESTING_DEPTH,
+    DEFAULT_NOT_NULL_PATTERNS, DEFAULT_TODO_MARKERS,
+};
+use crate::syntax_node::SyntaxNode;
+
+/// The configuration of a single lint rule
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuleConfig {
+    /// Whether the rule is run, or `None` to run it unless it is disabled by default
+    pub enabled: Option<bool>,
+    /// The severity of the diagnostics of the rule, instead of the one the lint reports
+    pub severity: Option<Severity>,
+}
+
+/// Configures which lints `run_lints` runs, by their rule ids
+///
+/// Rules that are not configured run with their own severity, unless they are disabled by
+/// default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    rules: HashMap<String, RuleConfig>,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables the rule with the id `rule`, e.g. `natural-joins`
+    pub fn with_rule_enabled(mut self, rule: &str, enabled: bool) -> Self {
+        self.rules.entry(rule.to_string()).or_default().enabled = Some(enabled);
+        self
+    }
+
+    /// Reports the diagnostics of the rule with the id `rule` with `severity`
+    pub fn with_rule_severity(mut self, rule: &str, severity: Severity) -> Self {
+        self.rules.entry(rule.to_string()).or_default().severity = Some(severity);
+        self
+    }
+
+    /// Returns the configuration of the rule with the id `rule`
+    pub fn rule(&self, rule: &str) -> RuleConfig {
+        self.rules.get(rule).copied().unwrap_or_default()
+    }
+
+    /// Whether the rule with the id `rule` is run, where `by_default` is whether the rule runs if
+    /// it is not configured
+    pub fn is_enabled(&self, rule: &str, by_default: bool) -> bool {
+        self.rule(rule).enabled.unwrap_or(by_default)
+    }
+}
+
+/// A lint of a source file
+pub type Lint = fn(&SyntaxNode) -> Vec<Diagnostic>;
+
+/// The built-in lints with their rule ids, using the default options of every lint
+const LINTS: &[(&str, Lint)] = &[
+    ("blocking-index-creation", |root| {
+        blocking_index_creation(root, Default::default())
+    }),
+    ("case-without-else", |root| {
+        per_statement(root, |stmt| {
+            with_message(
+                case_without_else(stmt, Default::default()),
+                "`CASE` without `ELSE` evaluates to `NULL` if no arm matches",
+            )
+        })
+    }),
+    ("concurrently-in-transaction", concurrently_in_transaction),
+    ("discourage-varchar", |root| {
+        discourage_varchar(root, &VarcharOptions::default())
+    }),
+    ("distinct-on-without-order", |root| {
+        per_statement(root, |stmt| {
+            with_message(
+                distinct_on_without_order(stmt),
+                "`DISTINCT ON` without a matching `ORDER BY` keeps an arbitrary row",
+            )
+        })
+    }),
+    ("duplicate-columns", |root| {
+        per_statement(root, duplicate_columns)
+    }),
+    ("duplicate-output-aliases", |root| {
+        per_statement(root, duplicate_output_aliases)
+    }),
+    ("equality-with-null", |root| {
+        per_statement(root, equality_with_null)
+    }),
+    ("having-without-group-by", |root| {
+        per_statement(root, |stmt| {
+            with_message(
+                having_without_group_by(stmt),
+                "`HAVING` without `GROUP BY` treats all rows as a single group",
+            )
+        })
+    }),
+    ("insert-value-counts", |root| {
+        per_statement(root, insert_value_counts)
+    }),
+    ("insert-without-columns", |root| {
+        with_message(
+            insert_without_columns(root),
+            "`INSERT` without a column list depends on the order of the columns of the table",
+        )
+    }),
+    ("likely-missing-not-null", |root| {
+        per_statement(root, |stmt| {
+            likely_missing_not_null(stmt, DEFAULT_NOT_NULL_PATTERNS)
+        })
+    }),
+    ("limit-without-order", |root| {
+        per_statement(root, |stmt| {
+            with_message(
+                limit_without_order(stmt),
+                "`LIMIT` without `ORDER BY` returns arbitrary rows",
+            )
+        })
+    }),
+    ("line-length", |root| {
+        line_length(root, &LineLengthOptions::default())
+    }),
+    ("natural-joins", |root| {
+        per_statement(root, |stmt| {
+            with_message(
+                natural_joins(stmt),
+                "`NATURAL JOIN` silently changes when the columns of the tables change",
+            )
+        })
+    }),
+    ("non-sargable-predicates", |root| {
+        per_statement(root, |stmt| {
+            non_sargable_predicates(stmt, &SargableOptions::default())
+        })
+    }),
+    ("not-in-subquery", |root| {
+        per_statement(root, not_in_subquery)
+    }),
+    ("or-chains-to-in", |root| {
+        per_statement(root, or_chains_to_in)
+    }),
+    ("order-by-ordinals", |root| {
+        per_statement(root, |stmt| {
+            with_message(
+                order_by_ordinals(stmt),
+                "a column position refers to another column when the select list changes, name the column instead",
+            )
+        })
+    }),
+    ("no-select-star", |root| {
+        per_statement(root, |stmt| {
+            with_message(
+                select_star(stmt),
+                "`*` depends on the columns of the table, list the columns instead",
+            )
+        })
+    }),
+    ("prepared-statement-usage", prepared_statement_usage),
+    ("redundant-semicolons", redundant_semicolons),
+    ("reversed-between", |root| {
+        per_statement(root, reversed_between)
+    }),
+    ("tables-without-pk", |root| {
+        tables_without_pk(root, Default::default())
+    }),
+    ("todo-comments", |root| {
+        todo_comments(root, DEFAULT_TODO_MARKERS)
+    }),
+    ("trailing-whitespace", trailing_whitespace),
+    ("unbounded-range-frames", |root| {
+        per_statement(root, |stmt| {
+            with_message(
+                unbounded_range_frames(stmt),
+                "a frame from `UNBOUNDED PRECEDING` to `UNBOUNDED FOLLOWING` spans the whole partition for every row, which may be expensive",
+            )
+        })
+    }),
+    ("unnecessary-union", |root| {
+        per_statement(root, unnecessary_union)
+    }),
+    ("values-row-lengths", values_row_lengths),
+    ("volatile-generated-columns", |root| {
+        per_statement(root, volatile_generated_columns)
+    }),
+];
+
+/// The built-in lints that only run if a `Config` enables them, because they are meant for
+/// specific files like migrations, enforce a choice of the team, or are heuristics
+const DISABLED_LINTS: &[(&str, Lint)] = &[
+    ("excessive-nesting", |root| {
+        excessive_nesting(root, DEFAULT_MAX_NESTING_DEPTH)
+    }),
+    ("naming-convention", |root| {
+        naming_violations(root, NamingConvention::SnakeCase)
+    }),
+    ("non-idempotent-ddl", |root| {
+        non_idempotent_ddl(root, &IdempotentDdlOptions::default())
+    }),
+    ("statement-policy", |root| {
+        per_statement(root, |stmt| {
+            check_statement_policy(stmt, &StatementPolicy::read_only())
+                .err()
+                .into_iter()
+                .collect()
+        })
+    }),
+];
+
+fn per_statement(
+    root: &SyntaxNode,
+    lint: impl Fn(&SyntaxNode) -> Vec<Diagnostic>,
+) -> Vec<Diagnostic> {
+    statements(root).into_iter().flat_map(lint).collect()
+}
+
+fn with_message(ranges: Vec<TextRange>, message: &str) -> Vec<Diagnostic> {
+    ranges
+        .into_iter()
+        .map(|range| Diagnostic::new(message, range))
+        .collect()
+}
+
+/// The lints that can be run on a source file, keyed by their rule ids
+#[derive(Debug, Clone)]
+pub struct LintRegistry {
+    /// The lints with whether they run if a `Config` does not configure them
+    lints: BTreeMap<String, (Lint, bool)>,
+}
+
+impl LintRegistry {
+    /// Creates a registry without any lints
+    pub fn new() -> Self {
+        Self {
+            lints: BTreeMap::new(),
+        }
+    }
+
+    /// Adds `lint` with the id `rule`, replacing the lint that already has this id
+    pub fn register(&mut self, rule: &str, lint: Lint) {
+        self.lints.insert(rule.to_string(), (lint, true));
+    }
+
+    /// Adds `lint` with the id `rule` like `register`, but only runs it if a `Config` enables it
+    pub fn register_disabled(&mut self, rule: &str, lint: Lint) {
+        self.lints.insert(rule.to_string(), (lint, false));
+    }
+
+    /// Returns the rule ids of all lints, in alphabetical order
+    pub fn rules(&self) -> impl Iterator<Item = &str> {
+        self.lints.keys().map(String::as_str)
+    }
+
+    /// Returns the diagnostics of all lints that `config` enables for the source file `root`,
+    /// ordered by their position
+    ///
+    /// Every diagnostic carries the id of its rule, and the severity that `config` overrides.
+    /// Diagnostics that are suppressed by a `pglsp-disable` comment are skipped.
+    pub fn run(&self, root: &SyntaxNode, config: &Config) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (rule, (lint, by_default)) in &self.lints {
+            if !config.is_enabled(rule, *by_default) {
+                continue;
+            }
+            let rule_config = config.rule(rule);
+            diagnostics.extend(lint(root).into_iter().map(|d| {
+                let severity = rule_config.severity.unwrap_or(d.severity());
+                d.with_rule(rule.as_str()).with_severity(severity)
+            }));
+        }
+        let suppressions = suppressions(root);
+        diagnostics.retain(|d| !suppressions.iter().any(|s| s.suppresses(d)));
+        diagnostics.sort_by_key(|d| d.range().start());
+        diagnostics
+    }
+}
+
+impl Default for LintRegistry {
+    /// Creates a registry with all built-in lints
+    fn default() -> Self {
+        let mut registry = Self::new();
+        for (rule, lint) in LINTS {
+            registry.register(rule, *lint);
+        }
+        for (rule, lint) in DISABLED_LINTS {
+            registry.register_disabled(rule, *lint);
+        }
+        registry
+    }
+}
+
+/// Returns the diagnostics of all built-in lints that `config` enables for the source file
+/// `root`, ordered by their position
+pub fn run_lints(root: &SyntaxNode, config: &Config) -> Vec<Diagnostic> {
+    LintRegistry::default().run(root, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use cstree::text::TextSize;
+
+    fn lint(input: &str, config: &Config) -> Vec<(Option<String>, Severity)> {
+        run_lints(parse(input).root(), config)
+            .iter()
+            .map(|d| (d.rule().map(str::to_string), d.severity()))
+            .collect()
+    }
+
+    #[test]
+    fn test_run_lints() {
+        let input = "select * from t limit 1;;\n";
+        assert_eq!(
+            lint(input, &Config::default()),
+            vec![
+                (Some("no-select-star".to_string()), Severity::Warning),
+                (Some("limit-without-order".to_string()), Severity::Warning),
+                (Some("redundant-semicolons".to_string()), Severity::Warning),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disable_rule() {
+        let input = "select * from t limit 1;;\n";
+        let config = Config::new()
+            .with_rule_enabled("no-select-star", false)
+            .with_rule_severity("redundant-semicolons", Severity::Error);
+        assert_eq!(
+            lint(input, &config),
+            vec![
+                (Some("limit-without-order".to_string()), Severity::Warning),
+                (Some("redundant-semicolons".to_string()), Severity::Error),
+            ]
+        );
+        assert!(
+            lint(input, &config.with_rule_enabled("no-select-star", true))
+                .iter()
+                .any(|(rule, _)| rule.as_deref() == Some("no-select-star"))
+        );
+    }
+
+    #[test]
+    fn test_suppressed_rule() {
+        let input =
+            "-- pglsp-disable-next-line no-select-star\nselect * from t;\nselect * from u;\n";
+        let diagnostics = run_lints(parse(input).root(), &Config::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule(), Some("no-select-star"));
+        assert_eq!(
+            diagnostics[0].range().start(),
+            TextSize::from(input.rfind('*').unwrap() as u32)
+        );
+
+        let input = "-- pglsp-disable-next-line natural-joins\nselect * from t;\n";
+        assert_eq!(lint(input, &Config::default()).len(), 1);
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let input = "drop table t;\n";
+        assert!(lint(input, &Config::default()).is_empty());
+        assert!(lint(
+            input,
+            &Config::new().with_rule_severity("statement-policy", Severity::Warning)
+        )
+        .is_empty());
+        assert_eq!(
+            lint(
+                input,
+                &Config::new().with_rule_enabled("statement-policy", true)
+            ),
+            vec![(Some("statement-policy".to_string()), Severity::Error)]
+        );
+        assert_eq!(
+            lint(
+                input,
+                &Config::new().with_rule_enabled("non-idempotent-ddl", true)
+            ),
+            vec![(Some("non-idempotent-ddl".to_string()), Severity::Warning)]
+        );
+    }
+
+    #[test]
+    fn test_custom_registry() {
+        let mut registry = LintRegistry::new();
+        registry.register("trailing-whitespace", trailing_whitespace);
+        assert_eq!(
+            registry.rules().collect::<Vec<_>>(),
+            vec!["trailing-whitespace"]
+        );
+
+        let parsed = parse("select * from t limit 1; \n");
+        let diagnostics = registry.run(parsed.root(), &Config::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule(), Some("trailing-whitespace"));
+    }
+}
@@ -0,0 +1,102 @@
+use cstree::text::TextRange;
+use pg_query::protobuf::{SelectStmt, SubLinkType};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{location_to_offset, name_token_at, parse_pg_query_stmt, token_at};
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the ranges of all `*` and `table.*` in the target lists of selects in `stmt`
+///
+/// The columns of a `*` change when the table changes, which breaks code that depends on their
+/// number or order. The select of an `EXISTS` is not flagged, since its columns are never read.
+pub fn select_star(stmt: &SyntaxNode) -> Vec<TextRange> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+    let nodes = get_nodes(ast.to_ref());
+
+    let exists_selects = nodes
+        .iter()
+        .filter_map(|(node, _)| match node {
+            NodeRef::SubLink(sub_link)
+                if sub_link.sub_link_type == SubLinkType::ExistsSublink as i32 =>
+            {
+                match sub_link.subselect.as_ref()?.node.as_ref()? {
+                    NodeEnum::SelectStmt(select) => Some(&**select as *const SelectStmt),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut ranges = nodes
+        .iter()
+        .filter_map(|(node, _)| match node {
+            NodeRef::SelectStmt(select)
+                if !exists_selects.contains(&(*select as *const SelectStmt)) =>
+            {
+                Some(select)
+            }
+            _ => None,
+        })
+        .flat_map(|select| &select.target_list)
+        .filter_map(|target| match target.node.as_ref()? {
+            NodeEnum::ResTarget(target) => match target.val.as_ref()?.node.as_ref()? {
+                NodeEnum::ColumnRef(column)
+                    if matches!(
+                        column.fields.last().and_then(|f| f.node.as_ref()),
+                        Some(NodeEnum::AStar(_))
+                    ) =>
+                {
+                    Some(column)
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter_map(|column| {
+            let first = token_at(stmt, column.location)?;
+            let star = location_to_offset(stmt, column.location)
+                .and_then(|offset| name_token_at(stmt, offset))?;
+            Some(first.text_range().cover(star.text_range()))
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|r| r.start());
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<&str> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        select_star(&parsed.cst)
+            .into_iter()
+            .map(|r| &input[r])
+            .collect()
+    }
+
+    #[test]
+    fn test_select_star() {
+        assert_eq!(
+            lint("select *, u.* from users u where id in (select * from admins);"),
+            vec!["*", "u.*", "*"]
+        );
+    }
+
+    #[test]
+    fn test_select_columns() {
+        assert!(lint("select id, count(*) from users group by id;").is_empty());
+        assert!(lint(
+            "select id from users u where exists (select * from admins a where a.id = u.id);"
+        )
+        .is_empty());
+    }
+}
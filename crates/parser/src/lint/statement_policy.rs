@@ -0,0 +1,160 @@
+use crate::ast::{parse_pg_query_stmt, statement_category, StatementCategory};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::syntax_kind::{syntax_kind_name, SyntaxKind};
+use crate::syntax_node::SyntaxNode;
+
+/// Configures which statements `check_statement_policy` denies
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatementPolicy {
+    /// The categories of statements that are denied, e.g. `StatementCategory::Ddl`
+    pub denied_categories: Vec<StatementCategory>,
+    /// The kinds of statements that are denied, e.g. `SyntaxKind::DropStmt`
+    pub denied_kinds: Vec<SyntaxKind>,
+}
+
+impl StatementPolicy {
+    /// A policy for read-only connections, which denies DML, DDL and DCL statements
+    pub fn read_only() -> Self {
+        Self {
+            denied_categories: vec![
+                StatementCategory::Dml,
+                StatementCategory::Ddl,
+                StatementCategory::Dcl,
+            ],
+            denied_kinds: Vec::new(),
+        }
+    }
+}
+
+impl StatementPolicy {
+    /// Whether the policy denies any statements
+    fn is_restrictive(&self) -> bool {
+        !self.denied_categories.is_empty() || !self.denied_kinds.is_empty()
+    }
+}
+
+/// Returns an error if the statement node `stmt` is denied by `policy`
+///
+/// A statement that cannot be parsed, e.g. because of newer syntax like `MERGE`, cannot be
+/// classified, so it is denied by every policy that denies any statements.
+pub fn check_statement_policy(
+    stmt: &SyntaxNode,
+    policy: &StatementPolicy,
+) -> Result<(), Diagnostic> {
+    if stmt.kind() == SyntaxKind::EmptyStatement {
+        return Ok(());
+    }
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None if policy.is_restrictive() => {
+            return Err(Diagnostic::new(
+                "statements that cannot be parsed are not allowed",
+                stmt.text_range(),
+            )
+            .with_severity(Severity::Error))
+        }
+        None => return Ok(()),
+    };
+
+    let kind = SyntaxKind::from_pg_query_node(&ast.to_ref());
+    let message = if policy.denied_kinds.contains(&kind) {
+        let keyword = keyword(stmt).unwrap_or_else(|| syntax_kind_name(kind).to_string());
+        format!("`{}` statements are not allowed", keyword)
+    } else {
+        match statement_category(stmt) {
+            Some(category) if policy.denied_categories.contains(&category) => {
+                format!("{} statements are not allowed", category.name())
+            }
+            _ => return Ok(()),
+        }
+    };
+    Err(Diagnostic::new(message, stmt.text_range()).with_severity(Severity::Error))
+}
+
+/// Returns the uppercase keyword that starts the statement, e.g. `DROP`
+fn keyword(stmt: &SyntaxNode) -> Option<String> {
+    let token = stmt
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .find(|t| !t.kind().is_trivia())?;
+    let text = stmt.try_resolved()?.text().to_string();
+    Some(text[token.text_range() - stmt.text_range().start()].to_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn check(input: &str, policy: &StatementPolicy) -> Result<(), String> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        check_statement_policy(&parsed.cst, policy).map_err(|d| d.message().to_string())
+    }
+
+    #[test]
+    fn test_deny_ddl() {
+        let policy = StatementPolicy {
+            denied_categories: vec![StatementCategory::Ddl],
+            denied_kinds: Vec::new(),
+        };
+
+        assert_eq!(
+            check("create table users (id int);", &policy),
+            Err("DDL statements are not allowed".to_string())
+        );
+        assert_eq!(check("select * from users;", &policy), Ok(()));
+    }
+
+    #[test]
+    fn test_deny_kind() {
+        let policy = StatementPolicy {
+            denied_categories: Vec::new(),
+            denied_kinds: vec![SyntaxKind::DropStmt],
+        };
+
+        assert_eq!(
+            check("drop table users;", &policy),
+            Err("`DROP` statements are not allowed".to_string())
+        );
+        assert_eq!(check("create table users (id int);", &policy), Ok(()));
+        assert!(check(
+            "insert into users values (1);",
+            &StatementPolicy::read_only()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_read_only_denies_hidden_writes() {
+        let policy = StatementPolicy::read_only();
+
+        assert_eq!(
+            check(
+                "with d as (delete from t returning *) select * from d;",
+                &policy
+            ),
+            Err("DML statements are not allowed".to_string())
+        );
+        assert_eq!(
+            check("explain analyze delete from t;", &policy),
+            Err("DML statements are not allowed".to_string())
+        );
+        assert_eq!(
+            check(
+                "merge into t using u on t.id = u.id when matched then delete;",
+                &policy
+            ),
+            Err("statements that cannot be parsed are not allowed".to_string())
+        );
+        assert_eq!(check("explain delete from t;", &policy), Ok(()));
+        assert_eq!(
+            check(
+                "merge into t using u on t.id = u.id when matched then delete;",
+                &StatementPolicy::default()
+            ),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Comments that suppress the diagnostics of lints, e.g. `-- pglsp-disable-next-line natural-joins`.
+//!
+//! A directive lists the rule ids it applies to, separated by commas or whitespace, and applies to
+//! all rules if it lists none:
+//!
+//! - `pglsp-disable-line` suppresses diagnostics that start on the line of the comment
+//! - `pglsp-disable-next-line` suppresses diagnostics that start on the next line, or within the
+//!   statement that starts on it
+//! - `pglsp-disable` suppresses diagnostics until a `pglsp-enable` that lists one of its rules or
+//!   none, or until the end of the file
+
+use cstree::text::{TextRange, TextSize};
+
+use crate::ast::statements;
+use crate::diagnostic::Diagnostic;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// A range of a source file in which the diagnostics of some rules are suppressed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Suppression {
+    /// The suppressed rule ids, or all rules if empty
+    rules: Vec<String>,
+    range: TextRange,
+}
+
+impl Suppression {
+    /// Whether the diagnostic `diagnostic` of a lint is suppressed
+    pub(crate) fn suppresses(&self, diagnostic: &Diagnostic) -> bool {
+        let rule_matches = match diagnostic.rule() {
+            Some(rule) => self.rules.is_empty() || self.rules.iter().any(|r| r == rule),
+            None => false,
+        };
+        rule_matches && self.range.contains_inclusive(diagnostic.range().start())
+    }
+}
+
+enum Directive {
+    DisableLine,
+    DisableNextLine,
+    Disable,
+    Enable,
+}
+
+/// Returns the suppressions of the directive comments of the source file `root`
+pub(crate) fn suppressions(root: &SyntaxNode) -> Vec<Suppression> {
+    let tokens = root
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .collect::<Vec<_>>();
+    let line_end = |idx: usize| {
+        tokens[idx..]
+            .iter()
+            .find(|t| t.kind() == SyntaxKind::Newline)
+            .copied()
+    };
+
+    let mut suppressions = Vec::new();
+    // the rules and the start of every `pglsp-disable` that is not enabled again
+    let mut blocks: Vec<(Vec<String>, TextSize)> = Vec::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        if token.kind() != SyntaxKind::Comment {
+            continue;
+        }
+        let (directive, rules) = match directive(token) {
+            Some(directive) => directive,
+            None => continue,
+        };
+        match directive {
+            Directive::DisableLine => {
+                let start = tokens[..idx]
+                    .iter()
+                    .rfind(|t| t.kind() == SyntaxKind::Newline)
+                    .map_or(root.text_range().start(), |t| t.text_range().end());
+                let end = line_end(idx).map_or(root.text_range().end(), |t| t.text_range().start());
+                suppressions.push(Suppression {
+                    rules,
+                    range: TextRange::new(start, end),
+                });
+            }
+            Directive::DisableNextLine => {
+                let newline = match line_end(idx) {
+                    Some(newline) => newline,
+                    None => continue,
+                };
+                let start = newline.text_range().end();
+                let next = tokens
+                    .iter()
+                    .position(|t| t.text_range().start() == start)
+                    .and_then(line_end);
+                let mut range = TextRange::new(
+                    start,
+                    next.map_or(root.text_range().end(), |t| t.text_range().start()),
+                );
+                if let Some(stmt) = statements(root)
+                    .into_iter()
+                    .find(|s| range.contains(s.text_range().start()))
+                {
+                    range = range.cover(stmt.text_range());
+                }
+                suppressions.push(Suppression { rules, range });
+            }
+            Directive::Disable => blocks.push((rules, token.text_range().end())),
+            Directive::Enable => blocks.retain(|(disabled, start)| {
+                let ends = rules.is_empty() || disabled.iter().any(|r| rules.contains(r));
+                if ends {
+                    suppressions.push(Suppression {
+                        rules: disabled.clone(),
+                        range: TextRange::new(*start, token.text_range().start()),
+                    });
+                }
+                !ends
+            }),
+        }
+    }
+    suppressions.extend(blocks.into_iter().map(|(rules, start)| Suppression {
+        rules,
+        range: TextRange::new(start, root.text_range().end()),
+    }));
+    suppressions
+}
+
+/// Returns the directive of the comment `token` and the rule ids it lists
+fn directive(token: &SyntaxToken) -> Option<(Directive, Vec<String>)> {
+    let text = token.resolved().text();
+    let text = match text.strip_prefix("--") {
+        Some(text) => text,
+        None => text.strip_prefix("/*")?.strip_suffix("*/")?,
+    };
+    let mut words = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty());
+    let directive = match words.next()? {
+        "pglsp-disable-line" => Directive::DisableLine,
+        "pglsp-disable-next-line" => Directive::DisableNextLine,
+        "pglsp-disable" => Directive::Disable,
+        "pglsp-enable" => Directive::Enable,
+        _ => return None,
+    };
+    Some((directive, words.map(str::to_string).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn suppressed(input: &str) -> Vec<(Vec<String>, &str)> {
+        suppressions(parse(input).root())
+            .into_iter()
+            .map(|s| (s.rules, &input[s.range]))
+            .collect()
+    }
+
+    #[test]
+    fn test_suppressions() {
+        let input = "select 1; -- pglsp-disable-line no-select-star, natural-joins\n-- pglsp-disable-next-line\nselect *\nfrom t;\n/* pglsp-disable limit-without-order */\nselect 2;\n-- pglsp-enable\n-- a comment\n";
+        assert_eq!(
+            suppressed(input),
+            vec![
+                (
+                    vec!["no-select-star".to_string(), "natural-joins".to_string()],
+                    "select 1; -- pglsp-disable-line no-select-star, natural-joins"
+                ),
+                (Vec::new(), "select *\nfrom t;"),
+                (vec!["limit-without-order".to_string()], "\nselect 2;\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_block() {
+        let input = "-- pglsp-disable\nselect 1;";
+        assert_eq!(suppressed(input), vec![(Vec::new(), "\nselect 1;")]);
+    }
+}
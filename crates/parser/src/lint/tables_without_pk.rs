@@ -0,0 +1,103 @@
+use pg_query::NodeEnum;
+
+use crate::ast::{
+    location_to_offset, name_token_at, parse_pg_query_stmt, statements, table_constraints,
+    ConstraintKind,
+};
+use crate::diagnostic::Diagnostic;
+use crate::syntax_node::SyntaxNode;
+
+/// Configures which tables `tables_without_pk` exempts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PrimaryKeyExemptions {
+    /// Exempt partitions, i.e. `CREATE TABLE ... PARTITION OF`
+    pub partitions: bool,
+    /// Exempt temporary tables
+    pub temporary_tables: bool,
+}
+
+impl Default for PrimaryKeyExemptions {
+    fn default() -> Self {
+        Self {
+            partitions: true,
+            temporary_tables: true,
+        }
+    }
+}
+
+/// Returns a diagnostic at the table name of every `CREATE TABLE` statement in `root` that does not
+/// define a primary key, unless the table is exempted by `exemptions`
+pub fn tables_without_pk(root: &SyntaxNode, exemptions: PrimaryKeyExemptions) -> Vec<Diagnostic> {
+    statements(root)
+        .into_iter()
+        .filter_map(|stmt| {
+            let create = match parse_pg_query_stmt(stmt)? {
+                NodeEnum::CreateStmt(create) => create,
+                _ => return None,
+            };
+            let relation = create.relation.as_ref()?;
+
+            if (exemptions.partitions && create.partbound.is_some())
+                || (exemptions.temporary_tables && relation.relpersistence == "t")
+            {
+                return None;
+            }
+            if table_constraints(stmt)
+                .iter()
+                .any(|c| c.kind == ConstraintKind::PrimaryKey)
+            {
+                return None;
+            }
+
+            let token = name_token_at(stmt, location_to_offset(stmt, relation.location)?)?;
+            Some(Diagnostic::new(
+                format!("table `{}` has no primary key", relation.relname),
+                token.text_range(),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn lint(input: &str, exemptions: PrimaryKeyExemptions) -> Vec<String> {
+        let result = parse(input);
+        tables_without_pk(result.root(), exemptions)
+            .iter()
+            .map(|d| format!("{} {}", &input[d.range()], d))
+            .collect()
+    }
+
+    #[test]
+    fn test_tables_without_pk() {
+        let input = "create table public.logs (message text);\ncreate table users (id int primary key);\ncreate table roles (id int, primary key (id));";
+
+        assert_eq!(
+            lint(input, PrimaryKeyExemptions::default()),
+            vec!["logs table `logs` has no primary key"]
+        );
+    }
+
+    #[test]
+    fn test_exemptions() {
+        let input = "create temporary table scratch (id int);\ncreate table logs_2023 partition of logs for values in (2023);";
+
+        assert!(lint(input, PrimaryKeyExemptions::default()).is_empty());
+        assert_eq!(
+            lint(
+                input,
+                PrimaryKeyExemptions {
+                    partitions: false,
+                    temporary_tables: false,
+                }
+            ),
+            vec![
+                "scratch table `scratch` has no primary key",
+                "logs_2023 table `logs_2023` has no primary key",
+            ]
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use cstree::text::{TextRange, TextSize};
+
+use crate::comments::comments;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::syntax_node::SyntaxNode;
+
+/// The markers `todo_comments` looks for by default
+pub const DEFAULT_TODO_MARKERS: &[&str] = &["TODO", "FIXME", "HACK"];
+
+/// Returns an info for every occurrence of one of `markers` in the comments of the source file
+/// `root`, e.g. `TODO` in `-- TODO: fix this`
+///
+/// Markers are case-sensitive and only match whole words, so `TODOS` is not a marker. The
+/// diagnostic covers the marker, and its message is the rest of the line from the marker on.
+pub fn todo_comments(root: &SyntaxNode, markers: &[&str]) -> Vec<Diagnostic> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut diagnostics = Vec::new();
+    for comment in comments(root) {
+        for marker in markers.iter().filter(|m| !m.is_empty()) {
+            for (idx, _) in comment.text.match_indices(marker) {
+                let end = idx + marker.len();
+                if comment.text[..idx].ends_with(is_word)
+                    || comment.text[end..].starts_with(is_word)
+                {
+                    continue;
+                }
+                let line = comment.text[idx..]
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim_end()
+                    .trim_end_matches("*/")
+                    .trim_end();
+                let start = comment.range.start() + TextSize::from(idx as u32);
+                diagnostics.push(
+                    Diagnostic::new(line, TextRange::at(start, TextSize::of(*marker)))
+                        .with_severity(Severity::Info),
+                );
+            }
+        }
+    }
+    diagnostics.sort_by_key(|d| d.range().start());
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn lint(input: &str) -> Vec<(String, &str)> {
+        todo_comments(parse(input).root(), DEFAULT_TODO_MARKERS)
+            .iter()
+            .map(|d| (d.message().to_string(), &input[d.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_todo_comments() {
+        assert_eq!(
+            lint("-- TODO: fix this\nselect 1; /* FIXME use an index */\n"),
+            vec![
+                ("TODO: fix this".to_string(), "TODO"),
+                ("FIXME use an index".to_string(), "FIXME"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comments_without_markers() {
+        assert!(lint("-- list all TODOS\nselect 'TODO' from t; -- todo\n").is_empty());
+    }
+}
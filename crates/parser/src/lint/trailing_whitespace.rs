@@ -0,0 +1,79 @@
+use cstree::text::TextRange;
+
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::fix::{Fix, TextEdit};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns a diagnostic for all whitespace at the end of a line in `root`
+///
+/// Only whitespace and tab tokens are considered, so whitespace within string literals and
+/// comments is ignored. The fix of the diagnostic removes the whitespace.
+pub fn trailing_whitespace(root: &SyntaxNode) -> Vec<Diagnostic> {
+    let mut ranges = Vec::new();
+    let mut run: Option<TextRange> = None;
+    for token in root
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+    {
+        match token.kind() {
+            SyntaxKind::Whitespace | SyntaxKind::Tab => {
+                run = Some(run.map_or(token.text_range(), |r| r.cover(token.text_range())));
+            }
+            SyntaxKind::Newline => ranges.extend(run.take()),
+            _ => run = None,
+        }
+    }
+    ranges
+        .into_iter()
+        .map(|range| {
+            Diagnostic::new("trailing whitespace", range)
+                .with_severity(Severity::Hint)
+                .with_fix(Fix::new(
+                    "Remove trailing whitespace",
+                    vec![TextEdit::delete(range)],
+                ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fix::apply_fixes;
+    use crate::parser::parse;
+
+    fn lint(input: &str) -> Vec<&str> {
+        trailing_whitespace(parse(input).root())
+            .iter()
+            .map(|d| &input[d.range()])
+            .collect()
+    }
+
+    #[test]
+    fn test_trailing_whitespace() {
+        assert_eq!(
+            lint("select id, \t\n  name\nfrom users;  \nselect 'a  \nb';"),
+            vec![" \t", "  "]
+        );
+    }
+
+    #[test]
+    fn test_clean_lines() {
+        assert!(lint("select id,\n  name\nfrom users;\n").is_empty());
+    }
+
+    #[test]
+    fn test_remove_trailing_whitespace() {
+        let input = "select id, \t\n  name  \nfrom users;   \n";
+        let fixes = trailing_whitespace(parse(input).root())
+            .iter()
+            .filter_map(|d| d.fix().cloned())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            apply_fixes(input, &fixes),
+            "select id,\n  name\nfrom users;\n"
+        );
+    }
+}
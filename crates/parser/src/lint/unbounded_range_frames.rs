@@ -0,0 +1,49 @@
+use cstree::text::TextRange;
+
+use crate::ast::{window_frames, FrameBound};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the ranges of all frames in `stmt` that span the whole partition, e.g.
+/// `RANGE BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING`
+///
+/// Every row of such a window sees all rows of its partition, which may be expensive for large
+/// partitions, and is usually better written as an aggregate over `PARTITION BY` without
+/// `ORDER BY`.
+pub fn unbounded_range_frames(stmt: &SyntaxNode) -> Vec<TextRange> {
+    window_frames(stmt)
+        .into_iter()
+        .filter(|f| {
+            f.explicit
+                && f.start == FrameBound::UnboundedPreceding
+                && f.end == FrameBound::UnboundedFollowing
+        })
+        .map(|f| f.range)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<&str> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        unbounded_range_frames(&parser.finish().cst)
+            .into_iter()
+            .map(|r| &input[r])
+            .collect()
+    }
+
+    #[test]
+    fn test_unbounded_range_frames() {
+        assert_eq!(
+            lint("select last_value(x) over (order by id range between unbounded preceding and unbounded following) from t;"),
+            vec!["range between unbounded preceding and unbounded following"]
+        );
+        assert!(lint(
+            "select sum(x) over (order by id rows between unbounded preceding and current row), rank() over (order by id) from t;"
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+use cstree::text::TextRange;
+
+use crate::ast::{all_set_operations, SetOp, SetOpKind, SetOperand};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::fix::{Fix, TextEdit};
+use crate::format::{original_case, Case};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns a hint at every `UNION` without `ALL` of the set operations of `stmt`, including the
+/// ones of subqueries, common table expressions and the query of an `INSERT`
+///
+/// `UNION` removes duplicate rows, which sorts or hashes the rows of both queries. This is a
+/// heuristic, since removing duplicates may be intended, and an explicit `UNION DISTINCT` is not
+/// flagged. The fix of the diagnostic adds `ALL`, in the case of the `UNION` keyword.
+pub fn unnecessary_union(stmt: &SyntaxNode) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for op in all_set_operations(stmt) {
+        collect_unions(stmt, &op, &mut diagnostics);
+    }
+    diagnostics
+}
+
+fn collect_unions(stmt: &SyntaxNode, op: &SetOp, diagnostics: &mut Vec<Diagnostic>) {
+    if let SetOperand::SetOp(left) = op.left.as_ref() {
+        collect_unions(stmt, left, diagnostics);
+    }
+
+    let keyword =
+        stmt.resolved().text().to_string()[op.operator - stmt.text_range().start()].to_string();
+    // the operator is the `UNION` keyword only, without `ALL` or `DISTINCT`
+    if op.kind == SetOpKind::Union && !op.all && !keyword.contains(char::is_whitespace) {
+        let all = match original_case(&keyword) {
+            Case::Lower => " all",
+            _ => " ALL",
+        };
+        diagnostics.push(
+            Diagnostic::new(
+                "`UNION` removes duplicate rows, use `UNION ALL` if the queries cannot return duplicates or they are wanted",
+                op.operator,
+            )
+            .with_severity(Severity::Info)
+            .with_fix(Fix::new(
+                "Replace with `UNION ALL`",
+                vec![TextEdit::replace(
+                    TextRange::empty(op.operator.end()),
+                    all,
+                )],
+            )),
+        );
+    }
+
+    if let SetOperand::SetOp(right) = op.right.as_ref() {
+        collect_unions(stmt, right, diagnostics);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fix::apply_fixes;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<Diagnostic> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        unnecessary_union(&parser.finish().cst)
+    }
+
+    #[test]
+    fn test_unnecessary_union() {
+        let input = "select a from t union select a from u UNION select a from v;";
+        let diagnostics = lint(input);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (&input[d.range()], d.severity()))
+                .collect::<Vec<_>>(),
+            vec![("union", Severity::Info), ("UNION", Severity::Info)]
+        );
+        let fixes = diagnostics
+            .iter()
+            .filter_map(|d| d.fix().cloned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            apply_fixes(input, &fixes),
+            "select a from t union all select a from u UNION ALL select a from v;"
+        );
+    }
+
+    #[test]
+    fn test_union_all() {
+        assert!(lint("select a from t union all select a from u;").is_empty());
+        assert!(lint("select a from t union distinct select a from u;").is_empty());
+        assert!(lint("select a from t except select a from u;").is_empty());
+    }
+
+    #[test]
+    fn test_nested_unions() {
+        let input = "with x as (select a from t union select a from u) insert into v select * from (select a from x union select 1) s;";
+        let diagnostics = lint(input);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| d.range().start())
+                .collect::<Vec<_>>(),
+            input
+                .match_indices("union")
+                .map(|(idx, _)| (idx as u32).into())
+                .collect::<Vec<_>>()
+        );
+    }
+}
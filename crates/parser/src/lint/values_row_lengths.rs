@@ -0,0 +1,57 @@
+use crate::ast::{statements, values_row_extents};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns an error for every row of a `VALUES` list in `root` whose number of values differs
+/// from the first row of its list
+///
+/// This covers standalone `VALUES` statements and `INSERT ... VALUES`. The values are counted in
+/// the ast of the statement. The diagnostic covers the values of the row.
+pub fn values_row_lengths(root: &SyntaxNode) -> Vec<Diagnostic> {
+    statements(root)
+        .into_iter()
+        .flat_map(|stmt| {
+            let rows = values_row_extents(stmt).unwrap_or_default();
+            let expected = rows.first().map_or(0, |(len, _)| *len);
+            rows.into_iter()
+                .filter(move |(len, _)| *len != expected)
+                .filter_map(move |(len, range)| {
+                    Some(
+                        Diagnostic::new(
+                            format!(
+                                "VALUES lists must all be the same length, expected {} values, found {}",
+                                expected, len
+                            ),
+                            range?,
+                        )
+                        .with_severity(Severity::Error),
+                    )
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn lint(input: &str) -> Vec<&str> {
+        values_row_lengths(parse(input).root())
+            .iter()
+            .map(|d| &input[d.range()])
+            .collect()
+    }
+
+    #[test]
+    fn test_values_row_lengths() {
+        assert_eq!(lint("values (1, 2), (3), (4, 5);"), vec!["3"]);
+        assert_eq!(lint("insert into t values (1), (2, 3);"), vec!["2, 3"]);
+        assert_eq!(lint("values (1), (2);\nvalues (1, 2), (3);"), vec!["3"]);
+    }
+
+    #[test]
+    fn test_equal_row_lengths() {
+        assert!(lint("values (1, 2), (3, 4);").is_empty());
+    }
+}
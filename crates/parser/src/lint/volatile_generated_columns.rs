@@ -0,0 +1,93 @@
+use crate::ast::{generated_columns, GeneratedKind};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::syntax_node::SyntaxNode;
+
+/// Built-in functions that are not immutable, because they depend on the time, on the session or
+/// on randomness
+const NON_IMMUTABLE_FUNCTIONS: &[&str] = &[
+    "clock_timestamp",
+    "current_catalog",
+    "current_date",
+    "current_role",
+    "current_schema",
+    "current_time",
+    "current_timestamp",
+    "current_user",
+    "currval",
+    "gen_random_uuid",
+    "lastval",
+    "localtime",
+    "localtimestamp",
+    "nextval",
+    "now",
+    "pg_backend_pid",
+    "random",
+    "session_user",
+    "setseed",
+    "statement_timestamp",
+    "timeofday",
+    "transaction_timestamp",
+    "txid_current",
+    "user",
+    "uuid_generate_v1",
+    "uuid_generate_v4",
+];
+
+/// Returns an error for every function call in the expression of a generated column of `stmt`
+/// that is not immutable, e.g. `random()`
+///
+/// The value of a generated column must only depend on the other columns of its row, and
+/// Postgres rejects such expressions. `DEFAULT` expressions are computed once on insert and may
+/// use any function, e.g. `DEFAULT now()`. The diagnostic covers the expression.
+pub fn volatile_generated_columns(stmt: &SyntaxNode) -> Vec<Diagnostic> {
+    generated_columns(stmt)
+        .iter()
+        .filter(|c| c.kind == GeneratedKind::Generated)
+        .flat_map(|c| {
+            c.functions
+                .iter()
+                .filter(|f| NON_IMMUTABLE_FUNCTIONS.contains(&f.as_str()))
+                .map(|f| {
+                    Diagnostic::new(
+                        format!(
+                            "`{}` is not immutable, so it cannot be used in the generated column `{}`",
+                            f, c.column
+                        ),
+                        c.range,
+                    )
+                    .with_severity(Severity::Error)
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn lint(input: &str) -> Vec<(String, &str)> {
+        let mut parser = Parser::new();
+        parser.parse_statement(input, None);
+        volatile_generated_columns(&parser.finish().cst)
+            .iter()
+            .map(|d| (d.message().to_string(), &input[d.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn test_volatile_generated_columns() {
+        assert_eq!(
+            lint("create table t (a int, r float generated always as (a * random()) stored);"),
+            vec![(
+                "`random` is not immutable, so it cannot be used in the generated column `r`"
+                    .to_string(),
+                "a * random()"
+            )]
+        );
+        assert!(lint(
+            "create table t (a int default random(), created_at timestamptz default now(), b int generated always as (abs(a)) stored);"
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+//! Conversions into the types of the `lsp-types` crate, so that a language server can return the
+//! results of the parser directly.
+//!
+//! This module is only available with the `lsp` feature.
+
+use cstree::text::TextRange;
+
+use crate::line_index::LineIndex;
+use crate::symbols::{document_symbols, DocumentSymbol, SymbolKind};
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the outline of the source file `root` as the symbols of the language server protocol
+///
+/// See `document_symbols` for the symbols. Positions are converted to lines and UTF-16 columns of
+/// the text of `root`.
+pub fn to_lsp_document_symbols(root: &SyntaxNode) -> Vec<lsp_types::DocumentSymbol> {
+    let index = LineIndex::new(&root.resolved().text().to_string());
+    document_symbols(root)
+        .iter()
+        .map(|s| to_lsp_symbol(&index, s))
+        .collect()
+}
+
+fn to_lsp_symbol(index: &LineIndex, symbol: &DocumentSymbol) -> lsp_types::DocumentSymbol {
+    #[allow(deprecated)]
+    lsp_types::DocumentSymbol {
+        name: symbol.name.clone(),
+        detail: None,
+        kind: to_lsp_symbol_kind(symbol.kind),
+        tags: None,
+        deprecated: None,
+        range: to_lsp_range(index, symbol.range),
+        selection_range: to_lsp_range(index, symbol.selection_range),
+        children: Some(
+            symbol
+                .children
+                .iter()
+                .map(|c| to_lsp_symbol(index, c))
+                .collect(),
+        ),
+    }
+}
+
+fn to_lsp_symbol_kind(kind: SymbolKind) -> lsp_types::SymbolKind {
+    match kind {
+        SymbolKind::Statement => lsp_types::SymbolKind::OBJECT,
+        SymbolKind::Table => lsp_types::SymbolKind::STRUCT,
+        SymbolKind::View => lsp_types::SymbolKind::INTERFACE,
+        SymbolKind::Index => lsp_types::SymbolKind::KEY,
+        SymbolKind::Function => lsp_types::SymbolKind::FUNCTION,
+        SymbolKind::Column => lsp_types::SymbolKind::FIELD,
+        SymbolKind::CommonTableExpr => lsp_types::SymbolKind::VARIABLE,
+    }
+}
+
+fn to_lsp_range(index: &LineIndex, range: TextRange) -> lsp_types::Range {
+    let position = |offset| {
+        let (line, character) = index.line_col_utf16(offset);
+        lsp_types::Position::new(line, character)
+    };
+    lsp_types::Range::new(position(range.start()), position(range.end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use lsp_types::{Position, Range};
+
+    #[test]
+    fn test_create_table_symbols() {
+        let input = "select 1;\ncreate table contact (\n    id int,\n    email text\n);";
+        let symbols = to_lsp_document_symbols(parse(input).root());
+
+        assert_eq!(symbols.len(), 2);
+        let table = &symbols[1];
+        assert_eq!(table.name, "CREATE TABLE contact");
+        assert_eq!(table.kind, lsp_types::SymbolKind::STRUCT);
+        assert_eq!(
+            table.range,
+            Range::new(Position::new(1, 0), Position::new(4, 1))
+        );
+        assert_eq!(
+            table.selection_range,
+            Range::new(Position::new(1, 13), Position::new(1, 20))
+        );
+
+        let columns = table
+            .children
+            .iter()
+            .flatten()
+            .map(|c| (c.name.as_str(), c.kind, c.selection_range))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                (
+                    "id",
+                    lsp_types::SymbolKind::FIELD,
+                    Range::new(Position::new(2, 4), Position::new(2, 6))
+                ),
+                (
+                    "email",
+                    lsp_types::SymbolKind::FIELD,
+                    Range::new(Position::new(3, 4), Position::new(3, 9))
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Metrics about the complexity of a source file, e.g. for dashboards and CI gates.
+
+use std::collections::BTreeMap;
+
+use crate::ast::{from_items, selects_with_parent, statements, subqueries, FromItemKind};
+use crate::cst_utils::nesting_depth;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Counts of the statements and of complex constructs of a source file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// The number of statements of every kind, e.g. `SyntaxKind::SelectStmt`. Statements with
+    /// syntax errors are counted as `SyntaxKind::Stmt` or `SyntaxKind::Error`.
+    pub statements: BTreeMap<SyntaxKind, usize>,
+    /// The number of explicit joins, e.g. two for `a join b on ... join c on ...`
+    pub joins: usize,
+    /// The maximum nesting depth of the queries of any statement, i.e. the `nesting_depth` that
+    /// the excessive-nesting lint checks. Parentheses of expressions do not count.
+    pub max_nesting_depth: u32,
+    /// The number of subqueries, including nested ones
+    pub subqueries: usize,
+}
+
+/// Returns the metrics of the source file `root`
+///
+/// Joins and subqueries are only counted in statements without syntax errors.
+pub fn statement_metrics(root: &SyntaxNode) -> Metrics {
+    let mut metrics = Metrics::default();
+    for stmt in statements(root) {
+        if stmt.kind() == SyntaxKind::EmptyStatement {
+            continue;
+        }
+        *metrics.statements.entry(stmt.kind()).or_default() += 1;
+        metrics.joins += selects_with_parent(stmt)
+            .iter()
+            .flat_map(|(select, _)| from_items(select))
+            .filter(|item| item.kind == FromItemKind::Join)
+            .map(|item| item.relations().len().saturating_sub(1))
+            .sum::<usize>();
+        metrics.max_nesting_depth = metrics.max_nesting_depth.max(nesting_depth(stmt));
+        metrics.subqueries += subqueries(stmt).len();
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_statement_metrics() {
+        let input = "select * from a join b on a.id = b.id left join c using (id);
+select * from (select id from d where id in (select id from e)) s, f;;
+insert into g values (1);
+selct 1;";
+
+        let metrics = statement_metrics(parse(input).root());
+
+        assert_eq!(
+            metrics.statements,
+            BTreeMap::from([
+                (SyntaxKind::Error, 1),
+                (SyntaxKind::SelectStmt, 2),
+                (SyntaxKind::InsertStmt, 1),
+            ])
+        );
+        assert_eq!(metrics.joins, 2);
+        assert_eq!(metrics.subqueries, 2);
+        assert_eq!(metrics.max_nesting_depth, 2);
+    }
+
+    #[test]
+    fn test_nesting_depth_ignores_parentheses() {
+        let input = "select count(*), coalesce((a + 1) * 2, 0) from t where b in (1, 2);";
+
+        let metrics = statement_metrics(parse(input).root());
+
+        assert_eq!(metrics.max_nesting_depth, 0);
+        assert_eq!(metrics.subqueries, 0);
+    }
+}
@@ -0,0 +1,206 @@
+//! Summaries of migration files, e.g. for deployment tooling.
+
+use std::collections::BTreeMap;
+
+use pg_query::protobuf::{Node, ObjectType, RangeVar, VariableSetKind};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{parse_pg_query_stmt, statements};
+use crate::pg_query_utils::get_nodes;
+use crate::schema::{QualifiedName, DEFAULT_SCHEMA};
+use crate::syntax_node::SyntaxNode;
+
+/// The kind of operation that a statement performs on a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StatementKind {
+    Create,
+    Alter,
+    Rename,
+    Drop,
+    Truncate,
+    CreateIndex,
+    Insert,
+    Update,
+    Delete,
+    Copy,
+    /// The table is read, e.g. by a select or the query of an insert
+    Select,
+}
+
+/// Returns the tables that the statements of the source file `root` touch, with the kinds of
+/// operations on every table in the order in which they first occur
+///
+/// All names are qualified with their schema. Unqualified names are resolved against the first
+/// schema of the latest `SET search_path` before the statement, or `DEFAULT_SCHEMA` if there is
+/// none. Common table expressions are not tables, and statements with syntax errors are skipped.
+pub fn affected_tables(root: &SyntaxNode) -> BTreeMap<QualifiedName, Vec<StatementKind>> {
+    let mut tables: BTreeMap<QualifiedName, Vec<StatementKind>> = BTreeMap::new();
+    let mut schema = DEFAULT_SCHEMA.to_string();
+
+    for stmt in statements(root) {
+        let ast = match parse_pg_query_stmt(stmt) {
+            Some(ast) => ast,
+            None => continue,
+        };
+        if let NodeEnum::VariableSetStmt(set) = &ast {
+            if set.name == "search_path" {
+                schema = match VariableSetKind::from_i32(set.kind) {
+                    Some(VariableSetKind::VarSetValue) => set
+                        .args
+                        .iter()
+                        .filter_map(|arg| match arg.node.as_ref()? {
+                            NodeEnum::AConst(c) => match c.val.as_ref()?.node.as_ref()? {
+                                NodeEnum::String(s) => Some(s.str.clone()),
+                                _ => None,
+                            },
+                            _ => None,
+                        })
+                        .find(|s| s != "$user")
+                        .unwrap_or_else(|| DEFAULT_SCHEMA.to_string()),
+                    _ => DEFAULT_SCHEMA.to_string(),
+                };
+            }
+            continue;
+        }
+
+        let mut add = |name: QualifiedName, kind: StatementKind| {
+            let kinds = tables.entry(name).or_default();
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        };
+        let qualify = |relation: &RangeVar| {
+            let relation_schema = match relation.schemaname.as_str() {
+                "" => schema.as_str(),
+                relation_schema => relation_schema,
+            };
+            QualifiedName::new(Some(relation_schema), &relation.relname)
+        };
+
+        let (targets, kind) = match &ast {
+            NodeEnum::CreateStmt(n) => (n.relation.iter().collect(), StatementKind::Create),
+            NodeEnum::CreateTableAsStmt(n) if n.relkind == ObjectType::ObjectTable as i32 => (
+                n.into
+                    .as_ref()
+                    .and_then(|i| i.rel.as_ref())
+                    .into_iter()
+                    .collect(),
+                StatementKind::Create,
+            ),
+            NodeEnum::AlterTableStmt(n) if n.relkind == ObjectType::ObjectTable as i32 => {
+                (n.relation.iter().collect(), StatementKind::Alter)
+            }
+            NodeEnum::RenameStmt(n)
+                if n.rename_type == ObjectType::ObjectTable as i32
+                    || n.relation_type == ObjectType::ObjectTable as i32 =>
+            {
+                (n.relation.iter().collect(), StatementKind::Rename)
+            }
+            NodeEnum::TruncateStmt(n) => (relations(&n.relations), StatementKind::Truncate),
+            NodeEnum::IndexStmt(n) => (n.relation.iter().collect(), StatementKind::CreateIndex),
+            NodeEnum::InsertStmt(n) => (n.relation.iter().collect(), StatementKind::Insert),
+            NodeEnum::UpdateStmt(n) => (n.relation.iter().collect(), StatementKind::Update),
+            NodeEnum::DeleteStmt(n) => (n.relation.iter().collect(), StatementKind::Delete),
+            NodeEnum::CopyStmt(n) => (n.relation.iter().collect(), StatementKind::Copy),
+            NodeEnum::DropStmt(n) if n.remove_type == ObjectType::ObjectTable as i32 => {
+                for object in &n.objects {
+                    if let Some(name) = dropped_table(object, &schema) {
+                        add(name, StatementKind::Drop);
+                    }
+                }
+                (Vec::new(), StatementKind::Drop)
+            }
+            _ => (Vec::new(), StatementKind::Select),
+        };
+        for target in &targets {
+            add(qualify(target), kind);
+        }
+
+        // all other tables are read
+        let nodes = get_nodes(ast.to_ref());
+        let ctes = nodes
+            .iter()
+            .filter_map(|(node, _)| match node {
+                NodeRef::CommonTableExpr(cte) => Some(cte.ctename.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for (node, _) in &nodes {
+            if let NodeRef::RangeVar(relation) = node {
+                let is_cte = relation.schemaname.is_empty() && ctes.contains(&&*relation.relname);
+                if !is_cte && !targets.iter().any(|t| std::ptr::eq(*t, *relation)) {
+                    add(qualify(relation), StatementKind::Select);
+                }
+            }
+        }
+    }
+    tables
+}
+
+fn relations(nodes: &[Node]) -> Vec<&RangeVar> {
+    nodes
+        .iter()
+        .filter_map(|n| match n.node.as_ref()? {
+            NodeEnum::RangeVar(relation) => Some(relation),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the name of a table of a `DROP TABLE`, which is a list of the parts of the name
+fn dropped_table(object: &Node, schema: &str) -> Option<QualifiedName> {
+    let parts = match object.node.as_ref()? {
+        NodeEnum::List(list) => list
+            .items
+            .iter()
+            .map(|n| match n.node.as_ref()? {
+                NodeEnum::String(s) => Some(s.str.as_str()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?,
+        _ => return None,
+    };
+    match parts.as_slice() {
+        [name] => Some(QualifiedName::new(Some(schema), name)),
+        [.., schema, name] => Some(QualifiedName::new(Some(schema), name)),
+        [] => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_affected_tables() {
+        let input = "create table Users (id int);
+insert into users (id) select id from staging.users;
+set search_path to app, public;
+with recent as (select * from events) update audit set n = 1 from recent;
+drop table public.users, old;";
+
+        let tables = affected_tables(parse(input).root())
+            .into_iter()
+            .map(|(name, kinds)| (name.to_string(), kinds))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            tables,
+            vec![
+                ("app.audit".to_string(), vec![StatementKind::Update]),
+                ("app.events".to_string(), vec![StatementKind::Select]),
+                ("app.old".to_string(), vec![StatementKind::Drop]),
+                (
+                    "public.users".to_string(),
+                    vec![
+                        StatementKind::Create,
+                        StatementKind::Insert,
+                        StatementKind::Drop
+                    ]
+                ),
+                ("staging.users".to_string(), vec![StatementKind::Select]),
+            ]
+        );
+    }
+}
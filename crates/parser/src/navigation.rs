@@ -0,0 +1,492 @@
+//! Navigation between the names of a statement, e.g. from the use of an alias to its declaration.
+//!
+//! Names are resolved within the statement that contains them. Every select, insert, update and
+//! delete opens a scope with its common table expressions and the aliases of its tables, and a
+//! name refers to the declaration in the innermost scope that declares it.
+//!
+//! Editors also move between whole statements, e.g. to run the next statement.
+
+use cstree::text::{TextRange, TextSize};
+use pg_query::protobuf::{ColumnRef, Node, RangeFunction, RangeVar};
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::name::resolve;
+use crate::ast::{
+    closing_parenthesis, location_to_offset, name_token_at, non_trivia_tokens, parse_pg_query_stmt,
+    query_range, statements, token_at,
+};
+use crate::pg_query_utils::get_children;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// Returns the range of the declaration of the common table expression or table alias at
+/// `offset` in the source file `root`
+///
+/// If `offset` is on a declaration, its own range is returned. Returns `None` if there is no
+/// such name at `offset`, or if the statement contains syntax errors.
+pub fn goto_definition(root: &SyntaxNode, offset: TextSize) -> Option<TextRange> {
+    let stmt = statements(root)
+        .into_iter()
+        .find(|s| s.text_range().contains_inclusive(offset))?;
+    resolve_names(stmt)
+        .bindings
+        .into_iter()
+        .find(|b| {
+            b.declaration.contains_inclusive(offset)
+                || b.references.iter().any(|r| r.contains_inclusive(offset))
+        })
+        .map(|b| b.declaration)
+}
+
+/// Returns the range of the first statement of the source file `root` that starts after
+/// `offset`, i.e. the statement after the one that contains `offset`
+///
+/// The range reaches from the first to the last token of the statement, without trivia.
+/// Statements that consist of semicolons only are skipped.
+pub fn statement_after(root: &SyntaxNode, offset: TextSize) -> Option<TextRange> {
+    statement_ranges(root)
+        .into_iter()
+        .find(|r| r.start() > offset)
+}
+
+/// Returns the range of the last statement of the source file `root` that starts before
+/// `offset`, which is the statement that contains `offset` if there is one
+///
+/// The range is the same as the one of `statement_after`.
+pub fn statement_before(root: &SyntaxNode, offset: TextSize) -> Option<TextRange> {
+    statement_ranges(root)
+        .into_iter()
+        .rev()
+        .find(|r| r.start() < offset)
+}
+
+fn statement_ranges(root: &SyntaxNode) -> Vec<TextRange> {
+    statements(root)
+        .into_iter()
+        .filter_map(|stmt| {
+            let tokens = non_trivia_tokens(stmt);
+            if tokens.iter().all(|t| t.kind() == SyntaxKind::Ascii59) {
+                return None;
+            }
+            Some(
+                tokens
+                    .first()?
+                    .text_range()
+                    .cover(tokens.last()?.text_range()),
+            )
+        })
+        .collect()
+}
+
+/// Returns the ranges of all uses of the common table expression, table alias or qualified
+/// column at `offset` in the source file `root`, including its declaration, ordered by their
+/// position
+///
+/// For a column like `c.id`, the uses are the columns with the same name that are qualified with
+/// the same alias. Returns an empty list if there is no such name at `offset`, or if the statement
+/// contains syntax errors.
+pub fn find_references(root: &SyntaxNode, offset: TextSize) -> Vec<TextRange> {
+    let stmt = match statements(root)
+        .into_iter()
+        .find(|s| s.text_range().contains_inclusive(offset))
+    {
+        Some(stmt) => stmt,
+        None => return Vec::new(),
+    };
+    let names = resolve_names(stmt);
+
+    let mut ranges = match names
+        .columns
+        .iter()
+        .find(|c| c.range.contains_inclusive(offset))
+    {
+        Some(column) => names
+            .columns
+            .iter()
+            .filter(|c| c.binding == column.binding && c.name == column.name)
+            .map(|c| c.range)
+            .collect(),
+        None => match names.bindings.into_iter().find(|b| {
+            b.declaration.contains_inclusive(offset)
+                || b.references.iter().any(|r| r.contains_inclusive(offset))
+        }) {
+            Some(binding) => std::iter::once(binding.declaration)
+                .chain(binding.references)
+                .collect(),
+            None => Vec::new(),
+        },
+    };
+    ranges.sort_by_key(|r| r.start());
+    ranges
+}
+
+/// A declared name, together with the names that refer to it
+#[derive(Debug)]
+struct Binding {
+    declaration: TextRange,
+    references: Vec<TextRange>,
+}
+
+/// A column that is qualified with a declared name, e.g. `id` of `c.id`
+#[derive(Debug)]
+struct ColumnReference {
+    /// The index of the binding of the qualifier
+    binding: usize,
+    name: String,
+    range: TextRange,
+}
+
+/// The resolved names of a statement
+#[derive(Debug, Default)]
+struct Names {
+    bindings: Vec<Binding>,
+    columns: Vec<ColumnReference>,
+}
+
+/// Resolves the names of the statement node `stmt`
+fn resolve_names(stmt: &SyntaxNode) -> Names {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Names::default(),
+    };
+    let mut resolver = Resolver {
+        stmt,
+        scopes: Vec::new(),
+        names: Names::default(),
+    };
+    resolver.walk(ast.to_ref());
+    resolver.names
+}
+
+struct Resolver<'a> {
+    stmt: &'a SyntaxNode,
+    /// The names that are declared in every enclosing scope, with the index of their binding
+    scopes: Vec<Vec<(String, usize)>>,
+    names: Names,
+}
+
+impl Resolver<'_> {
+    fn walk(&mut self, node: NodeRef) {
+        let declarations = declarations(node);
+        let is_scope = declarations.is_some();
+        if let Some((ctes, relations)) = declarations {
+            self.scopes.push(Vec::new());
+            for cte in ctes {
+                if let Some(NodeEnum::CommonTableExpr(cte)) = cte.node.as_ref() {
+                    let token = token_at(self.stmt, cte.location);
+                    self.declare(&cte.ctename, token);
+                }
+            }
+            for relation in relations {
+                self.declare_aliases(relation);
+            }
+        }
+
+        match node {
+            NodeRef::ColumnRef(column) => self.refer_column(column),
+            // a reference to a common table expression
+            NodeRef::RangeVar(range_var) if range_var.schemaname.is_empty() => {
+                let token = location_to_offset(self.stmt, range_var.location)
+                    .and_then(|offset| name_token_at(self.stmt, offset));
+                self.refer(&range_var.relname, token);
+            }
+            _ => {}
+        }
+
+        for child in get_children(node) {
+            self.walk(child);
+        }
+        if is_scope {
+            self.scopes.pop();
+        }
+    }
+
+    /// Declares the aliases of the tables of the `FROM` clause item `node`
+    fn declare_aliases(&mut self, node: NodeRef) {
+        match node {
+            NodeRef::RangeVar(range_var) => {
+                let alias = match &range_var.alias {
+                    Some(alias) => &alias.aliasname,
+                    None => return,
+                };
+                let stmt = self.stmt;
+                let token = location_to_offset(stmt, range_var.location)
+                    .and_then(|offset| name_token_at(stmt, offset))
+                    .and_then(|name| alias_token(stmt, name.text_range().end(), alias));
+                self.declare(alias, token);
+            }
+            // `(select ...) s`
+            NodeRef::RangeSubselect(subselect) => {
+                let alias = match &subselect.alias {
+                    Some(alias) => &alias.aliasname,
+                    None => return,
+                };
+                let stmt = self.stmt;
+                let tokens = non_trivia_tokens(stmt);
+                let token = subselect
+                    .subquery
+                    .as_deref()
+                    .and_then(|query| query_range(stmt, &tokens, query))
+                    .and_then(|range| alias_token(stmt, range.end(), alias));
+                self.declare(alias, token);
+            }
+            // `generate_series(1, 3) g`
+            NodeRef::RangeFunction(function) => {
+                let alias = match &function.alias {
+                    Some(alias) => &alias.aliasname,
+                    None => return,
+                };
+                let stmt = self.stmt;
+                let token =
+                    function_end(stmt, function).and_then(|end| alias_token(stmt, end, alias));
+                self.declare(alias, token);
+            }
+            NodeRef::JoinExpr(join) => {
+                for arg in [&join.larg, &join.rarg].into_iter().flatten() {
+                    if let Some(arg) = arg.node.as_ref() {
+                        self.declare_aliases(arg.to_ref());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn declare(&mut self, name: &str, token: Option<&SyntaxToken>) {
+        let (token, scope) = match (token, self.scopes.last_mut()) {
+            (Some(token), Some(scope)) => (token, scope),
+            _ => return,
+        };
+        scope.push((name.to_string(), self.names.bindings.len()));
+        self.names.bindings.push(Binding {
+            declaration: token.text_range(),
+            references: Vec::new(),
+        });
+    }
+
+    /// Adds the qualifier of `column` to its binding, and records the column if it is a column of
+    /// a table, e.g. `c.id`
+    fn refer_column(&mut self, column: &ColumnRef) {
+        let qualifier = match column.fields.first().and_then(|f| f.node.as_ref()) {
+            Some(NodeEnum::String(qualifier)) if column.fields.len() > 1 => qualifier,
+            _ => return,
+        };
+        let binding = self.refer(&qualifier.str, token_at(self.stmt, column.location));
+        let name = match column.fields.as_slice() {
+            [_, Node {
+                node: Some(NodeEnum::String(name)),
+            }] => &name.str,
+            _ => return,
+        };
+        let token = location_to_offset(self.stmt, column.location)
+            .and_then(|offset| name_token_at(self.stmt, offset))
+            .filter(|t| resolve(t.resolved().text()) == *name);
+        if let (Some(binding), Some(token)) = (binding, token) {
+            self.names.columns.push(ColumnReference {
+                binding,
+                name: name.clone(),
+                range: token.text_range(),
+            });
+        }
+    }
+
+    /// Adds the reference `token` to the binding of `name`, and returns the index of the binding
+    fn refer(&mut self, name: &str, token: Option<&SyntaxToken>) -> Option<usize> {
+        let token = token.filter(|t| resolve(t.resolved().text()) == name)?;
+        let (_, idx) = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.iter().rev().find(|(n, _)| n == name))?;
+        self.names.bindings[*idx]
+            .references
+            .push(token.text_range());
+        Some(*idx)
+    }
+}
+
+/// Returns the token of the alias `alias` that follows the `FROM` clause item ending at `end`
+fn alias_token<'a>(stmt: &'a SyntaxNode, end: TextSize, alias: &str) -> Option<&'a SyntaxToken> {
+    non_trivia_tokens(stmt)
+        .into_iter()
+        .filter(|t| t.text_range().start() >= end)
+        .find(|t| t.kind() != SyntaxKind::As)
+        .filter(|t| resolve(t.resolved().text()) == alias)
+}
+
+/// Returns the offset after the function call of the `FROM` clause item `function`, including
+/// `ROWS FROM (...)` and `WITH ORDINALITY`
+fn function_end(stmt: &SyntaxNode, function: &RangeFunction) -> Option<TextSize> {
+    let location = function
+        .functions
+        .iter()
+        .filter_map(|f| match f.node.as_ref()? {
+            NodeEnum::List(list) => match list.items.first()?.node.as_ref()? {
+                NodeEnum::FuncCall(call) => Some(call.location),
+                _ => None,
+            },
+            _ => None,
+        })
+        .next_back()?;
+    let tokens = non_trivia_tokens(stmt);
+    let offset = location_to_offset(stmt, location)?;
+    let open = tokens
+        .iter()
+        .position(|t| t.text_range().start() >= offset && t.kind() == SyntaxKind::Ascii40)?;
+    let mut end = closing_parenthesis(&tokens, open)?;
+    if function.is_rowsfrom {
+        end += 1;
+    }
+    if function.ordinality {
+        end += 2;
+    }
+    Some(tokens.get(end)?.text_range().end())
+}
+
+/// Returns the common table expressions and the tables that the statement `node` declares, if it
+/// opens a scope
+fn declarations(node: NodeRef<'_>) -> Option<(&[Node], Vec<NodeRef<'_>>)> {
+    let (with_clause, relations) = match node {
+        NodeRef::SelectStmt(n) => (&n.with_clause, relations(None, &n.from_clause)),
+        NodeRef::InsertStmt(n) => (&n.with_clause, relations(n.relation.as_ref(), &[])),
+        NodeRef::UpdateStmt(n) => (
+            &n.with_clause,
+            relations(n.relation.as_ref(), &n.from_clause),
+        ),
+        NodeRef::DeleteStmt(n) => (
+            &n.with_clause,
+            relations(n.relation.as_ref(), &n.using_clause),
+        ),
+        _ => return None,
+    };
+    let ctes = with_clause
+        .as_ref()
+        .map(|w| w.ctes.as_slice())
+        .unwrap_or_default();
+    Some((ctes, relations))
+}
+
+/// Returns the target table `relation` of a statement, followed by the items of its `FROM`
+/// clause `items`
+fn relations<'a>(relation: Option<&'a RangeVar>, items: &'a [Node]) -> Vec<NodeRef<'a>> {
+    relation
+        .into_iter()
+        .map(NodeRef::RangeVar)
+        .chain(items.iter().filter_map(|n| Some(n.node.as_ref()?.to_ref())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    /// Returns the range of the definition of the name at the `n`th occurrence of `name` in `input`
+    fn definition(input: &str, name: &str, n: usize) -> Option<(usize, usize)> {
+        let (offset, _) = input.match_indices(name).nth(n).unwrap();
+        let range = goto_definition(parse(input).root(), TextSize::from(offset as u32))?;
+        Some((usize::from(range.start()), usize::from(range.end())))
+    }
+
+    #[test]
+    fn test_statement_navigation() {
+        let input = "select 1;\n\n-- second\nselect 2;\n;\nselect 3;\n";
+        let root = parse(input).root().clone();
+        let text = |range: Option<TextRange>| range.map(|r| &input[r]);
+        let offset = |s: &str| TextSize::from(input.find(s).unwrap() as u32);
+
+        assert_eq!(text(statement_after(&root, 0.into())), Some("select 2;"));
+        assert_eq!(
+            text(statement_after(&root, offset("2;"))),
+            Some("select 3;")
+        );
+        assert_eq!(text(statement_after(&root, offset("select 3"))), None);
+
+        assert_eq!(
+            text(statement_before(&root, offset("3;"))),
+            Some("select 3;")
+        );
+        assert_eq!(
+            text(statement_before(&root, offset("select 3"))),
+            Some("select 2;")
+        );
+        assert_eq!(
+            text(statement_before(&root, offset("-- second"))),
+            Some("select 1;")
+        );
+        assert_eq!(text(statement_before(&root, 0.into())), None);
+    }
+
+    #[test]
+    fn test_goto_cte_definition() {
+        let input = "with recent as (select * from orders) select * from recent r where r.id > 1;";
+        let declaration = input.find("recent").unwrap();
+
+        assert_eq!(
+            definition(input, "recent", 1),
+            Some((declaration, declaration + 6))
+        );
+        assert_eq!(
+            definition(input, "recent", 0),
+            Some((declaration, declaration + 6))
+        );
+    }
+
+    #[test]
+    fn test_goto_alias_definition() {
+        let input = "select c.id from customers as c join orders o on o.customer_id = c.id;";
+        let declaration = input.find("c join").unwrap();
+
+        assert_eq!(
+            definition(input, "c.id", 0),
+            Some((declaration, declaration + 1))
+        );
+        assert_eq!(
+            definition(input, "c.id", 1),
+            Some((declaration, declaration + 1))
+        );
+        assert_eq!(definition(input, "customers", 0), None);
+    }
+
+    #[test]
+    fn test_goto_subquery_and_function_alias_definition() {
+        let input = "select s.id, g.g from (select id from t) s, generate_series(1, 3) as g;";
+        let subquery = input.find("s,").unwrap();
+        let function = input.rfind('g').unwrap();
+
+        assert_eq!(definition(input, "s.id", 0), Some((subquery, subquery + 1)));
+        assert_eq!(definition(input, "g.g", 0), Some((function, function + 1)));
+
+        let input = "select o.n from rows from (unnest(array[1])) with ordinality o;";
+        let declaration = input.rfind('o').unwrap();
+        assert_eq!(
+            definition(input, "o.n", 0),
+            Some((declaration, declaration + 1))
+        );
+    }
+
+    #[test]
+    fn test_find_references() {
+        let input = "select c.id, c.name from customers c where c.id > 1 and exists (select 1 from orders c where c.id = 2);";
+        let references = |offset: usize| {
+            find_references(parse(input).root(), TextSize::from(offset as u32))
+                .into_iter()
+                .map(|r| usize::from(r.start()))
+                .collect::<Vec<_>>()
+        };
+        let uses = input
+            .match_indices("c.")
+            .map(|(idx, _)| idx)
+            .take(3)
+            .collect::<Vec<_>>();
+        let declaration = input.find("c where").unwrap();
+
+        assert_eq!(
+            references(uses[0]),
+            vec![uses[0], uses[1], declaration, uses[2]]
+        );
+        assert_eq!(references(declaration), references(uses[2]));
+        // `id` of `c.id`
+        assert_eq!(references(uses[0] + 2), vec![uses[0] + 2, uses[2] + 2]);
+    }
+}
@@ -0,0 +1,257 @@
+//! Rendering of diagnostics for the command line and for CI, e.g. GitHub code scanning.
+
+use serde_json::{json, Value};
+
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::line_index::LineIndex;
+
+/// The format `format_diagnostics` renders diagnostics in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OutputFormat {
+    /// One human-readable line per diagnostic, e.g. `1:8: warning[no-select-star]: ...`
+    #[default]
+    Text,
+    /// A JSON array with an object per diagnostic
+    Json,
+    /// A SARIF 2.1.0 log
+    Sarif,
+}
+
+impl OutputFormat {
+    /// Returns the formatter of this format for the source file at the path or URI `uri`
+    ///
+    /// Only SARIF refers to the source file, the other formats ignore `uri`.
+    pub fn formatter(&self, uri: &str) -> Box<dyn DiagnosticFormatter> {
+        match self {
+            OutputFormat::Text => Box::new(TextFormatter),
+            OutputFormat::Json => Box::new(JsonFormatter),
+            OutputFormat::Sarif => Box::new(SarifFormatter::new(uri)),
+        }
+    }
+}
+
+/// Renders the diagnostics of a source file
+pub trait DiagnosticFormatter {
+    /// Renders `diagnostics`, whose ranges are offsets into `source`
+    fn format(&self, diagnostics: &[Diagnostic], source: &str) -> String;
+}
+
+/// Renders every diagnostic as `line:column: severity[rule]: message`, with one-based lines and
+/// columns
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextFormatter;
+
+impl DiagnosticFormatter for TextFormatter {
+    fn format(&self, diagnostics: &[Diagnostic], source: &str) -> String {
+        let index = LineIndex::new(source);
+        diagnostics
+            .iter()
+            .map(|d| {
+                let (line, column) = index.line_col(d.range().start());
+                let rule = d.rule().map(|r| format!("[{}]", r)).unwrap_or_default();
+                format!(
+                    "{}:{}: {}{}: {}\n",
+                    line + 1,
+                    column + 1,
+                    severity_name(d.severity()),
+                    rule,
+                    d.message()
+                )
+            })
+            .collect()
+    }
+}
+
+/// Renders the diagnostics as a JSON array of objects with the rule id, the severity, the
+/// message, and the zero-based start and end positions and offsets
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonFormatter;
+
+impl DiagnosticFormatter for JsonFormatter {
+    fn format(&self, diagnostics: &[Diagnostic], source: &str) -> String {
+        let index = LineIndex::new(source);
+        let position = |offset| {
+            let (line, column) = index.line_col(offset);
+            json!({ "line": line, "column": column, "offset": u32::from(offset) })
+        };
+        let diagnostics = diagnostics
+            .iter()
+            .map(|d| {
+                json!({
+                    "rule": d.rule(),
+                    "severity": severity_name(d.severity()),
+                    "message": d.message(),
+                    "start": position(d.range().start()),
+                    "end": position(d.range().end()),
+                })
+            })
+            .collect::<Vec<_>>();
+        Value::Array(diagnostics).to_string()
+    }
+}
+
+/// Renders the diagnostics as a SARIF 2.1.0 log with a single run, whose results have the rule
+/// ids, the source file and the one-based regions of the diagnostics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SarifFormatter {
+    /// The path or URI of the source file, e.g. `migrations/001_init.sql`
+    artifact_uri: String,
+}
+
+impl SarifFormatter {
+    /// Creates a formatter for the diagnostics of the source file at the path or URI
+    /// `artifact_uri`
+    pub fn new(artifact_uri: impl Into<String>) -> Self {
+        Self {
+            artifact_uri: artifact_uri.into(),
+        }
+    }
+}
+
+impl DiagnosticFormatter for SarifFormatter {
+    fn format(&self, diagnostics: &[Diagnostic], source: &str) -> String {
+        let index = LineIndex::new(source);
+        let mut rules = diagnostics
+            .iter()
+            .filter_map(|d| d.rule())
+            .collect::<Vec<_>>();
+        rules.sort_unstable();
+        rules.dedup();
+
+        let results = diagnostics
+            .iter()
+            .map(|d| {
+                let (start_line, start_column) = index.line_col(d.range().start());
+                let (end_line, end_column) = index.line_col(d.range().end());
+                let mut result = json!({
+                    "level": match d.severity() {
+                        Severity::Error => "error",
+                        Severity::Warning => "warning",
+                        Severity::Info | Severity::Hint => "note",
+                    },
+                    "message": { "text": d.message() },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": self.artifact_uri },
+                            "region": {
+                                "startLine": start_line + 1,
+                                "startColumn": start_column + 1,
+                                "endLine": end_line + 1,
+                                "endColumn": end_column + 1,
+                            }
+                        }
+                    }],
+                });
+                if let Some(rule) = d.rule() {
+                    result["ruleId"] = json!(rule);
+                }
+                result
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "postgres_lsp",
+                        "rules": rules.iter().map(|r| json!({ "id": r })).collect::<Vec<_>>(),
+                    }
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": results,
+            }]
+        })
+        .to_string()
+    }
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Hint => "hint",
+        Severity::Info => "info",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+/// Renders `diagnostics` of the source text `source` of the file at the path or URI `uri` in the
+/// format `format`
+pub fn format_diagnostics(
+    diagnostics: &[Diagnostic],
+    source: &str,
+    uri: &str,
+    format: OutputFormat,
+) -> String {
+    format.formatter(uri).format(diagnostics, source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::{run_lints, Config};
+    use crate::parser::parse;
+
+    fn diagnostics(input: &str) -> Vec<Diagnostic> {
+        run_lints(parse(input).root(), &Config::default())
+    }
+
+    #[test]
+    fn test_text() {
+        let input = "select 1;\nselect * from t;;";
+        assert_eq!(
+            format_diagnostics(&diagnostics(input), input, "t.sql", OutputFormat::Text),
+            "2:8: warning[no-select-star]: `*` depends on the columns of the table, list the columns instead\n2:17: warning[redundant-semicolons]: redundant semicolon\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let input = "select 1;\nselect * from t;";
+        let output = format_diagnostics(&diagnostics(input), input, "t.sql", OutputFormat::Json);
+        let value: Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(
+            value,
+            json!([{
+                "rule": "no-select-star",
+                "severity": "warning",
+                "message": "`*` depends on the columns of the table, list the columns instead",
+                "start": { "line": 1, "column": 7, "offset": 17 },
+                "end": { "line": 1, "column": 8, "offset": 18 },
+            }])
+        );
+    }
+
+    #[test]
+    fn test_sarif() {
+        let input = "select 1;\nselect * from t;;";
+        let output = format_diagnostics(
+            &diagnostics(input),
+            input,
+            "migrations/001_init.sql",
+            OutputFormat::Sarif,
+        );
+        let value: Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(value["version"], "2.1.0");
+        let run = &value["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"],
+            json!([{ "id": "no-select-star" }, { "id": "redundant-semicolons" }])
+        );
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "no-select-star");
+        assert_eq!(result["level"], "warning");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["artifactLocation"],
+            json!({ "uri": "migrations/001_init.sql" })
+        );
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["region"],
+            json!({ "startLine": 2, "startColumn": 8, "endLine": 2, "endColumn": 9 })
+        );
+        assert_eq!(run["results"].as_array().unwrap().len(), 2);
+    }
+}
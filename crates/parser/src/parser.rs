@@ -1,8 +1,13 @@
+use cstree::build::GreenNodeBuilder;
 use cstree::syntax::ResolvedNode;
-use cstree::{build::GreenNodeBuilder, text::TextRange};
+use cstree::text::{TextRange, TextSize};
+use encoding_rs::{DecoderResult, Encoding};
 use pg_query::NodeEnum;
 
+use crate::ast::statements;
 use crate::ast_node::RawStmt;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::source_file::{SourceFileSplitter, SourceFileToken};
 use crate::syntax_error::SyntaxError;
 use crate::syntax_kind::{SyntaxKind, SyntaxKindType};
 use crate::syntax_node::SyntaxNode;
@@ -14,8 +19,10 @@ pub struct Parser {
     inner: GreenNodeBuilder<'static, 'static, SyntaxKind>,
     /// A buffer for tokens that are not yet applied to the cst
     token_buffer: Vec<(SyntaxKind, String)>,
-    /// The current depth of the cst
-    curr_depth: i32,
+    /// The depths of all currently open nodes, innermost last
+    open_depths: Vec<i32>,
+    /// The number of unclosed parentheses within each of the currently open nodes
+    open_parens: Vec<u32>,
     /// The syntax errors accumulated during parsing
     errors: Vec<SyntaxError>,
     /// The pg_query statements representing the abtract syntax tree
@@ -37,10 +44,203 @@ pub struct Parse {
     pub stmts: Vec<RawStmt>,
 }
 
+/// Parses the source `text`
+///
+/// This is a shortcut for parsing a source file with a new `Parser`. Use `Parser` directly for
+/// more control, e.g. to parse individual statements.
+///
+/// ```
+/// let result = parser::parse("select 1;\nselect from;");
+///
+/// assert_eq!(result.root().text(), "select 1;\nselect from;");
+/// assert_eq!(result.statements().len(), 2);
+/// assert_eq!(result.diagnostics().len(), 1);
+/// assert_eq!(
+///     result.diagnostics()[0].message(),
+///     "Invalid statement: syntax error at or near \";\""
+/// );
+/// ```
+pub fn parse(text: &str) -> ParseResult {
+    let mut parser = Parser::new();
+    parser.parse_source_file(text);
+    ParseResult::from(parser.finish())
+}
+
+/// Parses the source `text` one statement at a time
+///
+/// Statements are split the same way as by `parse`, but each statement is only parsed when the
+/// iterator is advanced, and its tree can be dropped before the next one is parsed. This keeps
+/// the memory bounded for large files, e.g. dumps. Trivia and empty statements between the
+/// statements are skipped.
+///
+/// Every statement is parsed as a source file of its own, so the ranges within a `ParseResult`
+/// are relative to the start of the statement. The range of the statement in `text` is yielded
+/// along with it.
+///
+/// ```
+/// let text = "select 1;\n-- comment\nselect from;\nselect 3";
+/// let mut statements = parser::parse_streaming(text);
+///
+/// let (range, result) = statements.next().unwrap();
+/// assert_eq!(&text[range], "select 1;");
+/// assert!(result.diagnostics().is_empty());
+///
+/// let (range, result) = statements.next().unwrap();
+/// assert_eq!(&text[range], "select from;");
+/// assert_eq!(result.diagnostics().len(), 1);
+///
+/// let (range, _) = statements.next().unwrap();
+/// assert_eq!(&text[range], "select 3");
+/// assert!(statements.next().is_none());
+/// ```
+pub fn parse_streaming(text: &str) -> impl Iterator<Item = (TextRange, ParseResult)> + '_ {
+    SourceFileSplitter::new(text)
+        .filter(|(token, _)| *token == SourceFileToken::Statement)
+        .map(move |(_, span)| {
+            let range = TextRange::new(
+                TextSize::from(span.start as u32),
+                TextSize::from(span.end as u32),
+            );
+            (range, parse(&text[span]))
+        })
+}
+
+/// Parses the source `bytes` in `encoding`, e.g. `Encoding::for_label(b"latin1")`
+///
+/// The bytes are decoded to UTF-8 before parsing. Every malformed byte sequence is replaced with
+/// U+FFFD and reported as an error. All ranges, including those of the diagnostics, are offsets
+/// into the decoded text, not into `bytes`, because a character may take a different number of
+/// bytes in UTF-8 than in `encoding`.
+///
+/// ```
+/// use parser::Encoding;
+///
+/// let latin1 = Encoding::for_label(b"latin1").unwrap();
+/// let result = parser::parse_bytes(b"select caf\xe9 from t;", latin1);
+/// assert_eq!(result.root().text(), "select café from t;");
+/// assert!(result.diagnostics().is_empty());
+///
+/// let utf8 = Encoding::for_label(b"utf-8").unwrap();
+/// let result = parser::parse_bytes(b"select '\xff';", utf8);
+/// assert_eq!(result.root().text(), "select '\u{fffd}';");
+/// assert_eq!(
+///     result.diagnostics()[0].message(),
+///     "invalid byte sequence for encoding UTF-8"
+/// );
+/// ```
+pub fn parse_bytes(bytes: &[u8], encoding: &'static Encoding) -> ParseResult {
+    let (text, malformed) = decode(bytes, encoding);
+    let mut result = parse(&text);
+    result.diagnostics.splice(
+        0..0,
+        malformed.into_iter().map(|range| {
+            Diagnostic::new(
+                format!("invalid byte sequence for encoding {}", encoding.name()),
+                range,
+            )
+            .with_severity(Severity::Error)
+        }),
+    );
+    result
+}
+
+/// Decodes `bytes` in `encoding`, and returns the text along with the ranges of the replacement
+/// characters of malformed byte sequences
+fn decode(bytes: &[u8], encoding: &'static Encoding) -> (String, Vec<TextRange>) {
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut text = String::new();
+    let mut malformed = Vec::new();
+    let mut pos = 0;
+    loop {
+        let (result, read) =
+            decoder.decode_to_string_without_replacement(&bytes[pos..], &mut text, true);
+        pos += read;
+        match result {
+            DecoderResult::InputEmpty => break,
+            DecoderResult::OutputFull => text.reserve(
+                decoder
+                    .max_utf8_buffer_length_without_replacement(bytes.len() - pos)
+                    .unwrap_or(bytes.len() - pos)
+                    .max(4),
+            ),
+            DecoderResult::Malformed(_, _) => {
+                let start = TextSize::of(&text);
+                text.push(char::REPLACEMENT_CHARACTER);
+                malformed.push(TextRange::at(
+                    start,
+                    TextSize::of(char::REPLACEMENT_CHARACTER),
+                ));
+            }
+        }
+    }
+    (text, malformed)
+}
+
+/// Validates the source `text`, and returns whether it is free of syntax errors along with its
+/// diagnostics
+///
+/// Every statement of `text` is validated.
+///
+/// ```
+/// assert_eq!(parser::validate("select 1;"), (true, Vec::new()));
+///
+/// let (valid, diagnostics) = parser::validate("select 1;\nselect from;");
+/// assert!(!valid);
+/// assert_eq!(diagnostics.len(), 1);
+/// ```
+pub fn validate(text: &str) -> (bool, Vec<Diagnostic>) {
+    let diagnostics = parse(text).diagnostics;
+    let valid = diagnostics.iter().all(|d| d.severity() != Severity::Error);
+    (valid, diagnostics)
+}
+
+/// Result of `parse`
+#[derive(Debug)]
+pub struct ParseResult {
+    parse: Parse,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl ParseResult {
+    /// The root node of the concrete syntax tree
+    pub fn root(&self) -> &ResolvedNode<SyntaxKind> {
+        &self.parse.cst
+    }
+
+    /// The diagnostics of all syntax errors
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// The nodes of all statements in the concrete syntax tree, including erroneous ones
+    pub fn statements(&self) -> Vec<&SyntaxNode> {
+        statements(&self.parse.cst)
+    }
+
+    /// The underlying `Parse`
+    pub fn into_parse(self) -> Parse {
+        self.parse
+    }
+}
+
+impl From<Parse> for ParseResult {
+    fn from(parse: Parse) -> Self {
+        let diagnostics = parse.errors.iter().cloned().map(Diagnostic::from).collect();
+        Self { parse, diagnostics }
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Parser {
     pub fn new() -> Self {
         Self {
-            curr_depth: -1,
+            open_depths: Vec::new(),
+            open_parens: Vec::new(),
             inner: GreenNodeBuilder::new(),
             token_buffer: Vec::new(),
             errors: Vec::new(),
@@ -50,11 +250,20 @@ impl Parser {
         }
     }
 
+    /// The depth of the innermost open node, or -1 if no node is open
+    fn curr_depth(&self) -> i32 {
+        self.open_depths.last().copied().unwrap_or(-1)
+    }
+
     /// close all nodes until the specified depth is reached
+    ///
+    /// pg_query depths are not necessarily continuous, because nodes without a location are
+    /// skipped. Hence, we close every open node that is at `depth` or deeper.
     pub fn close_until_depth(&mut self, depth: i32) {
-        while self.curr_depth >= depth {
+        while self.curr_depth() >= depth {
             self.finish_node();
-            self.curr_depth -= 1;
+            self.open_depths.pop();
+            self.open_parens.pop();
         }
     }
 
@@ -70,15 +279,15 @@ impl Parser {
             self.token_buffer.is_empty(),
             "Token buffer must be empty before setting a checkpoint"
         );
-        self.checkpoint = Some(self.curr_depth);
+        self.checkpoint = Some(self.curr_depth());
         self.is_parsing_flat_node = is_parsing_flat_node;
     }
 
     /// close all nodes until checkpoint depth is reached
     pub fn close_checkpoint(&mut self) {
         self.consume_token_buffer();
-        if self.checkpoint.is_some() {
-            self.close_until_depth(self.checkpoint.unwrap());
+        if let Some(checkpoint) = self.checkpoint {
+            self.close_until_depth(checkpoint);
         }
         self.checkpoint = None;
         self.is_parsing_flat_node = false;
@@ -93,13 +302,14 @@ impl Parser {
     /// handles closing previous nodes if necessary
     /// and consumes token buffer before starting new node
     pub fn start_node_at(&mut self, kind: SyntaxKind, depth: Option<i32>) {
-        let depth = depth.unwrap_or(self.curr_depth + 1);
+        let depth = depth.unwrap_or(self.curr_depth() + 1);
         // close until target depth
         self.close_until_depth(depth);
 
         self.consume_token_buffer();
 
-        self.curr_depth = depth;
+        self.open_depths.push(depth);
+        self.open_parens.push(0);
         self.start_node(kind);
     }
 
@@ -110,20 +320,53 @@ impl Parser {
 
     /// Drains the token buffer and applies all tokens
     pub fn consume_token_buffer(&mut self) {
-        for (kind, text) in self.token_buffer.drain(..) {
-            self.inner.token(kind, &text);
+        for (kind, text) in std::mem::take(&mut self.token_buffer) {
+            self.apply_token(kind, &text);
+        }
+    }
+
+    /// Applies a token to the innermost open node, and keeps track of its parentheses
+    fn apply_token(&mut self, kind: SyntaxKind, text: &str) {
+        if let Some(parens) = self.open_parens.last_mut() {
+            match kind {
+                SyntaxKind::Ascii40 => *parens += 1,
+                SyntaxKind::Ascii41 => *parens = parens.saturating_sub(1),
+                _ => {}
+            }
         }
+        self.inner.token(kind, text);
+    }
+
+    /// Applies a closing parenthesis to the innermost open node that contains the matching
+    /// opening parenthesis, and closes all nodes within it
+    ///
+    /// Returns false if no open node contains an unclosed parenthesis.
+    fn close_paren(&mut self, text: &str) -> bool {
+        let depth = match self.open_parens.iter().rposition(|p| *p > 0) {
+            Some(idx) => self.open_depths[idx],
+            None => return false,
+        };
+        self.consume_token_buffer();
+        self.close_until_depth(depth + 1);
+        self.apply_token(SyntaxKind::Ascii41, text);
+        true
     }
 
     /// applies token based on its `SyntaxKindType`
     /// if `SyntaxKindType::Close`, closes all nodes until depth 1
     /// if `SyntaxKindType::Follow`, add token to buffer and wait until next node to apply token at same depth
-    /// otherwise, applies token immediately
+    /// otherwise, consumes the token buffer and applies token immediately
+    ///
+    /// a closing parenthesis is applied to the node that contains the opening one, if any
     ///
     /// if `is_parsing_flat_node` is true, applies token immediately
     pub fn token(&mut self, kind: SyntaxKind, text: &str) {
         if self.is_parsing_flat_node {
-            self.inner.token(kind, text);
+            self.apply_token(kind, text);
+            return;
+        }
+
+        if kind == SyntaxKind::Ascii41 && self.close_paren(text) {
             return;
         }
 
@@ -132,14 +375,16 @@ impl Parser {
                 // move up to depth 2 and consume buffered tokens before applying closing token
                 self.close_until_depth(2);
                 self.consume_token_buffer();
-                self.inner.token(kind, text);
+                self.apply_token(kind, text);
             }
             Some(SyntaxKindType::Follow) => {
                 // wait until next node, and apply token at same depth
                 self.token_buffer.push((kind, text.to_string()));
             }
             _ => {
-                self.inner.token(kind, text);
+                // buffered tokens precede this one in the source
+                self.consume_token_buffer();
+                self.apply_token(kind, text);
             }
         }
     }
@@ -164,3 +409,90 @@ impl Parser {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let text = "select 1;\nselect from;\n";
+
+        let (valid, diagnostics) = validate(text);
+
+        assert!(!valid);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity(), Severity::Error);
+        assert_eq!(&text[diagnostics[0].range()], "select from;");
+        assert_eq!(validate("select 1;\nselect 2"), (true, Vec::new()));
+    }
+
+    #[test]
+    fn test_parse_streaming() {
+        let text = "/* header */\nselect 1; -- one\n\nselect from;\n-- trailing\nselect 3";
+
+        let statements = parse_streaming(text)
+            .map(|(range, result)| {
+                (
+                    &text[range],
+                    result.root().text().to_string(),
+                    result
+                        .diagnostics()
+                        .iter()
+                        .map(|d| d.range())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            statements,
+            vec![
+                ("select 1;", "select 1;".to_string(), Vec::new()),
+                (
+                    "select from;",
+                    "select from;".to_string(),
+                    vec![TextRange::new(0.into(), 12.into())]
+                ),
+                ("select 3", "select 3".to_string(), Vec::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        let latin1 = Encoding::for_label(b"latin1").unwrap();
+        let result = parse_bytes(b"select caf\xe9;\nselect from;", latin1);
+        let text = result.root().text().to_string();
+        assert_eq!(text, "select caf\u{e9};\nselect from;");
+        // the range is an offset into the decoded text, where `\u{e9}` takes two bytes
+        assert_eq!(result.diagnostics().len(), 1);
+        assert_eq!(&text[result.diagnostics()[0].range()], "select from;");
+
+        let utf16 = Encoding::for_label(b"utf-16le").unwrap();
+        let bytes = "select '\u{fc}' from t;"
+            .encode_utf16()
+            .flat_map(|c| c.to_le_bytes())
+            .collect::<Vec<_>>();
+        let result = parse_bytes(&bytes, utf16);
+        assert_eq!(result.root().text(), "select '\u{fc}' from t;");
+        assert!(result.diagnostics().is_empty());
+
+        let utf8 = Encoding::for_label(b"utf-8").unwrap();
+        let result = parse_bytes(b"select '\xff\xfe' from t;", utf8);
+        let text = result.root().text().to_string();
+        assert_eq!(text, "select '\u{fffd}\u{fffd}' from t;");
+        assert_eq!(
+            result
+                .diagnostics()
+                .iter()
+                .map(|d| (d.severity(), &text[d.range()]))
+                .collect::<Vec<_>>(),
+            vec![(Severity::Error, "\u{fffd}"), (Severity::Error, "\u{fffd}")]
+        );
+        assert_eq!(
+            result.diagnostics()[1].range(),
+            TextRange::new(11.into(), 14.into())
+        );
+    }
+}
@@ -1,18 +1,1765 @@
-use pg_query::NodeRef;
+use pg_query::{Node, NodeRef};
 
 /// Gets the position value for a pg_query node
 ///
 /// This can mostly be generated by just returning `node.location` if the type has the property,
 /// but there are some exceptions where the location on the node itself is not leftmost position, e.g. for `AExpr`.
+///
+/// Returns -1 if the node does not carry a location.
 pub fn get_position_for_pg_query_node(node: &NodeRef) -> i32 {
     match node {
         NodeRef::ResTarget(n) => n.location,
-        NodeRef::AExpr(n) => get_position_for_pg_query_node(
-            &n.lexpr.as_ref().unwrap().node.as_ref().unwrap().to_ref(),
-        ),
+        NodeRef::AExpr(n) => leftmost(n.lexpr.as_deref(), n.location),
+        NodeRef::BoolExpr(n) => leftmost(n.args.first(), n.location),
+        NodeRef::TypeCast(n) => leftmost(n.arg.as_deref(), n.location),
+        NodeRef::SubLink(n) => leftmost(n.testexpr.as_deref(), n.location),
+        NodeRef::NullTest(n) => leftmost(n.arg.as_deref(), n.location),
+        NodeRef::BooleanTest(n) => leftmost(n.arg.as_deref(), n.location),
+        NodeRef::CollateClause(n) => leftmost(n.arg.as_deref(), n.location),
+        NodeRef::SortBy(n) => leftmost(n.node.as_deref(), n.location),
         NodeRef::RangeVar(n) => n.location,
         NodeRef::ColumnRef(n) => n.location,
         NodeRef::AConst(n) => n.location,
+        NodeRef::TableFunc(n) => n.location,
+        NodeRef::Var(n) => n.location,
+        NodeRef::Param(n) => n.location,
+        NodeRef::Aggref(n) => n.location,
+        NodeRef::GroupingFunc(n) => n.location,
+        NodeRef::WindowFunc(n) => n.location,
+        NodeRef::FuncExpr(n) => n.location,
+        NodeRef::NamedArgExpr(n) => n.location,
+        NodeRef::OpExpr(n) => n.location,
+        NodeRef::DistinctExpr(n) => n.location,
+        NodeRef::NullIfExpr(n) => n.location,
+        NodeRef::ScalarArrayOpExpr(n) => n.location,
+        NodeRef::RelabelType(n) => n.location,
+        NodeRef::CoerceViaIo(n) => n.location,
+        NodeRef::ArrayCoerceExpr(n) => n.location,
+        NodeRef::ConvertRowtypeExpr(n) => n.location,
+        NodeRef::CollateExpr(n) => n.location,
+        NodeRef::CaseExpr(n) => n.location,
+        NodeRef::CaseWhen(n) => n.location,
+        NodeRef::ArrayExpr(n) => n.location,
+        NodeRef::RowExpr(n) => n.location,
+        NodeRef::CoalesceExpr(n) => n.location,
+        NodeRef::MinMaxExpr(n) => n.location,
+        NodeRef::SqlvalueFunction(n) => n.location,
+        NodeRef::XmlExpr(n) => n.location,
+        NodeRef::CoerceToDomain(n) => n.location,
+        NodeRef::CoerceToDomainValue(n) => n.location,
+        NodeRef::SetToDefault(n) => n.location,
+        NodeRef::ParamRef(n) => n.location,
+        NodeRef::FuncCall(n) => n.location,
+        NodeRef::AArrayExpr(n) => n.location,
+        NodeRef::WindowDef(n) => n.location,
+        NodeRef::RangeTableSample(n) => n.location,
+        NodeRef::RangeTableFunc(n) => n.location,
+        NodeRef::RangeTableFuncCol(n) => n.location,
+        NodeRef::TypeName(n) => n.location,
+        NodeRef::ColumnDef(n) => n.location,
+        NodeRef::Constraint(n) => n.location,
+        NodeRef::DefElem(n) => n.location,
+        NodeRef::GroupingSet(n) => n.location,
+        NodeRef::XmlSerialize(n) => n.location,
+        NodeRef::WithClause(n) => n.location,
+        NodeRef::InferClause(n) => n.location,
+        NodeRef::OnConflictClause(n) => n.location,
+        NodeRef::CommonTableExpr(n) => n.location,
+        NodeRef::RoleSpec(n) => n.location,
+        NodeRef::PartitionElem(n) => n.location,
+        NodeRef::PartitionSpec(n) => n.location,
+        NodeRef::PartitionBoundSpec(n) => n.location,
+        NodeRef::PartitionRangeDatum(n) => n.location,
         _ => -1,
     }
 }
+
+/// Returns the position of `child` if it is known, otherwise `location`
+///
+/// Used for nodes whose own location points to an operator or keyword in the middle of the
+/// expression, e.g. `a = b` is located at `=`.
+fn leftmost(child: Option<&Node>, location: i32) -> i32 {
+    let pos = child
+        .and_then(|c| c.node.as_ref())
+        .map(|n| get_position_for_pg_query_node(&n.to_ref()))
+        .unwrap_or(-1);
+    if pos >= 0 && (location < 0 || pos < location) {
+        pos
+    } else {
+        location
+    }
+}
+
+/// Returns the direct children of a pg_query node
+///
+/// In contrast to `nodes()` of pg_query.rs, which only visits the subset of nodes it needs to
+/// find tables and functions, this visits every child that is a node itself.
+/// Generated from the protobuf definition in pg_query.rs (`libpg_query/protobuf/pg_query.proto`).
+pub fn get_children(node: NodeRef) -> Vec<NodeRef> {
+    let mut children = Vec::new();
+    match node {
+        NodeRef::Alias(n) => {
+            n.colnames.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::RangeVar(n) => {
+            if let Some(n) = &n.alias {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::TableFunc(n) => {
+            n.ns_uris.iter().for_each(|n| push_node(&mut children, n));
+            n.ns_names.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.docexpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.rowexpr {
+                push_node(&mut children, n);
+            }
+            n.colnames.iter().for_each(|n| push_node(&mut children, n));
+            n.coltypes.iter().for_each(|n| push_node(&mut children, n));
+            n.coltypmods
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.colcollations
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.colexprs.iter().for_each(|n| push_node(&mut children, n));
+            n.coldefexprs
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::Var(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::Param(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::Aggref(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.aggargtypes
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.aggdirectargs
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+            n.aggorder.iter().for_each(|n| push_node(&mut children, n));
+            n.aggdistinct
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.aggfilter {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::GroupingFunc(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+            n.refs.iter().for_each(|n| push_node(&mut children, n));
+            n.cols.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::WindowFunc(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.aggfilter {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::SubscriptingRef(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.refupperindexpr
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.reflowerindexpr
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.refexpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.refassgnexpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::FuncExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::NamedArgExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::OpExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::DistinctExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::NullIfExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::ScalarArrayOpExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::BoolExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::SubLink(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.testexpr {
+                push_node(&mut children, n);
+            }
+            n.oper_name.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.subselect {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::SubPlan(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.testexpr {
+                push_node(&mut children, n);
+            }
+            n.param_ids.iter().for_each(|n| push_node(&mut children, n));
+            n.set_param.iter().for_each(|n| push_node(&mut children, n));
+            n.par_param.iter().for_each(|n| push_node(&mut children, n));
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlternativeSubPlan(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.subplans.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::FieldSelect(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::FieldStore(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+            n.newvals.iter().for_each(|n| push_node(&mut children, n));
+            n.fieldnums.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::RelabelType(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CoerceViaIo(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::ArrayCoerceExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.elemexpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::ConvertRowtypeExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CollateExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CaseExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.defresult {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CaseWhen(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.expr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.result {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CaseTestExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::ArrayExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.elements.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::RowExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+            n.colnames.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::RowCompareExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.opnos.iter().for_each(|n| push_node(&mut children, n));
+            n.opfamilies
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.inputcollids
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.largs.iter().for_each(|n| push_node(&mut children, n));
+            n.rargs.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CoalesceExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::MinMaxExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::SqlvalueFunction(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::XmlExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            n.named_args
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.arg_names.iter().for_each(|n| push_node(&mut children, n));
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::NullTest(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::BooleanTest(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CoerceToDomain(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CoerceToDomainValue(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::SetToDefault(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CurrentOfExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::NextValueExpr(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::InferenceElem(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.expr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::TargetEntry(n) => {
+            if let Some(n) = &n.xpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.expr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::JoinExpr(n) => {
+            if let Some(n) = &n.larg {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.rarg {
+                push_node(&mut children, n);
+            }
+            n.using_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.quals {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.alias {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::FromExpr(n) => {
+            n.fromlist.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.quals {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::OnConflictExpr(n) => {
+            n.arbiter_elems
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.arbiter_where {
+                push_node(&mut children, n);
+            }
+            n.on_conflict_set
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.on_conflict_where {
+                push_node(&mut children, n);
+            }
+            n.excl_rel_tlist
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::IntoClause(n) => {
+            if let Some(n) = &n.rel {
+                children.push(n.to_ref());
+            }
+            n.col_names.iter().for_each(|n| push_node(&mut children, n));
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.view_query {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::RawStmt(n) => {
+            if let Some(n) = &n.stmt {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::Query(n) => {
+            if let Some(n) = &n.utility_stmt {
+                push_node(&mut children, n);
+            }
+            n.cte_list.iter().for_each(|n| push_node(&mut children, n));
+            n.rtable.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.jointree {
+                children.push(n.to_ref());
+            }
+            n.target_list
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.on_conflict {
+                children.push(n.to_ref());
+            }
+            n.returning_list
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.group_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.grouping_sets
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.having_qual {
+                push_node(&mut children, n);
+            }
+            n.window_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.distinct_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.sort_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.limit_offset {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.limit_count {
+                push_node(&mut children, n);
+            }
+            n.row_marks.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.set_operations {
+                push_node(&mut children, n);
+            }
+            n.constraint_deps
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.with_check_options
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::InsertStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            n.cols.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.select_stmt {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.on_conflict_clause {
+                children.push(n.to_ref());
+            }
+            n.returning_list
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.with_clause {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::DeleteStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            n.using_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.where_clause {
+                push_node(&mut children, n);
+            }
+            n.returning_list
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.with_clause {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::UpdateStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            n.target_list
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.where_clause {
+                push_node(&mut children, n);
+            }
+            n.from_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.returning_list
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.with_clause {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::SelectStmt(n) => {
+            n.distinct_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.into_clause {
+                children.push(n.to_ref());
+            }
+            n.target_list
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.from_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.where_clause {
+                push_node(&mut children, n);
+            }
+            n.group_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.having_clause {
+                push_node(&mut children, n);
+            }
+            n.window_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.values_lists
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.sort_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.limit_offset {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.limit_count {
+                push_node(&mut children, n);
+            }
+            n.locking_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.with_clause {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.larg {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.rarg {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::AlterTableStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            n.cmds.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterTableCmd(n) => {
+            if let Some(n) = &n.newowner {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.def {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::AlterDomainStmt(n) => {
+            n.type_name.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.def {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::SetOperationStmt(n) => {
+            if let Some(n) = &n.larg {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.rarg {
+                push_node(&mut children, n);
+            }
+            n.col_types.iter().for_each(|n| push_node(&mut children, n));
+            n.col_typmods
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.col_collations
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.group_clauses
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::GrantStmt(n) => {
+            n.objects.iter().for_each(|n| push_node(&mut children, n));
+            n.privileges
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.grantees.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::GrantRoleStmt(n) => {
+            n.granted_roles
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.grantee_roles
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.grantor {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::AlterDefaultPrivilegesStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.action {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::ClusterStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::CopyStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.query {
+                push_node(&mut children, n);
+            }
+            n.attlist.iter().for_each(|n| push_node(&mut children, n));
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.where_clause {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CreateStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            n.table_elts
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.inh_relations
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.partbound {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.partspec {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.of_typename {
+                children.push(n.to_ref());
+            }
+            n.constraints
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::DefineStmt(n) => {
+            n.defnames.iter().for_each(|n| push_node(&mut children, n));
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+            n.definition
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::DropStmt(n) => {
+            n.objects.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::TruncateStmt(n) => {
+            n.relations.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CommentStmt(n) => {
+            if let Some(n) = &n.object {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::IndexStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            n.index_params
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.index_including_params
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.where_clause {
+                push_node(&mut children, n);
+            }
+            n.exclude_op_names
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateFunctionStmt(n) => {
+            n.funcname.iter().for_each(|n| push_node(&mut children, n));
+            n.parameters
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.return_type {
+                children.push(n.to_ref());
+            }
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterFunctionStmt(n) => {
+            if let Some(n) = &n.func {
+                children.push(n.to_ref());
+            }
+            n.actions.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::DoStmt(n) => {
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::RenameStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.object {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::RuleStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.where_clause {
+                push_node(&mut children, n);
+            }
+            n.actions.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::TransactionStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::ViewStmt(n) => {
+            if let Some(n) = &n.view {
+                children.push(n.to_ref());
+            }
+            n.aliases.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.query {
+                push_node(&mut children, n);
+            }
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateDomainStmt(n) => {
+            n.domainname
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.type_name {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.coll_clause {
+                children.push(n.to_ref());
+            }
+            n.constraints
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreatedbStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::DropdbStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::VacuumStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+            n.rels.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::ExplainStmt(n) => {
+            if let Some(n) = &n.query {
+                push_node(&mut children, n);
+            }
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateTableAsStmt(n) => {
+            if let Some(n) = &n.query {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.into {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::CreateSeqStmt(n) => {
+            if let Some(n) = &n.sequence {
+                children.push(n.to_ref());
+            }
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterSeqStmt(n) => {
+            if let Some(n) = &n.sequence {
+                children.push(n.to_ref());
+            }
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::VariableSetStmt(n) => {
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateTrigStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            n.funcname.iter().for_each(|n| push_node(&mut children, n));
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+            n.columns.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.when_clause {
+                push_node(&mut children, n);
+            }
+            n.transition_rels
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.constrrel {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::CreatePlangStmt(n) => {
+            n.plhandler.iter().for_each(|n| push_node(&mut children, n));
+            n.plinline.iter().for_each(|n| push_node(&mut children, n));
+            n.plvalidator
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateRoleStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterRoleStmt(n) => {
+            if let Some(n) = &n.role {
+                children.push(n.to_ref());
+            }
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::DropRoleStmt(n) => {
+            n.roles.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::LockStmt(n) => {
+            n.relations.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::ConstraintsSetStmt(n) => {
+            n.constraints
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::ReindexStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::CreateSchemaStmt(n) => {
+            if let Some(n) = &n.authrole {
+                children.push(n.to_ref());
+            }
+            n.schema_elts
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterDatabaseStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterDatabaseSetStmt(n) => {
+            if let Some(n) = &n.setstmt {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::AlterRoleSetStmt(n) => {
+            if let Some(n) = &n.role {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.setstmt {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::CreateConversionStmt(n) => {
+            n.conversion_name
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.func_name.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateCastStmt(n) => {
+            if let Some(n) = &n.sourcetype {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.targettype {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.func {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::CreateOpClassStmt(n) => {
+            n.opclassname
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.opfamilyname
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.datatype {
+                children.push(n.to_ref());
+            }
+            n.items.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateOpFamilyStmt(n) => {
+            n.opfamilyname
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterOpFamilyStmt(n) => {
+            n.opfamilyname
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.items.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::PrepareStmt(n) => {
+            n.argtypes.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.query {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::ExecuteStmt(n) => {
+            n.params.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::DeclareCursorStmt(n) => {
+            if let Some(n) = &n.query {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CreateTableSpaceStmt(n) => {
+            if let Some(n) = &n.owner {
+                children.push(n.to_ref());
+            }
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterObjectDependsStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.object {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.extname {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::AlterObjectSchemaStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.object {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::AlterOwnerStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.object {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.newowner {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::AlterOperatorStmt(n) => {
+            if let Some(n) = &n.opername {
+                children.push(n.to_ref());
+            }
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterTypeStmt(n) => {
+            n.type_name.iter().for_each(|n| push_node(&mut children, n));
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::DropOwnedStmt(n) => {
+            n.roles.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::ReassignOwnedStmt(n) => {
+            n.roles.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.newrole {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::CompositeTypeStmt(n) => {
+            if let Some(n) = &n.typevar {
+                children.push(n.to_ref());
+            }
+            n.coldeflist
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateEnumStmt(n) => {
+            n.type_name.iter().for_each(|n| push_node(&mut children, n));
+            n.vals.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateRangeStmt(n) => {
+            n.type_name.iter().for_each(|n| push_node(&mut children, n));
+            n.params.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterEnumStmt(n) => {
+            n.type_name.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterTsdictionaryStmt(n) => {
+            n.dictname.iter().for_each(|n| push_node(&mut children, n));
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterTsconfigurationStmt(n) => {
+            n.cfgname.iter().for_each(|n| push_node(&mut children, n));
+            n.tokentype.iter().for_each(|n| push_node(&mut children, n));
+            n.dicts.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateFdwStmt(n) => {
+            n.func_options
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterFdwStmt(n) => {
+            n.func_options
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateForeignServerStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterForeignServerStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateUserMappingStmt(n) => {
+            if let Some(n) = &n.user {
+                children.push(n.to_ref());
+            }
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterUserMappingStmt(n) => {
+            if let Some(n) = &n.user {
+                children.push(n.to_ref());
+            }
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::DropUserMappingStmt(n) => {
+            if let Some(n) = &n.user {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::AlterTableSpaceOptionsStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterTableMoveAllStmt(n) => {
+            n.roles.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::SecLabelStmt(n) => {
+            if let Some(n) = &n.object {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CreateForeignTableStmt(n) => {
+            if let Some(n) = &n.base_stmt {
+                children.push(n.to_ref());
+            }
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::ImportForeignSchemaStmt(n) => {
+            n.table_list
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateExtensionStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterExtensionStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterExtensionContentsStmt(n) => {
+            if let Some(n) = &n.object {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CreateEventTrigStmt(n) => {
+            n.whenclause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.funcname.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::RefreshMatViewStmt(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::AlterSystemStmt(n) => {
+            if let Some(n) = &n.setstmt {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::CreatePolicyStmt(n) => {
+            if let Some(n) = &n.table {
+                children.push(n.to_ref());
+            }
+            n.roles.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.qual {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.with_check {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::AlterPolicyStmt(n) => {
+            if let Some(n) = &n.table {
+                children.push(n.to_ref());
+            }
+            n.roles.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.qual {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.with_check {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CreateTransformStmt(n) => {
+            if let Some(n) = &n.type_name {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.fromsql {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.tosql {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::CreateAmStmt(n) => {
+            n.handler_name
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreatePublicationStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+            n.tables.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterPublicationStmt(n) => {
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+            n.tables.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateSubscriptionStmt(n) => {
+            n.publication
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterSubscriptionStmt(n) => {
+            n.publication
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateStatsStmt(n) => {
+            n.defnames.iter().for_each(|n| push_node(&mut children, n));
+            n.stat_types
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.exprs.iter().for_each(|n| push_node(&mut children, n));
+            n.relations.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AlterCollationStmt(n) => {
+            n.collname.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CallStmt(n) => {
+            if let Some(n) = &n.funccall {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.funcexpr {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::AlterStatsStmt(n) => {
+            n.defnames.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AExpr(n) => {
+            n.name.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.lexpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.rexpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::ColumnRef(n) => {
+            n.fields.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AConst(n) => {
+            if let Some(n) = &n.val {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::FuncCall(n) => {
+            n.funcname.iter().for_each(|n| push_node(&mut children, n));
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+            n.agg_order.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.agg_filter {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.over {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::AIndices(n) => {
+            if let Some(n) = &n.lidx {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.uidx {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::AIndirection(n) => {
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+            n.indirection
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AArrayExpr(n) => {
+            n.elements.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::ResTarget(n) => {
+            n.indirection
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.val {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::MultiAssignRef(n) => {
+            if let Some(n) = &n.source {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::TypeCast(n) => {
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.type_name {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::CollateClause(n) => {
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+            n.collname.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::SortBy(n) => {
+            if let Some(n) = &n.node {
+                push_node(&mut children, n);
+            }
+            n.use_op.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::WindowDef(n) => {
+            n.partition_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.order_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.start_offset {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.end_offset {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::RangeSubselect(n) => {
+            if let Some(n) = &n.subquery {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.alias {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::RangeFunction(n) => {
+            n.functions.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.alias {
+                children.push(n.to_ref());
+            }
+            n.coldeflist
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::RangeTableSample(n) => {
+            if let Some(n) = &n.relation {
+                push_node(&mut children, n);
+            }
+            n.method.iter().for_each(|n| push_node(&mut children, n));
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.repeatable {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::RangeTableFunc(n) => {
+            if let Some(n) = &n.docexpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.rowexpr {
+                push_node(&mut children, n);
+            }
+            n.namespaces
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.columns.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.alias {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::RangeTableFuncCol(n) => {
+            if let Some(n) = &n.type_name {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.colexpr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.coldefexpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::TypeName(n) => {
+            n.names.iter().for_each(|n| push_node(&mut children, n));
+            n.typmods.iter().for_each(|n| push_node(&mut children, n));
+            n.array_bounds
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::ColumnDef(n) => {
+            if let Some(n) = &n.type_name {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.raw_default {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.cooked_default {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.identity_sequence {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.coll_clause {
+                children.push(n.to_ref());
+            }
+            n.constraints
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.fdwoptions
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::IndexElem(n) => {
+            if let Some(n) = &n.expr {
+                push_node(&mut children, n);
+            }
+            n.collation.iter().for_each(|n| push_node(&mut children, n));
+            n.opclass.iter().for_each(|n| push_node(&mut children, n));
+            n.opclassopts
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::Constraint(n) => {
+            if let Some(n) = &n.raw_expr {
+                push_node(&mut children, n);
+            }
+            n.keys.iter().for_each(|n| push_node(&mut children, n));
+            n.including.iter().for_each(|n| push_node(&mut children, n));
+            n.exclusions
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.options.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.where_clause {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.pktable {
+                children.push(n.to_ref());
+            }
+            n.fk_attrs.iter().for_each(|n| push_node(&mut children, n));
+            n.pk_attrs.iter().for_each(|n| push_node(&mut children, n));
+            n.old_conpfeqop
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::DefElem(n) => {
+            if let Some(n) = &n.arg {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::RangeTblEntry(n) => {
+            if let Some(n) = &n.tablesample {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.subquery {
+                children.push(n.to_ref());
+            }
+            n.joinaliasvars
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.joinleftcols
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.joinrightcols
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.functions.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.tablefunc {
+                children.push(n.to_ref());
+            }
+            n.values_lists
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.coltypes.iter().for_each(|n| push_node(&mut children, n));
+            n.coltypmods
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.colcollations
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.alias {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.eref {
+                children.push(n.to_ref());
+            }
+            n.security_quals
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::RangeTblFunction(n) => {
+            if let Some(n) = &n.funcexpr {
+                push_node(&mut children, n);
+            }
+            n.funccolnames
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.funccoltypes
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.funccoltypmods
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.funccolcollations
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::TableSampleClause(n) => {
+            n.args.iter().for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.repeatable {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::WithCheckOption(n) => {
+            if let Some(n) = &n.qual {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::GroupingSet(n) => {
+            n.content.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::WindowClause(n) => {
+            n.partition_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.order_clause
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.start_offset {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.end_offset {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::ObjectWithArgs(n) => {
+            n.objname.iter().for_each(|n| push_node(&mut children, n));
+            n.objargs.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::AccessPriv(n) => {
+            n.cols.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::CreateOpClassItem(n) => {
+            if let Some(n) = &n.name {
+                children.push(n.to_ref());
+            }
+            n.order_family
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.class_args
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.storedtype {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::TableLikeClause(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::FunctionParameter(n) => {
+            if let Some(n) = &n.arg_type {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.defexpr {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::LockingClause(n) => {
+            n.locked_rels
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::XmlSerialize(n) => {
+            if let Some(n) = &n.expr {
+                push_node(&mut children, n);
+            }
+            if let Some(n) = &n.type_name {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::WithClause(n) => {
+            n.ctes.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::InferClause(n) => {
+            n.index_elems
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.where_clause {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::OnConflictClause(n) => {
+            if let Some(n) = &n.infer {
+                children.push(n.to_ref());
+            }
+            n.target_list
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.where_clause {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::CommonTableExpr(n) => {
+            n.aliascolnames
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            if let Some(n) = &n.ctequery {
+                push_node(&mut children, n);
+            }
+            n.ctecolnames
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.ctecoltypes
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.ctecoltypmods
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.ctecolcollations
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::PartitionElem(n) => {
+            if let Some(n) = &n.expr {
+                push_node(&mut children, n);
+            }
+            n.collation.iter().for_each(|n| push_node(&mut children, n));
+            n.opclass.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::PartitionSpec(n) => {
+            n.part_params
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::PartitionBoundSpec(n) => {
+            n.listdatums
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.lowerdatums
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+            n.upperdatums
+                .iter()
+                .for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::PartitionRangeDatum(n) => {
+            if let Some(n) = &n.value {
+                push_node(&mut children, n);
+            }
+        }
+        NodeRef::PartitionCmd(n) => {
+            if let Some(n) = &n.name {
+                children.push(n.to_ref());
+            }
+            if let Some(n) = &n.bound {
+                children.push(n.to_ref());
+            }
+        }
+        NodeRef::VacuumRelation(n) => {
+            if let Some(n) = &n.relation {
+                children.push(n.to_ref());
+            }
+            n.va_cols.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::List(n) => {
+            n.items.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::IntList(n) => {
+            n.items.iter().for_each(|n| push_node(&mut children, n));
+        }
+        NodeRef::OidList(n) => {
+            n.items.iter().for_each(|n| push_node(&mut children, n));
+        }
+        _ => (),
+    }
+    children
+}
+
+fn push_node<'a>(children: &mut Vec<NodeRef<'a>>, node: &'a Node) {
+    if let Some(n) = node.node.as_ref() {
+        children.push(n.to_ref());
+    }
+}
+
+/// Returns `node` and all its descendants in breadth-first order, together with their depth
+///
+/// The depth of `node` itself is 1, which matches the depth of a statement returned by `nodes()` of pg_query.rs.
+pub fn get_nodes(node: NodeRef) -> Vec<(NodeRef, i32)> {
+    let mut nodes = vec![(node, 1)];
+    let mut idx = 0;
+    while idx < nodes.len() {
+        let (node, depth) = nodes[idx];
+        nodes.extend(get_children(node).into_iter().map(|n| (n, depth + 1)));
+        idx += 1;
+    }
+    nodes
+}
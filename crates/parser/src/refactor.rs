@@ -0,0 +1,242 @@
+//! Refactorings that rewrite the tokens of a statement.
+//!
+//! Unlike the transformations of the `format` module, refactorings change the content of tokens,
+//! e.g. to replace literals with parameters.
+
+use cstree::text::TextRange;
+use pg_query::{NodeEnum, NodeRef};
+
+use crate::ast::{
+    clause_context, non_trivia_tokens, parse_pg_query_stmt, syntax_node_for, values_rows,
+    ClauseContext,
+};
+use crate::parser::Parser;
+use crate::pg_query_utils::get_nodes;
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::SyntaxNode;
+
+/// Returns the ranges and values of all string, numeric and boolean literals in `WHERE` clauses
+/// and `VALUES` lists of `stmt`, ordered by their position
+///
+/// The values are decoded, e.g. the value of `'it''s'` is `it's`. The range of a negative number
+/// includes its sign. `NULL` is not a literal value and is ignored, and so are literals with a
+/// prefix type like `date '2020-01-01'`, because `date $1` is not valid syntax, and the modifiers
+/// of type names like the `10` of `varchar(10)`.
+pub fn extractable_literals(stmt: &SyntaxNode) -> Vec<(TextRange, String)> {
+    let ast = match parse_pg_query_stmt(stmt) {
+        Some(ast) => ast,
+        None => return Vec::new(),
+    };
+    let rows = values_rows(stmt)
+        .unwrap_or_default()
+        .into_iter()
+        .flatten()
+        .map(|n| n.text_range())
+        .collect::<Vec<_>>();
+
+    let nodes = get_nodes(ast.to_ref());
+    // the type name of a prefix cast precedes its literal, unlike the one of a `::` cast, and the
+    // implicit cast of a boolean has no type name location. The modifiers of a type name, e.g.
+    // the `10` of `varchar(10)`, are constants too, but no parameter can take their place.
+    let type_literals = nodes
+        .iter()
+        .flat_map(|(node, _)| match node {
+            NodeRef::TypeCast(cast) => match cast.arg.as_ref().and_then(|a| a.node.as_ref()) {
+                Some(NodeEnum::AConst(c))
+                    if cast
+                        .type_name
+                        .as_ref()
+                        .is_some_and(|t| (0..c.location).contains(&t.location)) =>
+                {
+                    vec![c.location]
+                }
+                _ => Vec::new(),
+            },
+            NodeRef::TypeName(t) => t
+                .typmods
+                .iter()
+                .filter_map(|m| match m.node.as_ref()? {
+                    NodeEnum::AConst(c) => Some(c.location),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut literals = nodes
+        .into_iter()
+        .filter_map(|(node, _)| {
+            let value = match node {
+                NodeRef::AConst(c) if !type_literals.contains(&c.location) => {
+                    c.val.as_ref()?.node.as_ref()?
+                }
+                _ => return None,
+            };
+            let syntax = syntax_node_for(stmt, &node)?;
+            let (range, token_kind) = literal_range(syntax)?;
+            if clause_context(syntax) != ClauseContext::WhereClause
+                && !rows.iter().any(|r| r.contains_range(range))
+            {
+                return None;
+            }
+
+            let value = match (value, token_kind) {
+                (_, SyntaxKind::TrueP) => "true".to_string(),
+                (_, SyntaxKind::FalseP) => "false".to_string(),
+                (NodeEnum::Integer(i), _) => i.ival.to_string(),
+                (NodeEnum::Float(f), _) => f.str.clone(),
+                (NodeEnum::String(s), SyntaxKind::Sconst | SyntaxKind::Usconst) => s.str.clone(),
+                _ => return None,
+            };
+            Some((range, value))
+        })
+        .collect::<Vec<_>>();
+    literals.sort_by_key(|(range, _)| range.start());
+    literals.dedup_by_key(|(range, _)| *range);
+    literals
+}
+
+/// Returns the range of the literal of the `AConst` node `node`, and the kind of its token
+///
+/// The node may contain trailing tokens that do not belong to the literal, e.g. a `::` of a cast.
+fn literal_range(node: &SyntaxNode) -> Option<(TextRange, SyntaxKind)> {
+    let mut start = None;
+    for token in non_trivia_tokens(node) {
+        match token.kind() {
+            SyntaxKind::ArithmeticOp if start.is_none() => start = Some(token.text_range()),
+            SyntaxKind::Iconst
+            | SyntaxKind::Fconst
+            | SyntaxKind::Sconst
+            | SyntaxKind::Usconst
+            | SyntaxKind::TrueP
+            | SyntaxKind::FalseP => {
+                let range = start
+                    .unwrap_or(token.text_range())
+                    .cover(token.text_range());
+                return Some((range, token.kind()));
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Replaces the literals of the statement `stmt_text` with parameters, and returns the new text
+/// together with the values of the parameters
+///
+/// The literals are the ones of `extractable_literals`. Parameters are numbered in the order of
+/// the literals, after the parameters that the statement already uses.
+pub fn parameterize(stmt_text: &str) -> (String, Vec<String>) {
+    let mut parser = Parser::new();
+    parser.parse_statement(stmt_text, None);
+    let parsed = parser.finish();
+
+    let used = parsed
+        .cst
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .filter(|t| t.kind() == SyntaxKind::Param)
+        .filter_map(|t| {
+            t.resolved()
+                .text()
+                .trim_start_matches('$')
+                .parse::<usize>()
+                .ok()
+        })
+        .max()
+        .unwrap_or(0);
+
+    let literals = extractable_literals(&parsed.cst);
+    let mut result = String::with_capacity(stmt_text.len());
+    let mut pos = 0;
+    for (idx, (range, _)) in literals.iter().enumerate() {
+        result.push_str(&stmt_text[pos..usize::from(range.start())]);
+        result.push_str(&format!("${}", used + idx + 1));
+        pos = usize::from(range.end());
+    }
+    result.push_str(&stmt_text[pos..]);
+
+    (
+        result,
+        literals.into_iter().map(|(_, value)| value).collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameterize() {
+        assert_eq!(
+            parameterize("select * from users where id = 5 and name = 'x';"),
+            (
+                "select * from users where id = $1 and name = $2;".to_string(),
+                vec!["5".to_string(), "x".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parameterize_in_lists_and_values() {
+        assert_eq!(
+            parameterize(
+                "select 1 from t where a = $1 and b in ('it''s', -2.5) and c = true and d = '2020-01-01'::date;"
+            ),
+            (
+                "select 1 from t where a = $1 and b in ($2, $3) and c = $4 and d = $5::date;"
+                    .to_string(),
+                vec![
+                    "it's".to_string(),
+                    "-2.5".to_string(),
+                    "true".to_string(),
+                    "2020-01-01".to_string()
+                ]
+            )
+        );
+        assert_eq!(
+            parameterize("insert into t (a, b) values (1, null);"),
+            (
+                "insert into t (a, b) values ($1, null);".to_string(),
+                vec!["1".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parameterize_prefix_casts() {
+        assert_eq!(
+            parameterize("select 1 from t where d = date '2020-01-01' and n = 1;"),
+            (
+                "select 1 from t where d = date '2020-01-01' and n = $1;".to_string(),
+                vec!["1".to_string()]
+            )
+        );
+        assert_eq!(
+            parameterize("select 1 from t where d > now() - interval '1 day';"),
+            (
+                "select 1 from t where d > now() - interval '1 day';".to_string(),
+                Vec::new()
+            )
+        );
+    }
+
+    #[test]
+    fn test_parameterize_type_modifiers() {
+        assert_eq!(
+            parameterize("select 1 from t where x::varchar(10) = 'a';"),
+            (
+                "select 1 from t where x::varchar(10) = $1;".to_string(),
+                vec!["a".to_string()]
+            )
+        );
+        assert_eq!(
+            parameterize("select 1 from t where x = cast(1.5 as numeric(4, 2));"),
+            (
+                "select 1 from t where x = cast($1 as numeric(4, 2));".to_string(),
+                vec!["1.5".to_string()]
+            )
+        );
+    }
+}
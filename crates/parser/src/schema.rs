@@ -0,0 +1,144 @@
+//! Information about the objects of a database.
+//!
+//! Schema-aware features, e.g. completion and some lints, accept a `&dyn SchemaProvider` to look
+//! up the objects that statements refer to. `StaticSchema` is an in-memory provider, and
+//! connecting to a live database only requires another implementation of the trait.
+
+use std::fmt;
+
+/// The schema of objects with unqualified names
+pub const DEFAULT_SCHEMA: &str = "public";
+
+/// The name of a database object, qualified with its schema if specified
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct QualifiedName {
+    pub schema: Option<String>,
+    pub name: String,
+}
+
+impl QualifiedName {
+    pub fn new(schema: Option<&str>, name: &str) -> Self {
+        Self {
+            schema: schema.map(|s| s.to_string()),
+            name: name.to_string(),
+        }
+    }
+
+    /// Parses a name like `public.users` or `users`
+    pub fn parse(name: &str) -> Self {
+        match name.split_once('.') {
+            Some((schema, name)) => Self::new(Some(schema), name),
+            None => Self::new(None, name),
+        }
+    }
+
+    /// The schema of the name, or `DEFAULT_SCHEMA` if it is unqualified
+    pub fn schema_or_default(&self) -> &str {
+        self.schema.as_deref().unwrap_or(DEFAULT_SCHEMA)
+    }
+}
+
+impl fmt::Display for QualifiedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.schema {
+            Some(schema) => write!(f, "{}.{}", schema, self.name),
+            None => self.name.fmt(f),
+        }
+    }
+}
+
+/// A column of a table
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ColumnInfo {
+    pub name: String,
+    /// The name of the type of the column, e.g. `int4`
+    pub type_name: String,
+}
+
+/// A function
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FunctionInfo {
+    pub name: QualifiedName,
+    /// The names of the types of the arguments
+    pub arguments: Vec<String>,
+    pub return_type: String,
+}
+
+/// Provides the objects of a database
+pub trait SchemaProvider {
+    /// Returns the names of the tables in `schema`
+    fn tables(&self, schema: &str) -> Vec<String>;
+
+    /// Returns the columns of `table`, or an empty list if the table is unknown
+    ///
+    /// Unqualified names refer to tables in `DEFAULT_SCHEMA`.
+    fn columns(&self, table: &QualifiedName) -> Vec<ColumnInfo>;
+
+    /// Returns all functions
+    fn functions(&self) -> Vec<FunctionInfo>;
+}
+
+/// A `SchemaProvider` for a fixed set of objects
+///
+/// Unqualified names of tables are added to `DEFAULT_SCHEMA`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StaticSchema {
+    tables: Vec<(QualifiedName, Vec<ColumnInfo>)>,
+    functions: Vec<FunctionInfo>,
+}
+
+impl StaticSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a table with the name `name`, e.g. `public.users`, and the columns `columns` given as
+    /// pairs of a name and a type name
+    pub fn with_table(mut self, name: &str, columns: &[(&str, &str)]) -> Self {
+        let name = QualifiedName::parse(name);
+        let name = QualifiedName::new(Some(name.schema_or_default()), &name.name);
+        let columns = columns
+            .iter()
+            .map(|(name, type_name)| ColumnInfo {
+                name: name.to_string(),
+                type_name: type_name.to_string(),
+            })
+            .collect();
+        self.tables.push((name, columns));
+        self
+    }
+
+    /// Adds a function with the name `name`, e.g. `lower`
+    pub fn with_function(mut self, name: &str, arguments: &[&str], return_type: &str) -> Self {
+        self.functions.push(FunctionInfo {
+            name: QualifiedName::parse(name),
+            arguments: arguments.iter().map(|a| a.to_string()).collect(),
+            return_type: return_type.to_string(),
+        });
+        self
+    }
+}
+
+impl SchemaProvider for StaticSchema {
+    fn tables(&self, schema: &str) -> Vec<String> {
+        self.tables
+            .iter()
+            .filter(|(name, _)| name.schema_or_default() == schema)
+            .map(|(name, _)| name.name.clone())
+            .collect()
+    }
+
+    fn columns(&self, table: &QualifiedName) -> Vec<ColumnInfo> {
+        self.tables
+            .iter()
+            .find(|(name, _)| {
+                name.name == table.name && name.schema_or_default() == table.schema_or_default()
+            })
+            .map(|(_, columns)| columns.clone())
+            .unwrap_or_default()
+    }
+
+    fn functions(&self) -> Vec<FunctionInfo> {
+        self.functions.clone()
+    }
+}
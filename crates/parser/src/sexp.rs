@@ -0,0 +1,56 @@
+use std::fmt::Write;
+
+use cstree::util::NodeOrToken;
+
+use crate::syntax_node::SyntaxNode;
+
+/// Renders the tree `root` as an s-expression of its kinds, e.g. `(SelectStmt Select (ResTarget ...))`
+///
+/// Nodes are rendered as a list of their kind and children, and tokens by their kind only. The text
+/// is omitted, which makes it useful to assert the structure of a tree independent of its exact bytes.
+/// Trivia tokens are only included if `with_trivia` is true.
+pub fn to_sexp(root: &SyntaxNode, with_trivia: bool) -> String {
+    let mut sexp = String::new();
+    write_sexp(root, with_trivia, &mut sexp);
+    sexp
+}
+
+fn write_sexp(node: &SyntaxNode, with_trivia: bool, sexp: &mut String) {
+    write!(sexp, "({:?}", node.kind()).unwrap();
+    for child in node.children_with_tokens() {
+        match child {
+            NodeOrToken::Node(n) => {
+                sexp.push(' ');
+                write_sexp(n, with_trivia, sexp);
+            }
+            NodeOrToken::Token(t) => {
+                if with_trivia || !t.kind().is_trivia() {
+                    write!(sexp, " {:?}", t.kind()).unwrap();
+                }
+            }
+        }
+    }
+    sexp.push(')');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_to_sexp() {
+        let mut parser = Parser::new();
+        parser.parse_statement("select 1;", None);
+        let parsed = parser.finish();
+
+        assert_eq!(
+            to_sexp(&parsed.cst, false),
+            "(SelectStmt Select (ResTarget (AConst Iconst)) Ascii59)"
+        );
+        assert_eq!(
+            to_sexp(&parsed.cst, true),
+            "(SelectStmt Select Whitespace (ResTarget (AConst Iconst)) Ascii59)"
+        );
+    }
+}
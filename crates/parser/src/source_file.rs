@@ -1,4 +1,7 @@
+use std::ops::Range;
+
 use logos::Logos;
+use pg_query::NodeEnum;
 
 use crate::{parser::Parser, syntax_kind::SyntaxKind};
 
@@ -10,43 +13,247 @@ use crate::{parser::Parser, syntax_kind::SyntaxKind};
 ///
 /// This regex-based lexer does the split.
 #[derive(Logos, Debug, PartialEq)]
-#[logos(skip r"[ \t\f]+")] // Ignore this regex pattern between tokens
 pub enum SourceFileToken {
     #[regex("[a-zA-Z0-9_]+(?:'[^']*'|(?:\\$\\$[^$]*\\$\\$|[^';])+)*;"gm)]
     Statement,
     #[regex("\n+"gm)]
     Newline,
+    #[regex(r"[ \t\f]+")]
+    Whitespace,
     #[regex("/\\*[^*]*\\*+(?:[^/*][^*]*\\*+)*/|--[^\n]*"g)]
     Comment,
+    /// A `;` that does not terminate a statement, e.g. the second one of `select 1;;`
+    #[token(";")]
+    EmptyStatement,
+    /// A byte order mark, which is only recognised at the start of a source file
+    Bom,
+    /// A `#!` line, which is only recognised at the start of a source file, after a byte order
+    /// mark if any
+    Shebang,
+    /// The inline data of a `COPY ... FROM STDIN`, which is only recognised after such a statement
+    CopyData,
+}
+
+/// Splits a source file into its tokens, and falls back to a statement up to the next semicolon
+/// or the end of the file for text that `SourceFileToken` does not recognise
+///
+/// Yields every token along with its span in the source file.
+pub(crate) struct SourceFileSplitter<'a> {
+    text: &'a str,
+    offset: usize,
+    lexer: logos::Lexer<'a, SourceFileToken>,
+    /// The tokens to yield before lexing on, in reverse, e.g. the byte order mark and shebang
+    /// line at the start of the source file
+    pending: Vec<(SourceFileToken, Range<usize>)>,
+}
+
+impl<'a> SourceFileSplitter<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        let mut pending = Vec::new();
+        let mut offset = 0;
+        if text.starts_with('\u{feff}') {
+            offset = '\u{feff}'.len_utf8();
+            pending.push((SourceFileToken::Bom, 0..offset));
+        }
+        if text[offset..].starts_with("#!") {
+            let end = text[offset..]
+                .find('\n')
+                .map_or(text.len(), |idx| offset + idx);
+            pending.push((SourceFileToken::Shebang, offset..end));
+            offset = end;
+        }
+        pending.reverse();
+
+        Self {
+            text,
+            offset,
+            lexer: SourceFileToken::lexer(&text[offset..]),
+            pending,
+        }
+    }
+}
+
+impl Iterator for SourceFileSplitter<'_> {
+    type Item = (SourceFileToken, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(token) = self.pending.pop() {
+            return Some(token);
+        }
+        let token = self.lexer.next()?;
+        let start = self.offset + self.lexer.span().start;
+        let span = match token {
+            Ok(token) => {
+                let span = start..self.offset + self.lexer.span().end;
+                if token != SourceFileToken::Statement {
+                    return Some((token, span));
+                }
+                span
+            }
+            Err(_) => {
+                let end =
+                    statement_end(&self.text[start..]).map_or(self.text.len(), |idx| start + idx);
+
+                // continue after the statement
+                self.offset = end;
+                self.lexer = SourceFileToken::lexer(&self.text[end..]);
+                start..end
+            }
+        };
+        if is_copy_from_stdin(&self.text[span.clone()]) {
+            self.split_copy_data(span.end);
+        }
+        Some((SourceFileToken::Statement, span))
+    }
+}
+
+/// Returns the end of the statement at the start of `text`, right after its first `;` that is
+/// not within a string, a quoted identifier or a dollar quoted string
+///
+/// Returns `None` if the statement does not end, or a quoted span is not terminated.
+fn statement_end(text: &str) -> Option<usize> {
+    let mut idx = 0;
+    while idx < text.len() {
+        match text.as_bytes()[idx] {
+            b';' => return Some(idx + 1),
+            // an escaped quote, e.g. the `''` of `'it''s'`, ends the span and starts another one
+            quote @ (b'\'' | b'"') => {
+                idx += 1 + text[idx + 1..].find(quote as char)?;
+            }
+            b'$' => {
+                if let Some(len) = dollar_tag_len(&text[idx..]) {
+                    let tag = &text[idx..idx + len];
+                    idx += len + text[idx + len..].find(tag)? + len - 1;
+                }
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    None
+}
+
+/// Returns the length of the dollar quote tag at the start of `text`, e.g. of `$$` or `$body$`
+///
+/// A parameter like `$1` is not a tag, since a tag cannot start with a digit.
+fn dollar_tag_len(text: &str) -> Option<usize> {
+    let name_len = text[1..]
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(text.len() - 1);
+    let name = &text[1..1 + name_len];
+    if name.starts_with(|c: char| c.is_ascii_digit()) || !text[1 + name_len..].starts_with('$') {
+        return None;
+    }
+    Some(name_len + 2)
+}
+
+impl SourceFileSplitter<'_> {
+    /// Splits the inline data that starts on the line after a `COPY ... FROM STDIN` ending at
+    /// `end`, and continues lexing after it
+    ///
+    /// The data ends with a `\.` line, or at the end of the file. Nothing is split if the
+    /// statement is followed by more than whitespace on its line.
+    fn split_copy_data(&mut self, end: usize) {
+        let line_end = match self.text[end..].find('\n') {
+            Some(idx) => end + idx + 1,
+            None => return,
+        };
+        if !self.text[end..line_end].trim().is_empty() {
+            return;
+        }
+
+        let mut data_end = self.text.len();
+        let mut line_start = line_end;
+        while line_start < self.text.len() {
+            let line = self.text[line_start..]
+                .split('\n')
+                .next()
+                .unwrap_or_default();
+            if line.trim_end_matches('\r') == "\\." {
+                data_end = line_start + line.trim_end_matches('\r').len();
+                break;
+            }
+            line_start += line.len() + 1;
+        }
+
+        // the whitespace and newline of the rest of the line of the statement
+        let mut tokens = Vec::new();
+        let mut trivia = SourceFileToken::lexer(&self.text[end..line_end]);
+        while let Some(Ok(token)) = trivia.next() {
+            tokens.push((token, end + trivia.span().start..end + trivia.span().end));
+        }
+        tokens.push((SourceFileToken::CopyData, line_end..data_end));
+        self.pending.extend(tokens.into_iter().rev());
+
+        self.offset = data_end;
+        self.lexer = SourceFileToken::lexer(&self.text[data_end..]);
+    }
+}
+
+/// Whether the statement `text` is a `COPY ... FROM STDIN`, which is followed by its data
+///
+/// Only statements that start with `COPY` are parsed to check this.
+fn is_copy_from_stdin(text: &str) -> bool {
+    let is_copy = text
+        .trim_start()
+        .get(..4)
+        .is_some_and(|word| word.eq_ignore_ascii_case("copy"));
+    if !is_copy {
+        return false;
+    }
+    let stmt = pg_query::parse(text)
+        .ok()
+        .and_then(|p| p.protobuf.stmts.into_iter().next())
+        .and_then(|s| s.stmt)
+        .and_then(|s| s.node);
+    // the file name of `STDIN` is empty
+    matches!(stmt, Some(NodeEnum::CopyStmt(copy)) if copy.is_from && copy.filename.is_empty())
 }
 
 impl Parser {
     /// Parse a source file
     ///
+    /// Text that the lexer does not recognise as a statement, e.g. a last statement without a
+    /// terminating semicolon, is parsed as a statement up to the next semicolon or the end of the file.
+    ///
     /// TODO: rename to `parse_source_at(text: &str, at: Option<u32>)`, and allow parsing substatements, e.g. bodies of create
     /// function statements.
     pub fn parse_source_file(&mut self, text: &str) {
-        let mut lexer = SourceFileToken::lexer(text);
-
         self.start_node_at(SyntaxKind::SourceFile, Some(0));
-        while let Some(token) = lexer.next() {
+        for (token, span) in SourceFileSplitter::new(text) {
+            let slice = &text[span.clone()];
             match token {
-                Ok(token) => {
-                    match token {
-                        SourceFileToken::Comment => {
-                            self.token(SyntaxKind::Comment, lexer.slice());
-                        }
-                        SourceFileToken::Newline => {
-                            self.token(SyntaxKind::Newline, lexer.slice());
-                        }
-                        SourceFileToken::Statement => {
-                            self.parse_statement(lexer.slice(), Some(lexer.span().start as u32));
-                        }
-                    };
+                SourceFileToken::Comment => {
+                    self.token(SyntaxKind::Comment, slice);
                 }
-                Err(_) => panic!("Unknown SourceFileToken: {:?}", lexer.span()),
-            }
+                SourceFileToken::Newline => {
+                    self.token(SyntaxKind::Newline, slice);
+                }
+                SourceFileToken::Whitespace => {
+                    self.token(SyntaxKind::Whitespace, slice);
+                }
+                SourceFileToken::Bom => {
+                    self.token(SyntaxKind::Bom, slice);
+                }
+                SourceFileToken::Shebang => {
+                    self.token(SyntaxKind::Shebang, slice);
+                }
+                SourceFileToken::CopyData => {
+                    self.token(SyntaxKind::CopyData, slice);
+                }
+                SourceFileToken::EmptyStatement => {
+                    self.start_node_at(SyntaxKind::EmptyStatement, Some(1));
+                    self.set_checkpoint(true);
+                    self.token(SyntaxKind::Ascii59, slice);
+                    self.close_checkpoint();
+                }
+                SourceFileToken::Statement => {
+                    self.parse_statement(slice, Some(span.start as u32));
+                }
+            };
         }
+        // trailing newlines and comments are still buffered
+        self.consume_token_buffer();
         self.finish_node();
     }
 }
@@ -59,7 +266,7 @@ mod tests {
     fn test_source_file_lexer() {
         let input = "select * from contact where id = '123';\n\n-- test comment\n\nselect wrong statement;\n\nselect id,username from contact\n\nselect id,name\nfrom contact -- test inline comment\nwhere id = '123';\n\n";
 
-        let mut lex = SourceFileToken::lexer(&input);
+        let mut lex = SourceFileToken::lexer(input);
 
         assert_eq!(lex.next(), Some(Ok(SourceFileToken::Statement)));
         assert_eq!(lex.slice(), "select * from contact where id = '123';");
@@ -105,4 +312,121 @@ select 1;
 
         assert_eq!(parsed.cst.text(), input);
     }
+
+    #[test]
+    fn test_source_file_parser_without_semicolon() {
+        let input = "select 1;  ;\n(select 2);\nselect 3";
+
+        let mut parser = Parser::new();
+        parser.parse_source_file(input);
+        let parsed = parser.finish();
+
+        assert_eq!(parsed.cst.text(), input);
+        assert_eq!(parsed.stmts.len(), 3);
+    }
+
+    #[test]
+    fn test_fallback_statement_end() {
+        let input = "(select 'a;b', \"c;\", $$d;$$, $x$e;$x$, $1);\n(select 'f'';');\nselect 1;";
+        let mut parser = Parser::new();
+        parser.parse_source_file(input);
+        let parsed = parser.finish();
+
+        assert_eq!(parsed.cst.text(), input);
+        assert_eq!(
+            parsed
+                .cst
+                .children()
+                .map(|n| n.text().to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "(select 'a;b', \"c;\", $$d;$$, $x$e;$x$, $1);",
+                "(select 'f'';');",
+                "select 1;"
+            ]
+        );
+        assert_eq!(statement_end("(select 'a;"), None);
+    }
+
+    #[test]
+    fn test_empty_statements() {
+        let kinds = |input: &str| {
+            let mut parser = Parser::new();
+            parser.parse_source_file(input);
+            let parsed = parser.finish();
+            assert_eq!(parsed.cst.text(), input);
+            parsed.cst.children().map(|n| n.kind()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            kinds("select 1;;select 2;"),
+            vec![
+                SyntaxKind::SelectStmt,
+                SyntaxKind::EmptyStatement,
+                SyntaxKind::SelectStmt
+            ]
+        );
+        assert_eq!(
+            kinds("; select 1;"),
+            vec![SyntaxKind::EmptyStatement, SyntaxKind::SelectStmt]
+        );
+    }
+
+    #[test]
+    fn test_bom_and_shebang() {
+        let parse = |input: &str| {
+            let mut parser = Parser::new();
+            parser.parse_source_file(input);
+            let parsed = parser.finish();
+            assert_eq!(parsed.cst.text(), input);
+            assert!(parsed.errors.is_empty());
+            parsed
+        };
+
+        let input = "\u{feff}select 1;";
+        let parsed = parse(input);
+        let first = parsed.cst.first_token().unwrap();
+        assert_eq!(first.kind(), SyntaxKind::Bom);
+        assert!(first.kind().is_trivia());
+        let stmt = parsed.cst.children().next().unwrap();
+        assert_eq!(&input[stmt.text_range()], "select 1;");
+
+        let input = "#!/usr/bin/env psql -f\nselect 1;\nselect 2;";
+        let parsed = parse(input);
+        let first = parsed.cst.first_token().unwrap();
+        assert_eq!(first.kind(), SyntaxKind::Shebang);
+        assert_eq!(first.resolved().text(), "#!/usr/bin/env psql -f");
+        assert_eq!(parsed.stmts.len(), 2);
+        let stmt = parsed.cst.children().nth(1).unwrap();
+        assert_eq!(&input[stmt.text_range()], "select 2;");
+    }
+
+    #[test]
+    fn test_copy_data() {
+        let input = "copy t from stdin; \n1\t'a;\n\\.\nselect 1;\ncopy t from stdin;\n2\n";
+        let mut parser = Parser::new();
+        parser.parse_source_file(input);
+        let parsed = parser.finish();
+        assert_eq!(parsed.cst.text(), input);
+        assert!(parsed.errors.is_empty());
+        assert_eq!(parsed.stmts.len(), 3);
+
+        let data = parsed
+            .cst
+            .children_with_tokens()
+            .filter(|e| e.kind() == SyntaxKind::CopyData)
+            .map(|e| &input[e.text_range()])
+            .collect::<Vec<_>>();
+        assert_eq!(data, vec!["1\t'a;\n\\.", "2\n"]);
+    }
+
+    #[test]
+    fn test_is_copy_from_stdin() {
+        assert!(is_copy_from_stdin(
+            "COPY t (a, b) FROM STDIN WITH (FORMAT csv);"
+        ));
+        assert!(!is_copy_from_stdin("copy (select 'from stdin') to stdout;"));
+        assert!(!is_copy_from_stdin("copy t from program 'cat stdin';"));
+        assert!(!is_copy_from_stdin("select 'copy t from stdin';"));
+    }
 }
@@ -1,9 +1,15 @@
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
 use cstree::text::{TextRange, TextSize};
 use logos::Logos;
-use regex::Regex;
+use pg_query::protobuf;
 
 use crate::{
-    parser::Parser, pg_query_utils::get_position_for_pg_query_node, syntax_kind::SyntaxKind,
+    parser::Parser,
+    pg_query_utils::{get_nodes, get_position_for_pg_query_node},
+    syntax_kind::SyntaxKind,
 };
 
 /// A super simple lexer for sql statements.
@@ -52,15 +58,23 @@ pub enum StatementToken {
     Ascii93,
     #[token("^")]
     Ascii94,
+    /// A positional parameter such as `$1`
+    #[regex("\\$[0-9]+")]
+    Param,
     // comments, whitespaces and keywords
-    // FIXME: nexted and named dollar quoted strings do not work yet
-    #[regex("'([^']+)'|\\$(\\w)?\\$.*\\$(\\w)?\\$")]
+    /// A quoted string, either `'text'` or dollar quoted, e.g. `$$text$$` or `$body$text$body$`
+    ///
+    /// `$` followed by a digit always starts a positional parameter, because a dollar quote tag
+    /// cannot start with a digit. Hence, `$1$x$1$` is the parameter `$1` followed by the
+    /// unterminated dollar quote `$x$`.
+    #[regex("'([^']+)'")]
+    #[regex("\\$([A-Za-z_][A-Za-z0-9_]*)?\\$", dollar_quoted)]
     Sconst,
     #[regex("(\\w+)"gm)]
     Word,
     #[regex(" +"gm)]
     Whitespace,
-    #[regex("\n+"gm)]
+    #[regex("(\r?\n)+"gm)]
     Newline,
     #[regex("\t+"gm)]
     Tab,
@@ -68,37 +82,56 @@ pub enum StatementToken {
     Comment,
 }
 
+/// Lexes the body and the closing tag of a dollar quoted string, after its opening tag was
+/// lexed
+///
+/// The string ends at the first occurrence of the opening tag, so that other dollar quotes can be
+/// nested within it. Returns false if the string is not terminated.
+fn dollar_quoted(lexer: &mut logos::Lexer<StatementToken>) -> bool {
+    let tag = lexer.slice();
+    match lexer.remainder().find(tag) {
+        Some(idx) => {
+            lexer.bump(idx + tag.len());
+            true
+        }
+        None => false,
+    }
+}
+
 impl StatementToken {
     /// Creates a `SyntaxKind` from a `StatementToken`.
     /// can be generated.
+    ///
+    /// Operators are converted to the kind of their class, like the pg_query tokens of
+    /// `SyntaxKind::from_pg_query_token`.
     pub fn syntax_kind(&self) -> SyntaxKind {
         match self {
-            StatementToken::Ascii37 => SyntaxKind::Ascii37,
+            StatementToken::Ascii37 => SyntaxKind::ArithmeticOp,
             StatementToken::Ascii40 => SyntaxKind::Ascii40,
             StatementToken::Ascii41 => SyntaxKind::Ascii41,
             StatementToken::Ascii42 => SyntaxKind::Ascii42,
-            StatementToken::Ascii43 => SyntaxKind::Ascii43,
+            StatementToken::Ascii43 => SyntaxKind::ArithmeticOp,
             StatementToken::Ascii44 => SyntaxKind::Ascii44,
-            StatementToken::Ascii45 => SyntaxKind::Ascii45,
+            StatementToken::Ascii45 => SyntaxKind::ArithmeticOp,
             StatementToken::Ascii46 => SyntaxKind::Ascii46,
-            StatementToken::Ascii47 => SyntaxKind::Ascii47,
+            StatementToken::Ascii47 => SyntaxKind::ArithmeticOp,
             StatementToken::Ascii58 => SyntaxKind::Ascii58,
             StatementToken::Ascii59 => SyntaxKind::Ascii59,
-            StatementToken::Ascii60 => SyntaxKind::Ascii60,
-            StatementToken::Ascii61 => SyntaxKind::Ascii61,
-            StatementToken::Ascii62 => SyntaxKind::Ascii62,
+            StatementToken::Ascii60 => SyntaxKind::ComparisonOp,
+            StatementToken::Ascii61 => SyntaxKind::ComparisonOp,
+            StatementToken::Ascii62 => SyntaxKind::ComparisonOp,
             StatementToken::Ascii63 => SyntaxKind::Ascii63,
             StatementToken::Ascii91 => SyntaxKind::Ascii91,
             StatementToken::Ascii92 => SyntaxKind::Ascii92,
             StatementToken::Ascii93 => SyntaxKind::Ascii93,
-            StatementToken::Ascii94 => SyntaxKind::Ascii94,
+            StatementToken::Ascii94 => SyntaxKind::ArithmeticOp,
             StatementToken::Word => SyntaxKind::Word,
             StatementToken::Whitespace => SyntaxKind::Whitespace,
             StatementToken::Newline => SyntaxKind::Newline,
             StatementToken::Tab => SyntaxKind::Tab,
+            StatementToken::Param => SyntaxKind::Param,
             StatementToken::Sconst => SyntaxKind::Sconst,
             StatementToken::Comment => SyntaxKind::Comment,
-            _ => panic!("Unknown StatementToken: {:?}", self),
         }
     }
 }
@@ -108,59 +141,134 @@ impl Parser {
     ///
     /// On a high level, the algorithm works as follows:
     /// 1. Parse the statement with pg_query.rs and order nodes by their position. If the
-    ///   statement contains syntax errors, the parser will report the error and continue to work without information
-    ///   about the nodes. The result will be a flat list of tokens under the generic `Stmt` node.
-    ///   If successful, the first node in the ordered list will be the main node of the statement,
-    ///   and serves as a root node.
-    /// 2. Scan the statements for tokens with pg_query.rs. This will never fail, even if the statement contains syntax errors.
-    /// 3. Parse the statement with the `StatementToken` lexer. The lexer will be the main vehicle
-    ///    while walking the statement.
-    /// 4. Walk the statement with the `StatementToken` lexer.
-    ///    - at every token, consume all nodes that are within the token's range.
-    ///    - if there is a pg_query token within the token's range, consume it. if not, fallback to
-    ///    the StatementToken. This is the case for e.g. whitespace.
-    /// 5. Close all open nodes for that statement.
+    ///    statement contains syntax errors, the parser will report the error and continue to work without information
+    ///    about the nodes. The result will be a flat list of tokens under the generic `Stmt` node,
+    ///    or under an `Error` node if the statement does not start with a keyword, e.g. `selct`.
+    ///    If successful, the first node returned by pg_query.rs is the main node of the statement,
+    ///    and serves as a root node. Nodes without a location cannot be placed and are skipped.
+    /// 2. Scan the statement for tokens with pg_query.rs. This only fails on lexical errors such as
+    ///    an unterminated string literal.
+    /// 3. Walk the pg_query.rs tokens.
+    ///    - the text between two tokens is trivia (whitespace, newlines) and is lexed with the
+    ///      `StatementToken` lexer.
+    ///    - at every token, consume all nodes that start before the end of the token.
+    ///    - apply the token.
+    ///
+    ///    If scanning failed, walk the statement with the `StatementToken` lexer instead.
+    /// 4. Close all open nodes for that statement.
     pub fn parse_statement(&mut self, text: &str, at_offset: Option<u32>) {
+        let (parsed, scanned) = run_pg_query(text);
+        self.parse_statement_with(text, at_offset, parsed, scanned);
+    }
+
+    /// Parses a statement `text` like `parse_statement`, but gives up on pg_query.rs if parsing
+    /// and scanning take longer than `timeout`
+    ///
+    /// All pg_query.rs work is run on a worker thread, which is safe because libpg_query keeps
+    /// its state thread-local. If the timeout is exceeded, a syntax error is recorded and the
+    /// statement is lexed into a flat list of tokens, as if it contained syntax errors. The worker
+    /// thread cannot be cancelled: it keeps running in the background until pg_query.rs returns,
+    /// and its result is discarded, so every timeout leaks a thread for that long.
+    pub fn parse_statement_with_timeout(
+        &mut self,
+        text: &str,
+        at_offset: Option<u32>,
+        timeout: Duration,
+    ) {
+        let (sender, receiver) = mpsc::channel();
+        let owned_text = text.to_string();
+        thread::spawn(move || {
+            // the receiver is gone if the timeout was exceeded
+            let _ = sender.send(run_pg_query(&owned_text));
+        });
+
+        let (parsed, scanned) = match receiver.recv_timeout(timeout) {
+            Ok(result) => result,
+            // without scan tokens, the whole statement is lexed by the `StatementToken` lexer
+            Err(_) => (
+                Err(format!(
+                    "Parsing the statement timed out after {:?}",
+                    timeout
+                )),
+                Ok(Vec::new()),
+            ),
+        };
+        self.parse_statement_with(text, at_offset, parsed, scanned);
+    }
+
+    /// Builds the cst of a statement `text` from the results of parsing and scanning it with
+    /// pg_query.rs
+    fn parse_statement_with(
+        &mut self,
+        text: &str,
+        at_offset: Option<u32>,
+        parsed: Result<protobuf::ParseResult, String>,
+        scanned: Result<Vec<protobuf::ScanToken>, String>,
+    ) {
         let offset = at_offset.unwrap_or(0);
         let range = TextRange::new(
             TextSize::from(offset),
             TextSize::from(offset + text.len() as u32),
         );
 
-        let mut pg_query_tokens = match pg_query::scan(text) {
-            Ok(scanned) => scanned.tokens.into_iter().peekable(),
+        let pg_query_tokens = match scanned {
+            Ok(tokens) => Some(tokens),
             Err(e) => {
-                self.error(e.to_string(), range);
-                Vec::new().into_iter().peekable()
+                self.error(e, range);
+                None
             }
         };
 
-        let parsed = pg_query::parse(text);
         let proto;
-        let mut nodes;
+        let mut is_unknown = false;
         let mut pg_query_nodes = match parsed {
             Ok(parsed) => {
-                proto = parsed.protobuf;
-
-                nodes = proto.nodes();
-
-                nodes.sort_by(|a, b| {
-                    get_position_for_pg_query_node(&a.0).cmp(&get_position_for_pg_query_node(&b.0))
-                });
-
-                nodes.into_iter().peekable()
+                proto = parsed;
+
+                let mut nodes = proto
+                    .stmts
+                    .iter()
+                    .filter_map(|s| s.stmt.as_ref().and_then(|s| s.node.as_ref()))
+                    .flat_map(|n| get_nodes(n.to_ref()))
+                    .collect::<Vec<_>>();
+
+                // the first node is the statement itself and becomes the root, all other nodes
+                // are ordered by their position
+                let root = if nodes.is_empty() {
+                    None
+                } else {
+                    Some(nodes.remove(0))
+                };
+                nodes.retain(|n| get_position_for_pg_query_node(&n.0) >= 0);
+                nodes.sort_by_key(|n| get_position_for_pg_query_node(&n.0));
+
+                root.into_iter()
+                    .chain(nodes)
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .peekable()
             }
             Err(e) => {
-                self.error(e.to_string(), range);
+                let tokens = pg_query_tokens.as_deref().unwrap_or_default();
+                match unsupported_statement(text, tokens) {
+                    Some((message, keyword)) => {
+                        self.error(message, keyword + TextSize::from(offset))
+                    }
+                    None => match unknown_statement(text, tokens) {
+                        Some((message, keyword)) => {
+                            is_unknown = true;
+                            self.error(message, keyword + TextSize::from(offset))
+                        }
+                        None => self.error(e, range),
+                    },
+                }
                 Vec::new().into_iter().peekable()
             }
         };
 
-        let mut lexer = StatementToken::lexer(&text);
-
         // parse root node if no syntax errors
         if pg_query_nodes.peek().is_some() {
-            let (node, depth, _) = pg_query_nodes.next().unwrap();
+            let (node, depth) = pg_query_nodes.next().unwrap();
             self.stmt(node.to_enum(), range);
             self.start_node_at(SyntaxKind::from_pg_query_node(&node), Some(depth));
             // if there is only one node, there are no children, and we do not need to buffer the
@@ -168,69 +276,144 @@ impl Parser {
             self.set_checkpoint(pg_query_nodes.peek().is_none());
         } else {
             // fallback to generic node as root
-            self.start_node_at(SyntaxKind::Stmt, None);
+            let kind = if is_unknown {
+                SyntaxKind::Error
+            } else {
+                SyntaxKind::Stmt
+            };
+            self.start_node_at(kind, None);
             self.set_checkpoint(true);
         }
 
-        // FIXME: the lexer, for some reason, does not parse dollar quoted string
-        // so we check if the error is one
-        while let Some(token) = lexer.next() {
-            let t: Option<StatementToken> = match token {
-                Ok(token) => Some(token),
-                Err(_) => {
-                    if Regex::new("'([^']+)'|\\$(\\w)?\\$.*\\$(\\w)?\\$")
-                        .unwrap()
-                        .is_match_at(lexer.slice(), 0)
-                    {
-                        Some(StatementToken::Sconst)
-                    } else {
-                        None
-                    }
-                }
-            };
+        match pg_query_tokens {
+            Some(tokens) => {
+                let mut pos = 0;
+                for token in tokens.iter() {
+                    let start = usize::try_from(token.start).unwrap();
+                    let end = usize::try_from(token.end).unwrap();
 
-            match t {
-                Some(token) => {
-                    let span = lexer.span();
+                    // everything between two tokens is trivia
+                    self.lex_statement_tokens(&text[pos..start]);
 
-                    // consume pg_query nodes until there is none, or the node is outside of the current text span
+                    // consume pg_query nodes until there is none, or the node starts after the current token
                     while let Some(node) = pg_query_nodes.peek() {
-                        let pos = get_position_for_pg_query_node(&node.0);
-                        if span.contains(&usize::try_from(pos).unwrap()) == false {
+                        let node_pos =
+                            usize::try_from(get_position_for_pg_query_node(&node.0)).unwrap();
+                        if node_pos >= end {
                             break;
-                        } else {
-                            // node is within span
-                            let (node, depth, _) = pg_query_nodes.next().unwrap();
-                            self.start_node_at(SyntaxKind::from_pg_query_node(&node), Some(depth));
                         }
+                        let (node, depth) = pg_query_nodes.next().unwrap();
+                        self.start_node_at(SyntaxKind::from_pg_query_node(&node), Some(depth));
                     }
 
-                    // consume pg_query token if it is within the current text span
-                    let next_pg_query_token = pg_query_tokens.peek();
-                    if next_pg_query_token.is_some()
-                        && (span.contains(
-                            &usize::try_from(next_pg_query_token.unwrap().start).unwrap(),
-                        ) || span
-                            .contains(&usize::try_from(next_pg_query_token.unwrap().end).unwrap()))
-                    {
-                        // TODO: if within function declaration and current token is Sconst, its
-                        // the function body. it should be passed into parse_source_file.
-                        self.token(
-                            SyntaxKind::from_pg_query_token(&pg_query_tokens.next().unwrap()),
-                            lexer.slice(),
-                        );
-                    } else {
-                        // fallback to statement token
-                        self.token(token.syntax_kind(), lexer.slice());
-                    }
+                    // TODO: if within function declaration and current token is Sconst, its
+                    // the function body. it should be passed into parse_source_file.
+                    let kind = match SyntaxKind::from_pg_query_token(token, &text[start..end]) {
+                        // comments are trivia, just as they are on the source file level
+                        SyntaxKind::SqlComment | SyntaxKind::CComment => SyntaxKind::Comment,
+                        kind => kind,
+                    };
+                    self.token(kind, &text[start..end]);
+                    pos = end;
                 }
-                None => panic!("Unknown StatementToken: {:?}", lexer.slice()),
+                self.lex_statement_tokens(&text[pos..]);
             }
+            None => self.lex_statement_tokens(text),
         }
 
         // close up nodes
         self.close_checkpoint();
     }
+
+    /// Applies all tokens of `text` using the `StatementToken` lexer
+    ///
+    /// Used for the trivia between pg_query tokens, and as a fallback for the whole statement if
+    /// pg_query fails to scan it. Anything the lexer does not understand is applied as a `Word`,
+    /// so that the tree always contains the full text.
+    fn lex_statement_tokens(&mut self, text: &str) {
+        let mut lexer = StatementToken::lexer(text);
+        while let Some(token) = lexer.next() {
+            let kind = match token {
+                Ok(token) => token.syntax_kind(),
+                Err(_) => SyntaxKind::Word,
+            };
+            self.token(kind, lexer.slice());
+        }
+    }
+}
+
+/// Parses and scans the statement `text` with pg_query.rs
+fn run_pg_query(
+    text: &str,
+) -> (
+    Result<protobuf::ParseResult, String>,
+    Result<Vec<protobuf::ScanToken>, String>,
+) {
+    (
+        pg_query::parse(text)
+            .map(|p| p.protobuf)
+            .map_err(|e| e.to_string()),
+        pg_query::scan(text)
+            .map(|s| s.tokens)
+            .map_err(|e| e.to_string()),
+    )
+}
+
+/// The major version of Postgres whose parser is bundled with pg_query.rs
+const PG_MAJOR_VERSION: u32 = 13;
+
+/// Statements that are added in later Postgres versions, with the version that added them
+const NEWER_STATEMENTS: &[(&str, u32)] = &[("MERGE", 15)];
+
+/// Returns an error message and the range of the keyword within `text` if the statement `text`
+/// starts with a keyword that the bundled Postgres version does not support yet
+///
+/// Such statements fail to parse with a generic syntax error, which does not tell the user that
+/// the statement is valid for a newer Postgres version.
+fn unsupported_statement(
+    text: &str,
+    tokens: &[protobuf::ScanToken],
+) -> Option<(String, TextRange)> {
+    let (_, range) = first_token(text, tokens)?;
+    let keyword = &text[range];
+    NEWER_STATEMENTS
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(keyword))
+        .map(|(k, version)| {
+            (
+                format!(
+                    "`{}` requires Postgres {} or later, but the parser supports Postgres {}",
+                    k, version, PG_MAJOR_VERSION
+                ),
+                range,
+            )
+        })
+}
+
+/// Returns an error message and the range of the first token within `text` if the statement
+/// `text` starts with an identifier instead of a keyword, e.g. a misspelled keyword like `selct`
+fn unknown_statement(text: &str, tokens: &[protobuf::ScanToken]) -> Option<(String, TextRange)> {
+    match first_token(text, tokens)? {
+        (SyntaxKind::Ident, range) => Some((
+            format!("`{}` is not the start of a statement", &text[range]),
+            range,
+        )),
+        _ => None,
+    }
+}
+
+/// Returns the kind and the range of the first token of `tokens` that is not a comment
+fn first_token(text: &str, tokens: &[protobuf::ScanToken]) -> Option<(SyntaxKind, TextRange)> {
+    tokens.iter().find_map(|t| {
+        let range = TextRange::new(
+            TextSize::from(u32::try_from(t.start).ok()?),
+            TextSize::from(u32::try_from(t.end).ok()?),
+        );
+        match SyntaxKind::from_pg_query_token(t, &text[range]) {
+            SyntaxKind::SqlComment | SyntaxKind::CComment => None,
+            kind => Some((kind, range)),
+        }
+    })
 }
 
 #[cfg(test)]
@@ -243,7 +426,7 @@ mod tests {
     fn test_statement_lexer() {
         let input = "select * from contact where id = '123 4 5';";
 
-        let mut lex = StatementToken::lexer(&input);
+        let mut lex = StatementToken::lexer(input);
 
         assert_eq!(lex.next(), Some(Ok(StatementToken::Word)));
         assert_eq!(lex.slice(), "select");
@@ -283,6 +466,38 @@ mod tests {
         assert_eq!(lex.next(), Some(Ok(StatementToken::Ascii59)));
     }
 
+    #[test]
+    fn test_dollar_quotes_and_params() {
+        let lex = |input: &str| {
+            let mut lexer = StatementToken::lexer(input);
+            let mut tokens = Vec::new();
+            while let Some(token) = lexer.next() {
+                tokens.push((token, lexer.slice().to_string()));
+            }
+            tokens
+        };
+
+        assert_eq!(
+            lex("$1"),
+            vec![(Ok(StatementToken::Param), "$1".to_string())]
+        );
+        assert_eq!(
+            lex("$$ a $$"),
+            vec![(Ok(StatementToken::Sconst), "$$ a $$".to_string())]
+        );
+        assert_eq!(
+            lex("$body$ select $$a$$ $body$"),
+            vec![(
+                Ok(StatementToken::Sconst),
+                "$body$ select $$a$$ $body$".to_string()
+            )]
+        );
+
+        let tokens = lex("$1$x$1$");
+        assert_eq!(tokens[0], (Ok(StatementToken::Param), "$1".to_string()));
+        assert_eq!(tokens[1], (Err(()), "$x$".to_string()));
+    }
+
     #[test]
     fn test_statement_parser() {
         let input = "select *,some_col from contact where id = '123 4 5';";
@@ -309,6 +524,50 @@ mod tests {
         assert_eq!(parsed.cst.text(), input);
     }
 
+    #[test]
+    fn test_misspelled_statement_keyword() {
+        let input = "selct * from t;\nselect 1;";
+        let parsed = crate::parser::parse(input);
+
+        let kinds = parsed
+            .root()
+            .children()
+            .map(|n| n.kind())
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec![SyntaxKind::Error, SyntaxKind::SelectStmt]);
+        assert_eq!(parsed.root().text(), input);
+
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message(),
+            "`selct` is not the start of a statement"
+        );
+        assert_eq!(&input[diagnostics[0].range()], "selct");
+    }
+
+    #[test]
+    fn test_parse_statement_with_timeout() {
+        let input = format!("select {};", vec!["1 + 1"; 50_000].join(", "));
+
+        let mut parser = Parser::new();
+        parser.parse_statement_with_timeout(&input, None, Duration::from_nanos(1));
+        let parsed = parser.finish();
+
+        assert_eq!(parsed.cst.kind(), SyntaxKind::Stmt);
+        assert_eq!(parsed.cst.text(), input.as_str());
+        assert!(parsed.stmts.is_empty());
+        assert_eq!(parsed.errors.len(), 1);
+        assert!(parsed.errors[0].to_string().contains("timed out"));
+
+        let mut parser = Parser::new();
+        parser.parse_statement_with_timeout("select 1;", None, Duration::from_secs(60));
+        let parsed = parser.finish();
+
+        assert_eq!(parsed.cst.kind(), SyntaxKind::SelectStmt);
+        assert!(parsed.errors.is_empty());
+    }
+
     #[test]
     fn test_create_sql_function() {
         let input = "CREATE FUNCTION dup(in int, out f1 int, out f2 text)
@@ -323,4 +582,24 @@ mod tests {
 
         assert_eq!(parsed.cst.text(), input);
     }
+
+    #[test]
+    fn test_merge_requires_newer_postgres() {
+        let input = "merge into t using s on t.id = s.id when matched then do nothing;";
+
+        let mut parser = Parser::new();
+        parser.parse_statement(input, Some(10));
+        let parsed = parser.finish();
+
+        assert_eq!(parsed.cst.text(), input);
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(
+            parsed.errors[0].to_string(),
+            "`MERGE` requires Postgres 15 or later, but the parser supports Postgres 13"
+        );
+        assert_eq!(
+            parsed.errors[0].range(),
+            TextRange::new(TextSize::from(10), TextSize::from(15))
+        );
+    }
 }
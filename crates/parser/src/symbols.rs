@@ -0,0 +1,411 @@
+//! The outline of a source file.
+//!
+//! Every statement is a symbol that is named after its keywords and the object it defines or
+//! modifies, e.g. `CREATE TABLE contact`. Some statements have nested symbols, e.g. the columns
+//! of a table.
+
+use cstree::text::TextRange;
+use pg_query::protobuf::{Node, ObjectType};
+use pg_query::NodeEnum;
+
+use crate::ast::name::resolve;
+use crate::ast::{
+    location_to_offset, non_trivia_tokens, parse_pg_query_stmt, statements, syntax_node_for,
+    token_at,
+};
+use crate::syntax_kind::SyntaxKind;
+use crate::syntax_node::{SyntaxNode, SyntaxToken};
+
+/// The kind of a `DocumentSymbol`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    /// A statement that does not define any of the objects below, e.g. a select
+    Statement,
+    Table,
+    View,
+    Index,
+    Function,
+    Column,
+    /// A common table expression of a `WITH` clause
+    CommonTableExpr,
+}
+
+/// A symbol of the outline of a source file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DocumentSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The range of the whole symbol, without surrounding trivia
+    pub range: TextRange,
+    /// The range of the name of the symbol, e.g. the name of a table
+    pub selection_range: TextRange,
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// Returns a symbol for every statement of the source file `root`
+///
+/// The columns of created tables and the common table expressions of statements are the children
+/// of the symbol of their statement. Statements with syntax errors are named after their keywords
+/// only, and have no children.
+pub fn document_symbols(root: &SyntaxNode) -> Vec<DocumentSymbol> {
+    statements(root)
+        .into_iter()
+        .filter_map(statement_symbol)
+        .collect()
+}
+
+fn statement_symbol(stmt: &SyntaxNode) -> Option<DocumentSymbol> {
+    let tokens = stmt
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .filter(|t| !t.kind().is_trivia() && t.kind() != SyntaxKind::Ascii59)
+        .collect::<Vec<_>>();
+    let range = tokens
+        .first()?
+        .text_range()
+        .cover(tokens.last()?.text_range());
+    let ast = parse_pg_query_stmt(stmt);
+
+    // the leading keywords after the `WITH` clause, e.g. `CREATE TABLE` without `IF NOT EXISTS`,
+    // and the object, e.g. `public.contact`, which a query does not have
+    let tokens = &tokens[with_clause_len(&tokens)..];
+    let object = ast
+        .as_ref()
+        .and_then(|ast| object_position(stmt, ast, tokens));
+    let keywords = match object {
+        Some((start, _)) => &tokens[..start],
+        None if matches!(
+            tokens.first()?.kind(),
+            SyntaxKind::Select | SyntaxKind::Values | SyntaxKind::Table
+        ) =>
+        {
+            &tokens[..1]
+        }
+        None => &tokens[..tokens.iter().take_while(|t| is_keyword(t)).count()],
+    };
+    let mut name = keywords
+        .iter()
+        .filter(|t| !is_modifier(t.kind()))
+        .map(|t| t.resolved().text().to_uppercase())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut selection_range = tokens.first()?.text_range();
+    if let Some((start, len)) = object {
+        let object = &tokens[start..start + len];
+        name = format!(
+            "{} {}",
+            name,
+            object
+                .iter()
+                .map(|t| t.resolved().text())
+                .collect::<String>()
+        );
+        selection_range = object[0].text_range().cover(object[len - 1].text_range());
+    }
+
+    let (kind, children) = match &ast {
+        Some(NodeEnum::CreateStmt(create)) => (
+            SymbolKind::Table,
+            create
+                .table_elts
+                .iter()
+                .filter_map(|n| column_symbol(stmt, n))
+                .collect(),
+        ),
+        Some(NodeEnum::CreateTableAsStmt(create))
+            if create.relkind == ObjectType::ObjectMatview as i32 =>
+        {
+            (SymbolKind::View, Vec::new())
+        }
+        Some(NodeEnum::ViewStmt(_)) => (SymbolKind::View, Vec::new()),
+        Some(NodeEnum::IndexStmt(_)) => (SymbolKind::Index, Vec::new()),
+        Some(NodeEnum::CreateFunctionStmt(_)) => (SymbolKind::Function, Vec::new()),
+        Some(ast) => {
+            let with_clause = match ast {
+                NodeEnum::SelectStmt(n) => n.with_clause.as_ref(),
+                NodeEnum::InsertStmt(n) => n.with_clause.as_ref(),
+                NodeEnum::UpdateStmt(n) => n.with_clause.as_ref(),
+                NodeEnum::DeleteStmt(n) => n.with_clause.as_ref(),
+                _ => None,
+            };
+            (
+                SymbolKind::Statement,
+                with_clause
+                    .map(|w| w.ctes.iter().filter_map(|n| cte_symbol(stmt, n)).collect())
+                    .unwrap_or_default(),
+            )
+        }
+        None => (SymbolKind::Statement, Vec::new()),
+    };
+
+    Some(DocumentSymbol {
+        name,
+        kind,
+        range,
+        selection_range,
+        children,
+    })
+}
+
+/// Returns the symbol of the column definition `node` of a `CREATE TABLE`
+fn column_symbol(stmt: &SyntaxNode, node: &Node) -> Option<DocumentSymbol> {
+    let column = match node.node.as_ref()? {
+        NodeEnum::ColumnDef(column) => column,
+        _ => return None,
+    };
+    let syntax = syntax_node_for(stmt, &NodeEnum::ColumnDef(column.clone()).to_ref())?;
+    let name = token_at(stmt, column.location)?;
+    Some(DocumentSymbol {
+        name: column.colname.clone(),
+        kind: SymbolKind::Column,
+        range: trimmed_range(syntax)?,
+        selection_range: name.text_range(),
+        children: Vec::new(),
+    })
+}
+
+/// Returns the symbol of the common table expression `node`, which ranges from its name to the
+/// closing parenthesis of its query
+fn cte_symbol(stmt: &SyntaxNode, node: &Node) -> Option<DocumentSymbol> {
+    let cte = match node.node.as_ref()? {
+        NodeEnum::CommonTableExpr(cte) => cte,
+        _ => return None,
+    };
+    let syntax = syntax_node_for(stmt, &NodeEnum::CommonTableExpr(cte.clone()).to_ref())?;
+    let name = token_at(stmt, cte.location)?;
+    let end = syntax
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .filter(|t| t.kind() == SyntaxKind::Ascii41)
+        .last()?;
+    Some(DocumentSymbol {
+        name: cte.ctename.clone(),
+        kind: SymbolKind::CommonTableExpr,
+        range: name.text_range().cover(end.text_range()),
+        selection_range: name.text_range(),
+        children: Vec::new(),
+    })
+}
+
+/// Returns the range of `node` without leading and trailing trivia
+fn trimmed_range(node: &SyntaxNode) -> Option<TextRange> {
+    let mut tokens = non_trivia_tokens(node).into_iter();
+    let first = tokens.next()?.text_range();
+    Some(first.cover(tokens.last().map_or(first, |t| t.text_range())))
+}
+
+/// Returns the number of tokens of the `WITH` clause at the start of `tokens`
+///
+/// The clause ends at the closing parenthesis of a query that is not followed by another common
+/// table expression.
+fn with_clause_len(tokens: &[&SyntaxToken]) -> usize {
+    if tokens.first().map(|t| t.kind()) != Some(SyntaxKind::With) {
+        return 0;
+    }
+    let mut depth = 0;
+    for (idx, token) in tokens.iter().enumerate() {
+        match token.kind() {
+            SyntaxKind::Ascii40 => depth += 1,
+            SyntaxKind::Ascii41 => {
+                depth -= 1;
+                // a parenthesized list of column names is followed by `AS`
+                if depth == 0
+                    && !matches!(
+                        tokens.get(idx + 1).map(|t| t.kind()),
+                        Some(SyntaxKind::Ascii44 | SyntaxKind::As)
+                    )
+                {
+                    return idx + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    0
+}
+
+/// Returns the index and the number of tokens of the name of the object that the statement
+/// `ast` defines or modifies, e.g. of `public.contact` in `create table public.contact (...)`
+///
+/// The name is located by the `RangeVar` of the object, or by its parts if it does not have one.
+fn object_position(
+    stmt: &SyntaxNode,
+    ast: &NodeEnum,
+    tokens: &[&SyntaxToken],
+) -> Option<(usize, usize)> {
+    let relation = match ast {
+        NodeEnum::CreateStmt(n) => n.relation.as_ref(),
+        NodeEnum::ViewStmt(n) => n.view.as_ref(),
+        NodeEnum::CreateTableAsStmt(n) => n.into.as_ref()?.rel.as_ref(),
+        NodeEnum::CreateSeqStmt(n) => n.sequence.as_ref(),
+        NodeEnum::AlterTableStmt(n) => n.relation.as_ref(),
+        NodeEnum::InsertStmt(n) => n.relation.as_ref(),
+        NodeEnum::UpdateStmt(n) => n.relation.as_ref(),
+        NodeEnum::DeleteStmt(n) => n.relation.as_ref(),
+        NodeEnum::IndexStmt(n) if n.idxname.is_empty() => n.relation.as_ref(),
+        NodeEnum::IndexStmt(n) => return name_position(tokens, std::slice::from_ref(&n.idxname)),
+        NodeEnum::CreateFunctionStmt(n) => return name_position(tokens, &strings(&n.funcname)),
+        NodeEnum::DropStmt(n) => match n.objects.first()?.node.as_ref()? {
+            NodeEnum::List(list) => return name_position(tokens, &strings(&list.items)),
+            _ => None,
+        },
+        _ => None,
+    }?;
+    let parts = [
+        &relation.catalogname,
+        &relation.schemaname,
+        &relation.relname,
+    ]
+    .iter()
+    .filter(|p| !p.is_empty())
+    .count();
+    let offset = location_to_offset(stmt, relation.location)?;
+    let start = tokens
+        .iter()
+        .position(|t| t.text_range().start() == offset)?;
+    Some((start, (2 * parts).checked_sub(1)?.min(tokens.len() - start)))
+}
+
+/// Returns the index and the number of tokens of the first qualified name in `tokens` after the
+/// leading keyword whose parts are `parts`
+fn name_position(tokens: &[&SyntaxToken], parts: &[String]) -> Option<(usize, usize)> {
+    let len = (2 * parts.len()).checked_sub(1)?;
+    (1..tokens.len())
+        .find(|&start| {
+            tokens.len() >= start + len
+                && parts.iter().enumerate().all(|(idx, part)| {
+                    resolve(tokens[start + 2 * idx].resolved().text()) == *part
+                        && (idx == 0 || tokens[start + 2 * idx - 1].kind() == SyntaxKind::Ascii46)
+                })
+        })
+        .map(|start| (start, len))
+}
+
+/// Returns the values of the `String` nodes of `nodes`
+fn strings(nodes: &[Node]) -> Vec<String> {
+    nodes
+        .iter()
+        .filter_map(|n| match n.node.as_ref()? {
+            NodeEnum::String(s) => Some(s.str.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether `token` is a word that is not an identifier
+///
+/// Unreserved keywords, e.g. `data`, are words as well, even if they are used as identifiers.
+/// This only names statements without a known object, which are named after their leading words.
+fn is_keyword(token: &SyntaxToken) -> bool {
+    token.kind() != SyntaxKind::Ident
+        && token
+            .resolved()
+            .text()
+            .starts_with(|c: char| c.is_alphabetic() || c == '_')
+}
+
+/// Whether the keyword `kind` only modifies the behaviour of a statement, e.g. `IF NOT EXISTS`
+///
+/// The `OR` of `OR REPLACE` is a `LogicalOp`, which cannot appear in the keywords otherwise.
+fn is_modifier(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::IfP
+            | SyntaxKind::Not
+            | SyntaxKind::Exists
+            | SyntaxKind::LogicalOp
+            | SyntaxKind::Replace
+            | SyntaxKind::Concurrently
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_create_table_symbols() {
+        let input = "create table if not exists public.contact (\n    id int primary key,\n    email text\n);";
+        let parsed = parse(input);
+        let symbols = document_symbols(parsed.root());
+
+        assert_eq!(symbols.len(), 1);
+        let table = &symbols[0];
+        assert_eq!(table.name, "CREATE TABLE public.contact");
+        assert_eq!(table.kind, SymbolKind::Table);
+        assert_eq!(&input[table.range], &input[..input.len() - 1]);
+        assert_eq!(&input[table.selection_range], "public.contact");
+
+        let columns = table
+            .children
+            .iter()
+            .map(|c| {
+                (
+                    c.name.as_str(),
+                    c.kind,
+                    &input[c.range],
+                    &input[c.selection_range],
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                ("id", SymbolKind::Column, "id int primary key", "id"),
+                ("email", SymbolKind::Column, "email text", "email"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_statement_symbols() {
+        let input = "with recent as (select 1) select * from recent;\nwith x (a) as (select 1) delete from users where id = 1;\ncreate table (;";
+        let parsed = parse(input);
+        let symbols = document_symbols(parsed.root());
+
+        let names = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                ("SELECT", SymbolKind::Statement),
+                ("DELETE FROM users", SymbolKind::Statement),
+                ("CREATE TABLE", SymbolKind::Statement),
+            ]
+        );
+        assert_eq!(symbols[0].children.len(), 1);
+        assert_eq!(symbols[0].children[0].name, "recent");
+        assert_eq!(&input[symbols[0].children[0].range], "recent as (select 1)");
+    }
+
+    #[test]
+    fn test_object_names() {
+        let input = "create table data (id int);
+create or replace view name as select 1;
+create index concurrently if not exists i on t (a);
+create function public.f() returns int as 'select 1' language sql;
+drop table if exists \"Data\", b;
+select data from t;";
+        let parsed = parse(input);
+        let symbols = document_symbols(parsed.root());
+
+        let names = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), &input[s.selection_range]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                ("CREATE TABLE data", "data"),
+                ("CREATE VIEW name", "name"),
+                ("CREATE INDEX i", "i"),
+                ("CREATE FUNCTION public.f", "public.f"),
+                ("DROP TABLE \"Data\"", "\"Data\""),
+                ("SELECT", "select"),
+            ]
+        );
+    }
+}
@@ -4,23 +4,59 @@
 use cstree::Syntax;
 use pg_query::{protobuf::ScanToken, NodeRef};
 
-/// An u32 enum of all valid syntax elements (nodes and tokens) of the postgres sql dialect, and a few custom ones
-/// that are not parsed by pg_query.rs, such as `Whitespace`.
-///
-/// Copied from pg_query.rs source code. Can be generated.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Syntax)]
-#[repr(u32)]
-pub enum SyntaxKind {
+/// Defines `SyntaxKind` together with `syntax_kind_name`, so that every variant has a name
+macro_rules! syntax_kinds {
+    ($($(#[$attr:meta])* $variant:ident,)*) => {
+        /// An u32 enum of all valid syntax elements (nodes and tokens) of the postgres sql dialect, and a few custom ones
+        /// that are not parsed by pg_query.rs, such as `Whitespace`.
+        ///
+        /// Copied from pg_query.rs source code. Can be generated.
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Syntax)]
+        #[repr(u32)]
+        pub enum SyntaxKind {
+            $($(#[$attr])* $variant,)*
+        }
+
+        /// Returns the name of `kind`, which is the name of its variant, e.g. `"SelectStmt"`
+        pub fn syntax_kind_name(kind: SyntaxKind) -> &'static str {
+            match kind {
+                $(SyntaxKind::$variant => stringify!($variant),)*
+            }
+        }
+    };
+}
+
+syntax_kinds! {
     // custom nodes, which are not parsed by pg_query.rs
     SourceFile,
     Comment,
     Whitespace,
     Newline,
     Tab,
+    /// a byte order mark at the start of a source file
+    Bom,
+    /// a `#!` line at the start of a source file, e.g. `#!/usr/bin/env psql`
+    Shebang,
+    /// the inline data of a `COPY ... FROM STDIN`, up to and including the terminating `\.` line
+    CopyData,
     /// common value for all keywords (select, from, ...)
     Word,
     /// node for unknown statements (e.g. when parsing fails)
     Stmt,
+    /// node for a stray `;` that does not terminate a statement
+    EmptyStatement,
+    /// node for a statement that does not start with a statement keyword, e.g. `selct * from t`
+    Error,
+    /// comparison operators, e.g. `=` and `<>`
+    ComparisonOp,
+    /// arithmetic operators, e.g. `+` and `/`
+    ArithmeticOp,
+    /// the logical operators `AND` and `OR`
+    LogicalOp,
+    /// json operators, e.g. `->>` and `#>`
+    JsonOp,
+    /// pattern matching operators, e.g. `LIKE` and `~*`
+    PatternOp,
     // from here copyied from NodeEnum
     Alias,
     RangeVar,
@@ -803,21 +839,244 @@ impl SyntaxKind {
     /// Can be generated
     pub fn from_pg_query_node(node: &NodeRef) -> Self {
         match node {
+            NodeRef::Alias(_) => SyntaxKind::Alias,
+            NodeRef::RangeVar(_) => SyntaxKind::RangeVar,
+            NodeRef::TableFunc(_) => SyntaxKind::TableFunc,
+            NodeRef::Expr(_) => SyntaxKind::Expr,
+            NodeRef::Var(_) => SyntaxKind::Var,
+            NodeRef::Param(_) => SyntaxKind::Param,
+            NodeRef::Aggref(_) => SyntaxKind::Aggref,
+            NodeRef::GroupingFunc(_) => SyntaxKind::GroupingFunc,
+            NodeRef::WindowFunc(_) => SyntaxKind::WindowFunc,
+            NodeRef::SubscriptingRef(_) => SyntaxKind::SubscriptingRef,
+            NodeRef::FuncExpr(_) => SyntaxKind::FuncExpr,
+            NodeRef::NamedArgExpr(_) => SyntaxKind::NamedArgExpr,
+            NodeRef::OpExpr(_) => SyntaxKind::OpExpr,
+            NodeRef::DistinctExpr(_) => SyntaxKind::DistinctExpr,
+            NodeRef::NullIfExpr(_) => SyntaxKind::NullIfExpr,
+            NodeRef::ScalarArrayOpExpr(_) => SyntaxKind::ScalarArrayOpExpr,
+            NodeRef::BoolExpr(_) => SyntaxKind::BoolExpr,
+            NodeRef::SubLink(_) => SyntaxKind::SubLink,
+            NodeRef::SubPlan(_) => SyntaxKind::SubPlan,
+            NodeRef::AlternativeSubPlan(_) => SyntaxKind::AlternativeSubPlan,
+            NodeRef::FieldSelect(_) => SyntaxKind::FieldSelect,
+            NodeRef::FieldStore(_) => SyntaxKind::FieldStore,
+            NodeRef::RelabelType(_) => SyntaxKind::RelabelType,
+            NodeRef::CoerceViaIo(_) => SyntaxKind::CoerceViaIo,
+            NodeRef::ArrayCoerceExpr(_) => SyntaxKind::ArrayCoerceExpr,
+            NodeRef::ConvertRowtypeExpr(_) => SyntaxKind::ConvertRowtypeExpr,
+            NodeRef::CollateExpr(_) => SyntaxKind::CollateExpr,
+            NodeRef::CaseExpr(_) => SyntaxKind::CaseExpr,
+            NodeRef::CaseWhen(_) => SyntaxKind::CaseWhen,
+            NodeRef::CaseTestExpr(_) => SyntaxKind::CaseTestExpr,
+            NodeRef::ArrayExpr(_) => SyntaxKind::ArrayExpr,
+            NodeRef::RowExpr(_) => SyntaxKind::RowExpr,
+            NodeRef::RowCompareExpr(_) => SyntaxKind::RowCompareExpr,
+            NodeRef::CoalesceExpr(_) => SyntaxKind::CoalesceExpr,
+            NodeRef::MinMaxExpr(_) => SyntaxKind::MinMaxExpr,
+            NodeRef::SqlvalueFunction(_) => SyntaxKind::SqlvalueFunction,
+            NodeRef::XmlExpr(_) => SyntaxKind::XmlExpr,
+            NodeRef::NullTest(_) => SyntaxKind::NullTest,
+            NodeRef::BooleanTest(_) => SyntaxKind::BooleanTest,
+            NodeRef::CoerceToDomain(_) => SyntaxKind::CoerceToDomain,
+            NodeRef::CoerceToDomainValue(_) => SyntaxKind::CoerceToDomainValue,
+            NodeRef::SetToDefault(_) => SyntaxKind::SetToDefault,
+            NodeRef::CurrentOfExpr(_) => SyntaxKind::CurrentOfExpr,
+            NodeRef::NextValueExpr(_) => SyntaxKind::NextValueExpr,
+            NodeRef::InferenceElem(_) => SyntaxKind::InferenceElem,
+            NodeRef::TargetEntry(_) => SyntaxKind::TargetEntry,
+            NodeRef::RangeTblRef(_) => SyntaxKind::RangeTblRef,
+            NodeRef::JoinExpr(_) => SyntaxKind::JoinExpr,
+            NodeRef::FromExpr(_) => SyntaxKind::FromExpr,
+            NodeRef::OnConflictExpr(_) => SyntaxKind::OnConflictExpr,
+            NodeRef::IntoClause(_) => SyntaxKind::IntoClause,
+            NodeRef::RawStmt(_) => SyntaxKind::RawStmt,
+            NodeRef::Query(_) => SyntaxKind::Query,
+            NodeRef::InsertStmt(_) => SyntaxKind::InsertStmt,
+            NodeRef::DeleteStmt(_) => SyntaxKind::DeleteStmt,
+            NodeRef::UpdateStmt(_) => SyntaxKind::UpdateStmt,
             NodeRef::SelectStmt(_) => SyntaxKind::SelectStmt,
+            NodeRef::AlterTableStmt(_) => SyntaxKind::AlterTableStmt,
+            NodeRef::AlterTableCmd(_) => SyntaxKind::AlterTableCmd,
+            NodeRef::AlterDomainStmt(_) => SyntaxKind::AlterDomainStmt,
+            NodeRef::SetOperationStmt(_) => SyntaxKind::SetOperationStmt,
+            NodeRef::GrantStmt(_) => SyntaxKind::GrantStmt,
+            NodeRef::GrantRoleStmt(_) => SyntaxKind::GrantRoleStmt,
+            NodeRef::AlterDefaultPrivilegesStmt(_) => SyntaxKind::AlterDefaultPrivilegesStmt,
+            NodeRef::ClosePortalStmt(_) => SyntaxKind::ClosePortalStmt,
+            NodeRef::ClusterStmt(_) => SyntaxKind::ClusterStmt,
+            NodeRef::CopyStmt(_) => SyntaxKind::CopyStmt,
+            NodeRef::CreateStmt(_) => SyntaxKind::CreateStmt,
+            NodeRef::DefineStmt(_) => SyntaxKind::DefineStmt,
+            NodeRef::DropStmt(_) => SyntaxKind::DropStmt,
+            NodeRef::TruncateStmt(_) => SyntaxKind::TruncateStmt,
+            NodeRef::CommentStmt(_) => SyntaxKind::CommentStmt,
+            NodeRef::FetchStmt(_) => SyntaxKind::FetchStmt,
+            NodeRef::IndexStmt(_) => SyntaxKind::IndexStmt,
             NodeRef::CreateFunctionStmt(_) => SyntaxKind::CreateFunctionStmt,
-            NodeRef::ResTarget(_) => SyntaxKind::ResTarget,
+            NodeRef::AlterFunctionStmt(_) => SyntaxKind::AlterFunctionStmt,
+            NodeRef::DoStmt(_) => SyntaxKind::DoStmt,
+            NodeRef::RenameStmt(_) => SyntaxKind::RenameStmt,
+            NodeRef::RuleStmt(_) => SyntaxKind::RuleStmt,
+            NodeRef::NotifyStmt(_) => SyntaxKind::NotifyStmt,
+            NodeRef::ListenStmt(_) => SyntaxKind::ListenStmt,
+            NodeRef::UnlistenStmt(_) => SyntaxKind::UnlistenStmt,
+            NodeRef::TransactionStmt(_) => SyntaxKind::TransactionStmt,
+            NodeRef::ViewStmt(_) => SyntaxKind::ViewStmt,
+            NodeRef::LoadStmt(_) => SyntaxKind::LoadStmt,
+            NodeRef::CreateDomainStmt(_) => SyntaxKind::CreateDomainStmt,
+            NodeRef::CreatedbStmt(_) => SyntaxKind::CreatedbStmt,
+            NodeRef::DropdbStmt(_) => SyntaxKind::DropdbStmt,
+            NodeRef::VacuumStmt(_) => SyntaxKind::VacuumStmt,
+            NodeRef::ExplainStmt(_) => SyntaxKind::ExplainStmt,
+            NodeRef::CreateTableAsStmt(_) => SyntaxKind::CreateTableAsStmt,
+            NodeRef::CreateSeqStmt(_) => SyntaxKind::CreateSeqStmt,
+            NodeRef::AlterSeqStmt(_) => SyntaxKind::AlterSeqStmt,
+            NodeRef::VariableSetStmt(_) => SyntaxKind::VariableSetStmt,
+            NodeRef::VariableShowStmt(_) => SyntaxKind::VariableShowStmt,
+            NodeRef::DiscardStmt(_) => SyntaxKind::DiscardStmt,
+            NodeRef::CreateTrigStmt(_) => SyntaxKind::CreateTrigStmt,
+            NodeRef::CreatePlangStmt(_) => SyntaxKind::CreatePlangStmt,
+            NodeRef::CreateRoleStmt(_) => SyntaxKind::CreateRoleStmt,
+            NodeRef::AlterRoleStmt(_) => SyntaxKind::AlterRoleStmt,
+            NodeRef::DropRoleStmt(_) => SyntaxKind::DropRoleStmt,
+            NodeRef::LockStmt(_) => SyntaxKind::LockStmt,
+            NodeRef::ConstraintsSetStmt(_) => SyntaxKind::ConstraintsSetStmt,
+            NodeRef::ReindexStmt(_) => SyntaxKind::ReindexStmt,
+            NodeRef::CheckPointStmt(_) => SyntaxKind::CheckPointStmt,
+            NodeRef::CreateSchemaStmt(_) => SyntaxKind::CreateSchemaStmt,
+            NodeRef::AlterDatabaseStmt(_) => SyntaxKind::AlterDatabaseStmt,
+            NodeRef::AlterDatabaseSetStmt(_) => SyntaxKind::AlterDatabaseSetStmt,
+            NodeRef::AlterRoleSetStmt(_) => SyntaxKind::AlterRoleSetStmt,
+            NodeRef::CreateConversionStmt(_) => SyntaxKind::CreateConversionStmt,
+            NodeRef::CreateCastStmt(_) => SyntaxKind::CreateCastStmt,
+            NodeRef::CreateOpClassStmt(_) => SyntaxKind::CreateOpClassStmt,
+            NodeRef::CreateOpFamilyStmt(_) => SyntaxKind::CreateOpFamilyStmt,
+            NodeRef::AlterOpFamilyStmt(_) => SyntaxKind::AlterOpFamilyStmt,
+            NodeRef::PrepareStmt(_) => SyntaxKind::PrepareStmt,
+            NodeRef::ExecuteStmt(_) => SyntaxKind::ExecuteStmt,
+            NodeRef::DeallocateStmt(_) => SyntaxKind::DeallocateStmt,
+            NodeRef::DeclareCursorStmt(_) => SyntaxKind::DeclareCursorStmt,
+            NodeRef::CreateTableSpaceStmt(_) => SyntaxKind::CreateTableSpaceStmt,
+            NodeRef::DropTableSpaceStmt(_) => SyntaxKind::DropTableSpaceStmt,
+            NodeRef::AlterObjectDependsStmt(_) => SyntaxKind::AlterObjectDependsStmt,
+            NodeRef::AlterObjectSchemaStmt(_) => SyntaxKind::AlterObjectSchemaStmt,
+            NodeRef::AlterOwnerStmt(_) => SyntaxKind::AlterOwnerStmt,
+            NodeRef::AlterOperatorStmt(_) => SyntaxKind::AlterOperatorStmt,
+            NodeRef::AlterTypeStmt(_) => SyntaxKind::AlterTypeStmt,
+            NodeRef::DropOwnedStmt(_) => SyntaxKind::DropOwnedStmt,
+            NodeRef::ReassignOwnedStmt(_) => SyntaxKind::ReassignOwnedStmt,
+            NodeRef::CompositeTypeStmt(_) => SyntaxKind::CompositeTypeStmt,
+            NodeRef::CreateEnumStmt(_) => SyntaxKind::CreateEnumStmt,
+            NodeRef::CreateRangeStmt(_) => SyntaxKind::CreateRangeStmt,
+            NodeRef::AlterEnumStmt(_) => SyntaxKind::AlterEnumStmt,
+            NodeRef::AlterTsdictionaryStmt(_) => SyntaxKind::AlterTsdictionaryStmt,
+            NodeRef::AlterTsconfigurationStmt(_) => SyntaxKind::AlterTsconfigurationStmt,
+            NodeRef::CreateFdwStmt(_) => SyntaxKind::CreateFdwStmt,
+            NodeRef::AlterFdwStmt(_) => SyntaxKind::AlterFdwStmt,
+            NodeRef::CreateForeignServerStmt(_) => SyntaxKind::CreateForeignServerStmt,
+            NodeRef::AlterForeignServerStmt(_) => SyntaxKind::AlterForeignServerStmt,
+            NodeRef::CreateUserMappingStmt(_) => SyntaxKind::CreateUserMappingStmt,
+            NodeRef::AlterUserMappingStmt(_) => SyntaxKind::AlterUserMappingStmt,
+            NodeRef::DropUserMappingStmt(_) => SyntaxKind::DropUserMappingStmt,
+            NodeRef::AlterTableSpaceOptionsStmt(_) => SyntaxKind::AlterTableSpaceOptionsStmt,
+            NodeRef::AlterTableMoveAllStmt(_) => SyntaxKind::AlterTableMoveAllStmt,
+            NodeRef::SecLabelStmt(_) => SyntaxKind::SecLabelStmt,
+            NodeRef::CreateForeignTableStmt(_) => SyntaxKind::CreateForeignTableStmt,
+            NodeRef::ImportForeignSchemaStmt(_) => SyntaxKind::ImportForeignSchemaStmt,
+            NodeRef::CreateExtensionStmt(_) => SyntaxKind::CreateExtensionStmt,
+            NodeRef::AlterExtensionStmt(_) => SyntaxKind::AlterExtensionStmt,
+            NodeRef::AlterExtensionContentsStmt(_) => SyntaxKind::AlterExtensionContentsStmt,
+            NodeRef::CreateEventTrigStmt(_) => SyntaxKind::CreateEventTrigStmt,
+            NodeRef::AlterEventTrigStmt(_) => SyntaxKind::AlterEventTrigStmt,
+            NodeRef::RefreshMatViewStmt(_) => SyntaxKind::RefreshMatViewStmt,
+            NodeRef::ReplicaIdentityStmt(_) => SyntaxKind::ReplicaIdentityStmt,
+            NodeRef::AlterSystemStmt(_) => SyntaxKind::AlterSystemStmt,
+            NodeRef::CreatePolicyStmt(_) => SyntaxKind::CreatePolicyStmt,
+            NodeRef::AlterPolicyStmt(_) => SyntaxKind::AlterPolicyStmt,
+            NodeRef::CreateTransformStmt(_) => SyntaxKind::CreateTransformStmt,
+            NodeRef::CreateAmStmt(_) => SyntaxKind::CreateAmStmt,
+            NodeRef::CreatePublicationStmt(_) => SyntaxKind::CreatePublicationStmt,
+            NodeRef::AlterPublicationStmt(_) => SyntaxKind::AlterPublicationStmt,
+            NodeRef::CreateSubscriptionStmt(_) => SyntaxKind::CreateSubscriptionStmt,
+            NodeRef::AlterSubscriptionStmt(_) => SyntaxKind::AlterSubscriptionStmt,
+            NodeRef::DropSubscriptionStmt(_) => SyntaxKind::DropSubscriptionStmt,
+            NodeRef::CreateStatsStmt(_) => SyntaxKind::CreateStatsStmt,
+            NodeRef::AlterCollationStmt(_) => SyntaxKind::AlterCollationStmt,
+            NodeRef::CallStmt(_) => SyntaxKind::CallStmt,
+            NodeRef::AlterStatsStmt(_) => SyntaxKind::AlterStatsStmt,
             NodeRef::AExpr(_) => SyntaxKind::AExpr,
-            NodeRef::RangeVar(_) => SyntaxKind::RangeVar,
             NodeRef::ColumnRef(_) => SyntaxKind::ColumnRef,
+            NodeRef::ParamRef(_) => SyntaxKind::ParamRef,
             NodeRef::AConst(_) => SyntaxKind::AConst,
-            _ => panic!("Unknown node type: {:?}", node),
+            NodeRef::FuncCall(_) => SyntaxKind::FuncCall,
+            NodeRef::AStar(_) => SyntaxKind::AStar,
+            NodeRef::AIndices(_) => SyntaxKind::AIndices,
+            NodeRef::AIndirection(_) => SyntaxKind::AIndirection,
+            NodeRef::AArrayExpr(_) => SyntaxKind::AArrayExpr,
+            NodeRef::ResTarget(_) => SyntaxKind::ResTarget,
+            NodeRef::MultiAssignRef(_) => SyntaxKind::MultiAssignRef,
+            NodeRef::TypeCast(_) => SyntaxKind::TypeCast,
+            NodeRef::CollateClause(_) => SyntaxKind::CollateClause,
+            NodeRef::SortBy(_) => SyntaxKind::SortBy,
+            NodeRef::WindowDef(_) => SyntaxKind::WindowDef,
+            NodeRef::RangeSubselect(_) => SyntaxKind::RangeSubselect,
+            NodeRef::RangeFunction(_) => SyntaxKind::RangeFunction,
+            NodeRef::RangeTableSample(_) => SyntaxKind::RangeTableSample,
+            NodeRef::RangeTableFunc(_) => SyntaxKind::RangeTableFunc,
+            NodeRef::RangeTableFuncCol(_) => SyntaxKind::RangeTableFuncCol,
+            NodeRef::TypeName(_) => SyntaxKind::TypeName,
+            NodeRef::ColumnDef(_) => SyntaxKind::ColumnDef,
+            NodeRef::IndexElem(_) => SyntaxKind::IndexElem,
+            NodeRef::Constraint(_) => SyntaxKind::Constraint,
+            NodeRef::DefElem(_) => SyntaxKind::DefElem,
+            NodeRef::RangeTblEntry(_) => SyntaxKind::RangeTblEntry,
+            NodeRef::RangeTblFunction(_) => SyntaxKind::RangeTblFunction,
+            NodeRef::TableSampleClause(_) => SyntaxKind::TableSampleClause,
+            NodeRef::WithCheckOption(_) => SyntaxKind::WithCheckOption,
+            NodeRef::SortGroupClause(_) => SyntaxKind::SortGroupClause,
+            NodeRef::GroupingSet(_) => SyntaxKind::GroupingSet,
+            NodeRef::WindowClause(_) => SyntaxKind::WindowClause,
+            NodeRef::ObjectWithArgs(_) => SyntaxKind::ObjectWithArgs,
+            NodeRef::AccessPriv(_) => SyntaxKind::AccessPriv,
+            NodeRef::CreateOpClassItem(_) => SyntaxKind::CreateOpClassItem,
+            NodeRef::TableLikeClause(_) => SyntaxKind::TableLikeClause,
+            NodeRef::FunctionParameter(_) => SyntaxKind::FunctionParameter,
+            NodeRef::LockingClause(_) => SyntaxKind::LockingClause,
+            NodeRef::RowMarkClause(_) => SyntaxKind::RowMarkClause,
+            NodeRef::XmlSerialize(_) => SyntaxKind::XmlSerialize,
+            NodeRef::WithClause(_) => SyntaxKind::WithClause,
+            NodeRef::InferClause(_) => SyntaxKind::InferClause,
+            NodeRef::OnConflictClause(_) => SyntaxKind::OnConflictClause,
+            NodeRef::CommonTableExpr(_) => SyntaxKind::CommonTableExpr,
+            NodeRef::RoleSpec(_) => SyntaxKind::RoleSpec,
+            NodeRef::TriggerTransition(_) => SyntaxKind::TriggerTransition,
+            NodeRef::PartitionElem(_) => SyntaxKind::PartitionElem,
+            NodeRef::PartitionSpec(_) => SyntaxKind::PartitionSpec,
+            NodeRef::PartitionBoundSpec(_) => SyntaxKind::PartitionBoundSpec,
+            NodeRef::PartitionRangeDatum(_) => SyntaxKind::PartitionRangeDatum,
+            NodeRef::PartitionCmd(_) => SyntaxKind::PartitionCmd,
+            NodeRef::VacuumRelation(_) => SyntaxKind::VacuumRelation,
+            NodeRef::InlineCodeBlock(_) => SyntaxKind::InlineCodeBlock,
+            NodeRef::CallContext(_) => SyntaxKind::CallContext,
+            NodeRef::Integer(_) => SyntaxKind::Integer,
+            NodeRef::Float(_) => SyntaxKind::Float,
+            NodeRef::String(_) => SyntaxKind::String,
+            NodeRef::BitString(_) => SyntaxKind::BitString,
+            NodeRef::Null(_) => SyntaxKind::Null,
+            NodeRef::List(_) => SyntaxKind::List,
+            NodeRef::IntList(_) => SyntaxKind::IntList,
+            NodeRef::OidList(_) => SyntaxKind::OidList,
         }
     }
 
-    /// Converts a `pg_query` ScanToken to a `SyntaxKind`
+    /// Converts a `pg_query` ScanToken with the source `text` to a `SyntaxKind`
     /// Can be generated
-    pub fn from_pg_query_token(token: &ScanToken) -> Self {
-        match token.token {
+    ///
+    /// Operators are converted to the kind of their class, e.g. `ComparisonOp`. The class of a
+    /// generic `Op` token, such as `->>`, is derived from `text`.
+    pub fn from_pg_query_token(token: &ScanToken, text: &str) -> Self {
+        let kind = match token.token {
             0 => SyntaxKind::Nul,
             37 => SyntaxKind::Ascii37,
             40 => SyntaxKind::Ascii40,
@@ -1312,22 +1571,160 @@ impl SyntaxKind {
             730 => SyntaxKind::Postfixop,
             731 => SyntaxKind::Uminus,
             _ => panic!("Unknown StatementToken: {:?}", token),
+        };
+        kind.operator_class(text).unwrap_or(kind)
+    }
+
+    /// Returns the operator class of a token of this kind with the source `text`, if it is an
+    /// operator
+    ///
+    /// `*` is not classified, since it is also used for all columns, e.g. in `select *`. `NOT` is
+    /// not classified either, since it is also part of e.g. `NOT NULL`.
+    fn operator_class(&self, text: &str) -> Option<SyntaxKind> {
+        match self {
+            SyntaxKind::Ascii60
+            | SyntaxKind::Ascii61
+            | SyntaxKind::Ascii62
+            | SyntaxKind::LessEquals
+            | SyntaxKind::GreaterEquals
+            | SyntaxKind::NotEquals => Some(SyntaxKind::ComparisonOp),
+            SyntaxKind::Ascii37
+            | SyntaxKind::Ascii43
+            | SyntaxKind::Ascii45
+            | SyntaxKind::Ascii47
+            | SyntaxKind::Ascii94 => Some(SyntaxKind::ArithmeticOp),
+            SyntaxKind::And | SyntaxKind::Or => Some(SyntaxKind::LogicalOp),
+            SyntaxKind::Like | SyntaxKind::Ilike | SyntaxKind::Similar => {
+                Some(SyntaxKind::PatternOp)
+            }
+            SyntaxKind::Op => match text {
+                "->" | "->>" | "#>" | "#>>" | "#-" | "?|" | "?&" | "@?" => Some(SyntaxKind::JsonOp),
+                "~" | "~*" | "!~" | "!~*" | "~~" | "~~*" | "!~~" | "!~~*" => {
+                    Some(SyntaxKind::PatternOp)
+                }
+                _ => None,
+            },
+            _ => None,
         }
     }
 
     /// Returns the `SyntaxKindType` of a `SyntaxKind`
     /// Maybe this can be generated from the ScanToken type in pg_query.rs
+    ///
+    /// Operators only occur as the kind of their class, so all comparison operators, not only `=`,
+    /// are `Follow` tokens. They are children of the expression of the operator, e.g. the `<` of
+    /// `a < b` is a sibling of the columns of its `AExpr`, not a child of the column `a`.
     pub fn get_type(&self) -> Option<SyntaxKindType> {
         match self {
             SyntaxKind::Whitespace => Some(SyntaxKindType::Follow),
+            SyntaxKind::Newline => Some(SyntaxKindType::Follow),
+            SyntaxKind::Tab => Some(SyntaxKindType::Follow),
+            SyntaxKind::Comment => Some(SyntaxKindType::Follow),
+            SyntaxKind::Bom => Some(SyntaxKindType::Follow),
+            SyntaxKind::Shebang => Some(SyntaxKindType::Follow),
             SyntaxKind::Ascii40 => Some(SyntaxKindType::Follow),
             SyntaxKind::Ascii41 => Some(SyntaxKindType::Follow),
             SyntaxKind::Ascii44 => Some(SyntaxKindType::Follow),
             SyntaxKind::Ascii59 => Some(SyntaxKindType::Close),
-            SyntaxKind::Ascii61 => Some(SyntaxKindType::Follow),
+            SyntaxKind::ComparisonOp => Some(SyntaxKindType::Follow),
             SyntaxKind::From => Some(SyntaxKindType::Follow),
             SyntaxKind::Where => Some(SyntaxKindType::Follow),
+            SyntaxKind::GroupP => Some(SyntaxKindType::Follow),
+            SyntaxKind::Having => Some(SyntaxKindType::Follow),
+            SyntaxKind::Order => Some(SyntaxKindType::Follow),
+            SyntaxKind::By => Some(SyntaxKindType::Follow),
+            SyntaxKind::Limit => Some(SyntaxKindType::Follow),
+            SyntaxKind::Offset => Some(SyntaxKindType::Follow),
+            SyntaxKind::Join => Some(SyntaxKindType::Follow),
+            SyntaxKind::On => Some(SyntaxKindType::Follow),
+            SyntaxKind::LogicalOp => Some(SyntaxKindType::Follow),
             _ => None,
         }
     }
+
+    /// Whether a `SyntaxKind` is trivia, i.e. whitespace, newlines, comments, and a leading byte
+    /// order mark or shebang line
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::Whitespace
+                | SyntaxKind::Newline
+                | SyntaxKind::Tab
+                | SyntaxKind::Comment
+                | SyntaxKind::Bom
+                | SyntaxKind::Shebang
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syntax_kind_name() {
+        assert_eq!(syntax_kind_name(SyntaxKind::SourceFile), "SourceFile");
+        assert_eq!(syntax_kind_name(SyntaxKind::Whitespace), "Whitespace");
+        assert_eq!(syntax_kind_name(SyntaxKind::SelectStmt), "SelectStmt");
+        assert_eq!(syntax_kind_name(SyntaxKind::Ascii59), "Ascii59");
+        assert_eq!(syntax_kind_name(SyntaxKind::Uminus), "Uminus");
+    }
+
+    #[test]
+    fn test_operator_classes() {
+        let input = "select a = 1 + 2 and b ->> 'c' like 'd'";
+        let kinds = pg_query::scan(input)
+            .unwrap()
+            .tokens
+            .iter()
+            .map(|t| {
+                let text = &input[t.start as usize..t.end as usize];
+                (text, SyntaxKind::from_pg_query_token(t, text))
+            })
+            .filter(|(_, kind)| {
+                matches!(
+                    kind,
+                    SyntaxKind::ComparisonOp
+                        | SyntaxKind::ArithmeticOp
+                        | SyntaxKind::LogicalOp
+                        | SyntaxKind::JsonOp
+                        | SyntaxKind::PatternOp
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            vec![
+                ("=", SyntaxKind::ComparisonOp),
+                ("+", SyntaxKind::ArithmeticOp),
+                ("and", SyntaxKind::LogicalOp),
+                ("->>", SyntaxKind::JsonOp),
+                ("like", SyntaxKind::PatternOp),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operators_follow() {
+        let input = "select 1 from t where a < b and c <> 1;";
+        let mut parser = crate::parser::Parser::new();
+        parser.parse_statement(input, None);
+        let parsed = parser.finish();
+        let parents = parsed
+            .cst
+            .descendants_with_tokens()
+            .filter_map(|e| e.into_token())
+            .filter(|t| matches!(t.kind(), SyntaxKind::ComparisonOp | SyntaxKind::LogicalOp))
+            .map(|t| (t.resolved().text(), t.parent().kind()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parents,
+            vec![
+                ("<", SyntaxKind::AExpr),
+                ("and", SyntaxKind::BoolExpr),
+                ("<>", SyntaxKind::AExpr),
+            ]
+        );
+    }
 }
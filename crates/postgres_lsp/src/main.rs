@@ -145,7 +145,7 @@ impl LanguageServer for Backend {
         let semantic_tokens = || -> Option<Vec<SemanticToken>> {
             let mut im_complete_tokens = self.semantic_token_map.get_mut(&uri)?;
             let rope = self.document_map.get(&uri)?;
-            im_complete_tokens.sort_by(|a, b| a.start.cmp(&b.start));
+            im_complete_tokens.sort_by_key(|t| t.start);
             let mut pre_line = 0;
             let mut pre_start = 0;
             let semantic_tokens = im_complete_tokens
@@ -191,7 +191,7 @@ impl LanguageServer for Backend {
 
     async fn semantic_tokens_range(
         &self,
-        params: SemanticTokensRangeParams,
+        _params: SemanticTokensRangeParams,
     ) -> Result<Option<SemanticTokensRangeResult>> {
         println!("semantic_tokens_range");
         return Ok(None);
@@ -257,13 +257,14 @@ impl Backend {
         let semantic_tokens = result
             .cst
             .descendants_with_tokens()
-            .filter_map(|item| match semantic_token_from_syntax_kind(item.kind()) {
-                Some(token_type) => Some(ImCompleteSemanticToken {
-                    start: item.text_range().start().into(),
-                    token_type,
-                    length: item.text_range().len().into(),
-                }),
-                None => None,
+            .filter_map(|item| {
+                semantic_token_from_syntax_kind(item.kind()).map(|token_type| {
+                    ImCompleteSemanticToken {
+                        start: item.text_range().start().into(),
+                        token_type,
+                        length: item.text_range().len().into(),
+                    }
+                })
             })
             .collect::<Vec<_>>();
 
@@ -58,15 +58,14 @@ pub struct ImCompleteSemanticToken {
 
 pub fn semantic_token_from_syntax_kind(syntax: SyntaxKind) -> Option<usize> {
     let token_type = match syntax {
-        SyntaxKind::Ascii37 => Some(SemanticTokenType::OPERATOR),
         SyntaxKind::Ascii42 => Some(SemanticTokenType::OPERATOR),
-        SyntaxKind::Ascii43 => Some(SemanticTokenType::OPERATOR),
         SyntaxKind::Ascii44 => Some(SemanticTokenType::PROPERTY),
-        SyntaxKind::Ascii45 => Some(SemanticTokenType::OPERATOR),
-        SyntaxKind::Ascii47 => Some(SemanticTokenType::OPERATOR),
-        SyntaxKind::Ascii60 => Some(SemanticTokenType::OPERATOR),
-        SyntaxKind::Ascii62 => Some(SemanticTokenType::OPERATOR),
         SyntaxKind::Ascii63 => Some(SemanticTokenType::OPERATOR),
+        SyntaxKind::ComparisonOp => Some(SemanticTokenType::OPERATOR),
+        SyntaxKind::ArithmeticOp => Some(SemanticTokenType::OPERATOR),
+        SyntaxKind::LogicalOp => Some(SemanticTokenType::KEYWORD),
+        SyntaxKind::JsonOp => Some(SemanticTokenType::OPERATOR),
+        SyntaxKind::PatternOp => Some(SemanticTokenType::OPERATOR),
         SyntaxKind::Sconst => Some(SemanticTokenType::STRING),
         SyntaxKind::Comment => Some(SemanticTokenType::COMMENT),
         SyntaxKind::Select => Some(SemanticTokenType::KEYWORD),